@@ -0,0 +1,1196 @@
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::dart;
+use crate::di;
+use crate::form::{label, render_fields, FormFieldSpec, FormParts, InputKind, Validator};
+use crate::l10n;
+use crate::model::{FieldSpec, FieldType, ModelSpec};
+use crate::project::{ProjectConfig, RouteParam, Screen, ScreenKind};
+use crate::router::{backend, write_router};
+use crate::ui_kit::{self, ActionIcon, PageAction};
+use crate::{camel_case, generate_controller_template, pascal_case};
+
+pub fn add_crud(project_dir: &Path, feature_name: &str) -> Result<()> {
+    let mut config = ProjectConfig::load(project_dir)?;
+
+    if !config.use_riverpod {
+        bail!("CRUD screens are driven by Riverpod controllers, but this project does not use Riverpod");
+    }
+    if !config.features.iter().any(|f| f.name == feature_name) {
+        bail!("Unknown feature `{}`", feature_name);
+    }
+    let Some(model) = config.model(feature_name).cloned() else {
+        bail!(
+            "No model spec for `{}`. Models are generated from the Supabase schema when the project is created.",
+            feature_name
+        );
+    };
+
+    // Never replace screens or routes, including ones added under an earlier
+    // CRUD route such as `<feature>_detail`
+    for route in ["list", "new", "detail", "edit"] {
+        let route = format!("{}_{}", feature_name, route);
+        if config.has_route(&route) {
+            bail!("A route named `{}` already exists", route);
+        }
+    }
+    let feature_path = project_dir.join("lib").join("features").join(feature_name);
+    let presentation = feature_path.join("presentation");
+    for screen in ["list", "detail", "form"] {
+        let file = presentation.join(format!("{}_{}_screen.dart", feature_name, screen));
+        if file.exists() {
+            bail!(
+                "{} already exists, remove it to generate the CRUD screens",
+                file.display()
+            );
+        }
+    }
+    // The controller only replaces the stub the feature was created with,
+    // not one edited by hand or extended by `add form`
+    let controller = feature_path
+        .join("logic")
+        .join(format!("{}_controller.dart", feature_name));
+    if let Ok(content) = fs::read_to_string(&controller) {
+        if content != generate_controller_template(feature_name) {
+            bail!(
+                "{} has been changed, remove it to generate the CRUD controller",
+                controller.display()
+            );
+        }
+    }
+    fs::create_dir_all(&presentation)?;
+    fs::create_dir_all(feature_path.join("logic"))?;
+
+    let files = vec![
+        (
+            controller,
+            if config.typed_errors {
+                generate_typed_crud_controller_template(&config, &model)
+            } else {
+                generate_crud_controller_template(&config, &model)
+            },
+        ),
+        (
+            presentation.join(format!("{}_list_screen.dart", feature_name)),
+            generate_list_screen_template(&config, &model),
+        ),
+        (
+            presentation.join(format!("{}_detail_screen.dart", feature_name)),
+            generate_detail_screen_template(&config, &model),
+        ),
+        (
+            presentation.join(format!("{}_form_screen.dart", feature_name)),
+            generate_form_screen_template(&config, &model),
+        ),
+    ];
+
+    for (path, content) in files {
+        fs::write(path, content)?;
+    }
+
+    // Nest the CRUD routes under the feature's own route
+    let key = model.key();
+    let key_param = RouteParam {
+        name: key.dart_name(),
+        dart_type: key.dart_type(),
+        query: false,
+    };
+    let feature = config
+        .feature_mut(feature_name)
+        .expect("feature checked above");
+    feature.root_screen = Some(format!("{}_list", feature_name));
+    feature.screens.push(Screen {
+        name: format!("{}_new", feature_name),
+        widget: format!("{}_form", feature_name),
+        path: "new".to_string(),
+        params: Vec::new(),
+        children: Vec::new(),
+        kind: ScreenKind::Form,
+        fields: Vec::new(),
+        guards: Vec::new(),
+    });
+    feature.screens.push(Screen {
+        name: format!("{}_detail", feature_name),
+        widget: format!("{}_detail", feature_name),
+        path: format!(":{}", key_param.name),
+        params: vec![key_param.clone()],
+        children: vec![Screen {
+            name: format!("{}_edit", feature_name),
+            widget: format!("{}_form", feature_name),
+            path: "edit".to_string(),
+            params: vec![key_param],
+            children: Vec::new(),
+            kind: ScreenKind::Form,
+            fields: Vec::new(),
+            guards: Vec::new(),
+        }],
+        kind: ScreenKind::Stateless,
+        fields: Vec::new(),
+        guards: Vec::new(),
+    });
+
+    write_router(project_dir, &config)?;
+    l10n::write_arb_files(project_dir, &config)?;
+    config.save(project_dir)?;
+
+    Ok(())
+}
+
+fn imports(project_name: &str, feature_name: &str) -> String {
+    format!(
+        "import 'package:{0}/features/{1}/domain/{1}_model.dart';
+import 'package:{0}/features/{1}/logic/{1}_controller.dart';",
+        project_name, feature_name
+    )
+}
+
+// Imports of the router package and routes, one per line after a newline
+fn navigation_imports(config: &ProjectConfig, navigates: bool, pops: bool) -> String {
+    backend(config)
+        .screen_imports(config, navigates, pops)
+        .iter()
+        .map(|import| format!("\n{}", import))
+        .collect()
+}
+
+// Column shown in the list tiles: the first text column that is not a
+// reference, or the key
+fn title_field(model: &ModelSpec) -> &FieldSpec {
+    model
+        .fields
+        .iter()
+        .find(|f| {
+            f.field_type == FieldType::String
+                && !f.is_list
+                && !f.primary_key
+                && !f.column.ends_with("_id")
+        })
+        .unwrap_or(model.key())
+}
+
+// Columns the user edits. Database-managed keys and timestamps, lists and
+// JSON blobs are left out of the form.
+fn form_fields(model: &ModelSpec) -> Vec<&FieldSpec> {
+    model
+        .fields
+        .iter()
+        .filter(|f| !f.is_list && f.field_type != FieldType::Json)
+        .filter(|f| !(f.has_default && (f.primary_key || f.field_type == FieldType::DateTime)))
+        .collect()
+}
+
+// Form inputs matching the editable columns of a model
+pub fn model_form_fields(model: &ModelSpec) -> Vec<FormFieldSpec> {
+    form_fields(model)
+        .into_iter()
+        .map(|f| {
+            let input = match f.field_type {
+                FieldType::Int | FieldType::Double => InputKind::Number,
+                FieldType::Bool => InputKind::Checkbox,
+                FieldType::DateTime => InputKind::Date,
+                _ if f.column.contains("email") => InputKind::Email,
+                _ => InputKind::Text,
+            };
+            // Checkboxes always hold a value, so `required` would force them on
+            let validators = if f.is_optional() || input == InputKind::Checkbox {
+                Vec::new()
+            } else {
+                vec![Validator::Required]
+            };
+            FormFieldSpec {
+                name: f.column.clone(),
+                input,
+                validators,
+            }
+        })
+        .collect()
+}
+
+fn generate_crud_controller_template(config: &ProjectConfig, model: &ModelSpec) -> String {
+    let project_name = &config.name;
+    let feature_name = &model.name;
+    let pascal = pascal_case(feature_name);
+    let camel = camel_case(feature_name);
+    let class_name = model.class_name();
+    let key = model.key();
+    let key_name = key.dart_name();
+    let key_type = key.dart_type();
+    let di_import = di::import(config);
+    let repository = di::resolve(
+        config,
+        &format!("{pascal}Repository"),
+        &format!("{pascal}Repository()"),
+    );
+
+    format!(
+        r#"import 'package:flutter_riverpod/flutter_riverpod.dart';
+{di_import}import 'package:{project_name}/features/{feature_name}/data/{feature_name}_repository.dart';
+import 'package:{project_name}/features/{feature_name}/domain/{feature_name}_model.dart';
+
+final {camel}RepositoryProvider = Provider<{pascal}Repository>((ref) {{
+  return {repository};
+}});
+
+final {camel}Controller = StateNotifierProvider<{pascal}Notifier, {pascal}State>((ref) {{
+  return {pascal}Notifier(ref.watch({camel}RepositoryProvider));
+}});
+
+final {camel}DetailProvider =
+    FutureProvider.autoDispose.family<{class_name}?, {key_type}>((ref, {key_name}) {{
+  return ref.watch({camel}RepositoryProvider).fetchById({key_name});
+}});
+
+class {pascal}State {{
+  const {pascal}State({{
+    this.items = const [],
+    this.isLoading = false,
+    this.hasMore = true,
+    this.error,
+  }});
+
+  final List<{class_name}> items;
+  final bool isLoading;
+  final bool hasMore;
+  final Object? error;
+}}
+
+class {pascal}Notifier extends StateNotifier<{pascal}State> {{
+  {pascal}Notifier(this._repository) : super(const {pascal}State()) {{
+    refresh();
+  }}
+
+  static const pageSize = 20;
+
+  final {pascal}Repository _repository;
+
+  Future<void> refresh() async {{
+    state = {pascal}State(items: state.items, isLoading: true);
+    try {{
+      final items = await _repository.fetchAll(limit: pageSize);
+      state = {pascal}State(items: items, hasMore: items.length == pageSize);
+    }} catch (e) {{
+      state = {pascal}State(items: state.items, error: e);
+    }}
+  }}
+
+  Future<void> loadMore() async {{
+    if (state.isLoading || !state.hasMore) return;
+
+    state = {pascal}State(items: state.items, isLoading: true);
+    try {{
+      final items = await _repository.fetchAll(
+        offset: state.items.length,
+        limit: pageSize,
+      );
+      state = {pascal}State(
+        items: [...state.items, ...items],
+        hasMore: items.length == pageSize,
+      );
+    }} catch (e) {{
+      state = {pascal}State(items: state.items, error: e);
+    }}
+  }}
+
+  Future<{class_name}> create({class_name} model) async {{
+    final created = await _repository.insert(model);
+    state = {pascal}State(
+      items: [created, ...state.items],
+      hasMore: state.hasMore,
+    );
+    return created;
+  }}
+
+  Future<{class_name}> save({class_name} model) async {{
+    final saved = await _repository.update(model);
+    state = {pascal}State(
+      items: [
+        for (final item in state.items)
+          if (item.{key_name} == saved.{key_name}) saved else item,
+      ],
+      hasMore: state.hasMore,
+    );
+    return saved;
+  }}
+
+  Future<void> delete({key_type} {key_name}) async {{
+    await _repository.delete({key_name});
+    state = {pascal}State(
+      items: state.items.where((item) => item.{key_name} != {key_name}).toList(),
+      hasMore: state.hasMore,
+    );
+  }}
+}}
+"#
+    )
+}
+
+// Controller that keeps the failures of the repository's `Result`s
+fn generate_typed_crud_controller_template(config: &ProjectConfig, model: &ModelSpec) -> String {
+    let project_name = &config.name;
+    let feature_name = &model.name;
+    let pascal = pascal_case(feature_name);
+    let camel = camel_case(feature_name);
+    let class_name = model.class_name();
+    let key = model.key();
+    let key_name = key.dart_name();
+    let key_type = key.dart_type();
+    let di_import = di::import(config);
+    let repository = di::resolve(
+        config,
+        &format!("{pascal}Repository"),
+        &format!("{pascal}Repository()"),
+    );
+
+    format!(
+        r#"import 'package:flutter_riverpod/flutter_riverpod.dart';
+{di_import}import 'package:{project_name}/core/errors/failure.dart';
+import 'package:{project_name}/core/errors/result.dart';
+import 'package:{project_name}/features/{feature_name}/data/{feature_name}_repository.dart';
+import 'package:{project_name}/features/{feature_name}/domain/{feature_name}_model.dart';
+
+final {camel}RepositoryProvider = Provider<{pascal}Repository>((ref) {{
+  return {repository};
+}});
+
+final {camel}Controller = StateNotifierProvider<{pascal}Notifier, {pascal}State>((ref) {{
+  return {pascal}Notifier(ref.watch({camel}RepositoryProvider));
+}});
+
+// Fails with the repository's failure, see `FailureView.of`
+final {camel}DetailProvider =
+    FutureProvider.autoDispose.family<{class_name}, {key_type}>((ref, {key_name}) async {{
+  final result = await ref.watch({camel}RepositoryProvider).fetchById({key_name});
+  return result.getOrThrow();
+}});
+
+class {pascal}State {{
+  const {pascal}State({{
+    this.items = const [],
+    this.isLoading = false,
+    this.hasMore = true,
+    this.failure,
+  }});
+
+  final List<{class_name}> items;
+  final bool isLoading;
+  final bool hasMore;
+  final Failure? failure;
+}}
+
+class {pascal}Notifier extends StateNotifier<{pascal}State> {{
+  {pascal}Notifier(this._repository) : super(const {pascal}State()) {{
+    refresh();
+  }}
+
+  static const pageSize = 20;
+
+  final {pascal}Repository _repository;
+
+  Future<void> refresh() async {{
+    state = {pascal}State(items: state.items, isLoading: true);
+    final result = await _repository.fetchAll(limit: pageSize);
+    state = switch (result) {{
+      Ok(value: final items) =>
+        {pascal}State(items: items, hasMore: items.length == pageSize),
+      Err(:final failure) => {pascal}State(items: state.items, failure: failure),
+    }};
+  }}
+
+  Future<void> loadMore() async {{
+    if (state.isLoading || !state.hasMore) return;
+
+    state = {pascal}State(items: state.items, isLoading: true);
+    final result = await _repository.fetchAll(
+      offset: state.items.length,
+      limit: pageSize,
+    );
+    state = switch (result) {{
+      Ok(value: final items) => {pascal}State(
+          items: [...state.items, ...items],
+          hasMore: items.length == pageSize,
+        ),
+      Err(:final failure) => {pascal}State(items: state.items, failure: failure),
+    }};
+  }}
+
+  Future<Result<{class_name}>> create({class_name} model) async {{
+    final result = await _repository.insert(model);
+    if (result case Ok(value: final created)) {{
+      state = {pascal}State(
+        items: [created, ...state.items],
+        hasMore: state.hasMore,
+      );
+    }}
+    return result;
+  }}
+
+  Future<Result<{class_name}>> save({class_name} model) async {{
+    final result = await _repository.update(model);
+    if (result case Ok(value: final saved)) {{
+      state = {pascal}State(
+        items: [
+          for (final item in state.items)
+            if (item.{key_name} == saved.{key_name}) saved else item,
+        ],
+        hasMore: state.hasMore,
+      );
+    }}
+    return result;
+  }}
+
+  Future<Result<void>> delete({key_type} {key_name}) async {{
+    final result = await _repository.delete({key_name});
+    if (result.isOk) {{
+      state = {pascal}State(
+        items: state.items.where((item) => item.{key_name} != {key_name}).toList(),
+        hasMore: state.hasMore,
+      );
+    }}
+    return result;
+  }}
+}}
+"#
+    )
+}
+
+fn generate_list_screen_template(config: &ProjectConfig, model: &ModelSpec) -> String {
+    let feature_name = &model.name;
+    let pascal = pascal_case(feature_name);
+    let camel = camel_case(feature_name);
+    let kit = config.ui_kit;
+    let key = model.key();
+    let key_name = key.dart_name();
+    let title = title_field(model).dart_name();
+    let navigation_imports = navigation_imports(config, true, false);
+    let imports = imports(&config.name, feature_name);
+    let router = backend(config);
+    let go_new = router.go_to(
+        config,
+        &format!("{}_new", feature_name),
+        &format!("{}_form", feature_name),
+        None,
+        0,
+    );
+    // Routes take the key non-null, though it is unset on items not yet saved
+    let key_unwrap = if key.is_optional() { "!" } else { "" };
+    let go_detail = router.go_to(
+        config,
+        &format!("{}_detail", feature_name),
+        &format!("{}_detail", feature_name),
+        Some((&key_name, &format!("item.{}{}", key_name, key_unwrap))),
+        0,
+    );
+    let l10n_import = l10n::import(config);
+    let lookup = l10n::lookup(config);
+    let title_text = l10n::text(config, &l10n::key(feature_name, "list", "title"), &pascal);
+    let (failure_import, failure, failure_widget) = if config.typed_errors {
+        (
+            format!(
+                "\nimport 'package:{}/core/widgets/failure_view.dart';",
+                config.name
+            ),
+            "failure",
+            format!(
+                "FailureView(\n      failure: state.failure!,\n      onRetry: ref.read({}Controller.notifier).refresh,\n    )",
+                camel
+            ),
+        )
+    } else {
+        (
+            String::new(),
+            "error",
+            "Padding(\n      padding: const EdgeInsets.all(16),\n      child: Center(child: Text('${state.error}')),\n    )".to_string(),
+        )
+    };
+
+    let tile = kit.list_tile(
+        &format!("Text('${{item.{}}}')", title),
+        None,
+        Some(&format!("() => {}", go_detail)),
+    );
+    let slivers = format!(
+        r#"if (state.{failure} != null && state.items.isEmpty)
+  SliverToBoxAdapter(
+    child: {failure_widget},
+  )
+else
+  SliverList.builder(
+    itemCount: state.items.length + (state.hasMore ? 1 : 0),
+    itemBuilder: (context, index) {{
+      if (index >= state.items.length) {{
+        return const Padding(
+          padding: EdgeInsets.all(16),
+          child: Center(child: {progress}),
+        );
+      }}
+
+      final {class_var} item = state.items[index];
+      return {tile};
+    }},
+  ),"#,
+        progress = kit.progress_indicator(),
+        class_var = model.class_name(),
+        tile = ui_kit::indent(&tile, 6),
+    );
+    let refresh = format!("ref.read({}Controller.notifier).refresh", camel);
+    let go_new = format!("() => {}", go_new);
+    let mut actions = vec![PageAction {
+        icon: ActionIcon::Add,
+        label: "Add",
+        on_pressed: &go_new,
+        floating: true,
+    }];
+    if !kit.pulls_to_refresh() {
+        actions.push(PageAction {
+            icon: ActionIcon::Refresh,
+            label: "Refresh",
+            on_pressed: &refresh,
+            floating: false,
+        });
+    }
+    let list = kit.refreshable_list(&refresh, "_scrollController", &slivers);
+    let page = kit.page(&title_text, &actions, &ui_kit::indent(&list, 6));
+    let widgets_import = kit.widgets_import();
+    let components_import = kit.components_import();
+
+    format!(
+        r#"{widgets_import}
+import 'package:flutter_riverpod/flutter_riverpod.dart';{navigation_imports}{failure_import}{components_import}
+{imports}{l10n_import}
+
+class {pascal}ListScreen extends ConsumerStatefulWidget {{
+  const {pascal}ListScreen({{super.key}});
+
+  @override
+  ConsumerState<{pascal}ListScreen> createState() => _{pascal}ListScreenState();
+}}
+
+class _{pascal}ListScreenState extends ConsumerState<{pascal}ListScreen> {{
+  final _scrollController = ScrollController();
+
+  @override
+  void initState() {{
+    super.initState();
+    _scrollController.addListener(_onScroll);
+  }}
+
+  @override
+  void dispose() {{
+    _scrollController.dispose();
+    super.dispose();
+  }}
+
+  // Load the next page when the list is close to its end
+  void _onScroll() {{
+    if (_scrollController.position.extentAfter < 200) {{
+      ref.read({camel}Controller.notifier).loadMore();
+    }}
+  }}
+
+  @override
+  Widget build(BuildContext context) {{
+    final state = ref.watch({camel}Controller);{lookup}
+
+    return {page};
+  }}
+}}
+"#
+    )
+}
+
+fn generate_detail_screen_template(config: &ProjectConfig, model: &ModelSpec) -> String {
+    let feature_name = &model.name;
+    let pascal = pascal_case(feature_name);
+    let camel = camel_case(feature_name);
+    let kit = config.ui_kit;
+    let key = model.key();
+    let key_name = key.dart_name();
+    let key_type = key.dart_type();
+    let navigation_imports = navigation_imports(config, true, true);
+    let imports = imports(&config.name, feature_name);
+    let router = backend(config);
+    let go_edit = router.go_to(
+        config,
+        &format!("{}_edit", feature_name),
+        &format!("{}_form", feature_name),
+        Some((&key_name, &key_name)),
+        0,
+    );
+    let pop = router.pop();
+    let camel_detail = format!("{}DetailProvider({})", camel, key_name);
+    let l10n_import = l10n::import(config);
+    let lookup = l10n::lookup(config);
+    let title_text = l10n::text(config, &l10n::key(feature_name, "detail", "title"), &pascal);
+
+    // Typed failures replace the missing-item check and surface failed deletes
+    let (failure_imports, delete, error, data) = if config.typed_errors {
+        (
+            format!(
+                "\nimport 'package:{0}/core/errors/result.dart';\nimport 'package:{0}/core/widgets/failure_view.dart';",
+                config.name
+            ),
+            format!(
+                r#"final result = await ref.read({camel}Controller.notifier).delete({key_name});
+    if (!context.mounted) return;
+    switch (result) {{
+      case Ok():
+        {pop};
+      case Err(:final failure):
+{failure_error}
+    }}"#,
+                failure_error = kit.show_error("failure.message", 8)
+            ),
+            format!(
+                "FailureView.of(\n          error,\n          onRetry: () => ref.invalidate({}),\n        )",
+                camel_detail
+            ),
+            format!("({} item) {{", model.class_name()),
+        )
+    } else {
+        (
+            String::new(),
+            format!(
+                "await ref.read({}Controller.notifier).delete({});\n    if (context.mounted) {};",
+                camel, key_name, pop
+            ),
+            "Center(child: Text('$error'))".to_string(),
+            format!(
+                "({}? item) {{\n          if (item == null) {{\n            return const Center(child: Text('Not found'));\n          }}",
+                model.class_name()
+            ),
+        )
+    };
+
+    let tiles = model
+        .fields
+        .iter()
+        .map(|f| {
+            let value = if f.is_optional() {
+                format!("${{item.{} ?? '-'}}", f.dart_name())
+            } else {
+                format!("${{item.{}}}", f.dart_name())
+            };
+            let tile = kit.list_tile(
+                &format!("const Text({})", dart::string_literal(&label(&f.column))),
+                Some(&format!("Text('{}')", value)),
+                None,
+            );
+            format!("              {},", ui_kit::indent(&tile, 14))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let confirm = ui_kit::indent(&kit.confirm("Delete this item?", "Delete"), 4);
+    let body = format!(
+        r#"item.when(
+        loading: () => const Center(child: {progress}),
+        error: (error, _) => {error},
+        data: {data}
+          return ListView(
+            children: [
+{tiles}
+            ],
+          );
+        }},
+      )"#,
+        progress = kit.progress_indicator()
+    );
+    let go_edit = format!("() => {}", go_edit);
+    let page = kit.page(
+        &title_text,
+        &[
+            PageAction {
+                icon: ActionIcon::Edit,
+                label: "Edit",
+                on_pressed: &go_edit,
+                floating: false,
+            },
+            PageAction {
+                icon: ActionIcon::Delete,
+                label: "Delete",
+                on_pressed: "() => _delete(context, ref)",
+                floating: false,
+            },
+        ],
+        &body,
+    );
+    let widgets_import = kit.widgets_import();
+    let components_import = kit.components_import();
+
+    format!(
+        r#"{widgets_import}
+import 'package:flutter_riverpod/flutter_riverpod.dart';{navigation_imports}{failure_imports}{components_import}
+{imports}{l10n_import}
+
+class {pascal}DetailScreen extends ConsumerWidget {{
+  const {pascal}DetailScreen({{super.key, required this.{key_name}}});
+
+  final {key_type} {key_name};
+
+  Future<void> _delete(BuildContext context, WidgetRef ref) async {{
+    final confirmed = await {confirm};
+    if (confirmed != true) return;
+
+    {delete}
+  }}
+
+  @override
+  Widget build(BuildContext context, WidgetRef ref) {{
+    final item = ref.watch({camel_detail});{lookup}
+
+    return {page};
+  }}
+}}
+"#
+    )
+}
+
+fn generate_form_screen_template(config: &ProjectConfig, model: &ModelSpec) -> String {
+    let feature_name = &model.name;
+    let pascal = pascal_case(feature_name);
+    let camel = camel_case(feature_name);
+    let class_name = model.class_name();
+    let key = model.key();
+    let key_name = key.dart_name();
+    let key_type = key.dart_type();
+    let navigation_imports = navigation_imports(config, false, true);
+    let imports = imports(&config.name, feature_name);
+    let pop = backend(config).pop();
+    let fields = form_fields(model);
+    let kit = config.ui_kit;
+    let FormParts {
+        declarations,
+        disposals,
+        widgets,
+    } = render_fields(config, &model_form_fields(model), (feature_name, "form"));
+
+    let mut loads = Vec::new();
+    let mut values = Vec::new();
+
+    for field in &fields {
+        let name = field.dart_name();
+        let required = !field.is_optional();
+
+        loads.push(match (field.field_type, required) {
+            (FieldType::Bool, false) => format!("      _{0} = item.{0} ?? false;", name),
+            (FieldType::Bool | FieldType::DateTime, _) => format!("      _{0} = item.{0};", name),
+            (FieldType::Int | FieldType::Double, true) => {
+                format!("      _{0}Controller.text = '${{item.{0}}}';", name)
+            }
+            (FieldType::Int | FieldType::Double, false) => {
+                format!(
+                    "      _{0}Controller.text = item.{0}?.toString() ?? '';",
+                    name
+                )
+            }
+            (_, true) => format!("      _{0}Controller.text = item.{0};", name),
+            (_, false) => format!("      _{0}Controller.text = item.{0} ?? '';", name),
+        });
+
+        values.push(match (field.field_type, required) {
+            (FieldType::Bool, _) | (FieldType::DateTime, false) => format!("{0}: _{0}", name),
+            (FieldType::DateTime, true) => format!("{0}: _{0}!", name),
+            (FieldType::Int | FieldType::Double, true) => format!(
+                "{0}: {1}.parse(_{0}Controller.text)",
+                name,
+                field.field_type.dart_type()
+            ),
+            (FieldType::Int | FieldType::Double, false) => format!(
+                "{0}: {1}.tryParse(_{0}Controller.text)",
+                name,
+                field.field_type.dart_type()
+            ),
+            (_, true) => format!("{0}: _{0}Controller.text", name),
+            (_, false) => format!(
+                "{0}: _{0}Controller.text.isEmpty ? null : _{0}Controller.text",
+                name
+            ),
+        });
+    }
+
+    // Required columns that have no input still need a value on insert
+    let mut create_values = values.clone();
+    for field in &model.fields {
+        if !field.is_optional() && !fields.iter().any(|f| f.column == field.column) {
+            let value = if field.is_list {
+                "const []"
+            } else {
+                "const {}"
+            };
+            create_values.push(format!("{}: {}", field.dart_name(), value));
+        }
+    }
+
+    // Typed failures come back as a `Result` instead of being caught
+    let indent = if config.typed_errors { 10 } else { 12 };
+    let create_values = create_values
+        .iter()
+        .map(|v| format!("{}{},", " ".repeat(indent), v))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let update_values = values
+        .iter()
+        .map(|v| format!("{}{},", " ".repeat(indent), v))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let loads = loads.join("\n");
+    let key_unwrap = if key.is_optional() { "!" } else { "" };
+
+    let failure_error = kit.show_error("failure.message", 8);
+    let loads = loads
+        .lines()
+        .map(|l| format!("  {}", l))
+        .collect::<Vec<_>>()
+        .join("\n");
+    // An item that failed to load is shown in place of the form
+    let (result_import, load_state, load, load_error, load_view, retry, submit) = if config
+        .typed_errors
+    {
+        let loads = loads
+            .lines()
+            .map(|l| format!("  {}", l))
+            .collect::<Vec<_>>()
+            .join("\n");
+        (
+            format!(
+                "\nimport 'package:{0}/core/errors/failure.dart';\nimport 'package:{0}/core/errors/result.dart';\nimport 'package:{0}/core/widgets/failure_view.dart';",
+                config.name
+            ),
+            "Failure? _loadFailure;",
+            format!(
+                r#"final result = await ref.read({camel}RepositoryProvider).fetchById({key_name});
+    if (!mounted) return;
+
+    switch (result) {{
+      case Ok(value: final item):
+        setState(() {{
+          _existing = item;
+{loads}
+        }});
+      case Err(:final failure):
+        setState(() => _loadFailure = failure);
+    }}"#
+            ),
+            "loadFailure",
+            "FailureView(failure: loadFailure, onRetry: _retryLoad)",
+            format!(
+                r#"
+
+  void _retryLoad() {{
+    setState(() => _loadFailure = null);
+    _load(widget.{key_name}!);
+  }}"#
+            ),
+            format!(
+                r#"final existing = _existing;
+    final Result<{class_name}> result;
+    if (existing == null) {{
+      result = await controller.create(
+        {class_name}(
+{create_values}
+        ),
+      );
+    }} else {{
+      result = await controller.save(
+        existing.copyWith(
+{update_values}
+        ),
+      );
+    }}
+    if (!mounted) return;
+
+    setState(() => _isSaving = false);
+    switch (result) {{
+      case Ok():
+        if (existing != null) {{
+          ref.invalidate({camel}DetailProvider(existing.{key_name}{key_unwrap}));
+        }}
+        {pop};
+      case Err(:final failure):
+{failure_error}
+    }}"#
+            ),
+        )
+    } else {
+        let show_error = kit.show_error("'$e'", 8);
+        (
+            String::new(),
+            "String? _loadError;",
+            format!(
+                r#"try {{
+      final item = await ref.read({camel}DetailProvider({key_name}).future);
+      if (!mounted) return;
+      if (item == null) {{
+        setState(() => _loadError = 'Not found');
+        return;
+      }}
+
+      setState(() {{
+        _existing = item;
+{loads}
+      }});
+    }} catch (e) {{
+      if (mounted) setState(() => _loadError = '$e');
+    }}"#
+            ),
+            "loadError",
+            "Center(child: Text(loadError))",
+            String::new(),
+            format!(
+                r#"try {{
+      final existing = _existing;
+      if (existing == null) {{
+        await controller.create(
+          {class_name}(
+{create_values}
+          ),
+        );
+      }} else {{
+        await controller.save(
+          existing.copyWith(
+{update_values}
+          ),
+        );
+        ref.invalidate({camel}DetailProvider(existing.{key_name}{key_unwrap}));
+      }}
+      if (mounted) {pop};
+    }} catch (e) {{
+      if (mounted) {{
+{show_error}
+      }}
+    }} finally {{
+      if (mounted) setState(() => _isSaving = false);
+    }}"#
+            ),
+        )
+    };
+
+    let widgets_import = kit.widgets_import();
+    let components_import = kit.components_import();
+    let form_state = kit.form_state();
+    let validate = kit.validate();
+    let loading_page = kit.loading_page();
+    let l10n_import = l10n::import(config);
+    let lookup = l10n::lookup(config);
+    let title = format!(
+        "Text(widget.{} == null ? {} : {})",
+        key_name,
+        l10n::message(
+            config,
+            &l10n::key(feature_name, "form", "new_title"),
+            &format!("New {}", pascal)
+        ),
+        l10n::message(
+            config,
+            &l10n::key(feature_name, "form", "edit_title"),
+            &format!("Edit {}", pascal)
+        )
+    );
+    let load_view = ui_kit::indent(&kit.scaffold(&title, load_view), 2);
+    let scaffold = kit.scaffold(
+        &title,
+        &format!(
+            r#"{}(
+        key: _formKey,
+        child: ListView(
+          padding: const EdgeInsets.all(16),
+          children: [
+{widgets}
+            const SizedBox(height: 24),
+            {}(
+              onPressed: _isSaving ? null : _submit,
+              child: {},
+            ),
+          ],
+        ),
+      )"#,
+            kit.form(),
+            kit.button(),
+            l10n::text(config, "save", "Save")
+        ),
+    );
+
+    format!(
+        r#"{widgets_import}
+import 'package:flutter_riverpod/flutter_riverpod.dart';{navigation_imports}{result_import}{components_import}
+{imports}{l10n_import}
+
+class {pascal}FormScreen extends ConsumerStatefulWidget {{
+  const {pascal}FormScreen({{super.key, this.{key_name}}});
+
+  /// Set when editing an existing item
+  final {key_type}? {key_name};
+
+  @override
+  ConsumerState<{pascal}FormScreen> createState() => _{pascal}FormScreenState();
+}}
+
+class _{pascal}FormScreenState extends ConsumerState<{pascal}FormScreen> {{
+  final _formKey = GlobalKey<{form_state}>();
+{declarations}
+  {class_name}? _existing;
+  {load_state}
+  bool _isSaving = false;
+
+  @override
+  void initState() {{
+    super.initState();
+    final {key_name} = widget.{key_name};
+    if ({key_name} != null) _load({key_name});
+  }}
+
+  @override
+  void dispose() {{
+{disposals}
+    super.dispose();
+  }}
+
+  Future<void> _load({key_type} {key_name}) async {{
+    {load}
+  }}{retry}
+
+  Future<void> _submit() async {{
+    if (!_formKey.currentState!.{validate}) return;
+
+    setState(() => _isSaving = true);
+    final controller = ref.read({camel}Controller.notifier);
+    {submit}
+  }}
+
+  @override
+  Widget build(BuildContext context) {{
+    final {load_error} = _{load_error};
+    // Form fields read their initial values once, so wait for the item
+    if (widget.{key_name} != null && _existing == null && {load_error} == null) {{
+      return {loading_page};
+    }}
+{lookup}
+    if ({load_error} != null) {{
+      return {load_view};
+    }}
+    return {scaffold};
+  }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_kit::UiKit;
+
+    fn field(column: &str, field_type: FieldType) -> FieldSpec {
+        FieldSpec {
+            column: column.to_string(),
+            field_type,
+            is_list: false,
+            nullable: false,
+            primary_key: false,
+            has_default: false,
+        }
+    }
+
+    #[test]
+    fn form_fields_follow_the_editable_columns() {
+        let mut id = field("id", FieldType::Int);
+        id.primary_key = true;
+        id.has_default = true;
+        let mut created_at = field("created_at", FieldType::DateTime);
+        created_at.has_default = true;
+        let mut tags = field("tags", FieldType::String);
+        tags.is_list = true;
+        let model = ModelSpec {
+            name: "todo".to_string(),
+            table: Some("todos".to_string()),
+            fields: vec![
+                id,
+                field("owner_id", FieldType::String),
+                field("title", FieldType::String),
+                field("new", FieldType::Bool),
+                field("contact_email", FieldType::String),
+                created_at,
+                tags,
+                field("meta", FieldType::Json),
+            ],
+        };
+
+        assert_eq!(title_field(&model).column, "title");
+        let fields = model_form_fields(&model);
+        let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["owner_id", "title", "new", "contact_email"]);
+        assert_eq!(fields[1].validators, [Validator::Required]);
+        assert_eq!(fields[2].input, InputKind::Checkbox);
+        assert!(fields[2].validators.is_empty());
+        assert_eq!(fields[2].dart_name(), model.fields[3].dart_name());
+        assert_eq!(fields[3].input, InputKind::Email);
+    }
+
+    #[test]
+    fn screens_use_the_ui_kit() {
+        let mut id = field("id", FieldType::Int);
+        id.primary_key = true;
+        let model = ModelSpec {
+            name: "todo".to_string(),
+            table: None,
+            fields: vec![id, field("title", FieldType::String)],
+        };
+        for kit in UiKit::ALL {
+            for typed_errors in [false, true] {
+                let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+                    "name": "app",
+                    "package": "com.example",
+                    "use_riverpod": true,
+                    "use_supabase": false,
+                    "ui_kit": kit,
+                    "typed_errors": typed_errors,
+                    "features": [{ "name": "todo", "layers": [] }],
+                }))
+                .unwrap();
+                let list = generate_list_screen_template(&config, &model);
+                let detail = generate_detail_screen_template(&config, &model);
+                for screen in [&list, &detail] {
+                    assert!(screen.starts_with(kit.widgets_import()));
+                    if matches!(kit, UiKit::Cupertino | UiKit::Fluent) {
+                        for material in [" Scaffold(", "AppBar(", "(Icons.", "SnackBar", "Circular"]
+                        {
+                            assert!(!screen.contains(material), "{} uses {}", kit, material);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn detail_labels_are_escaped() {
+        let mut id = field("id", FieldType::Int);
+        id.primary_key = true;
+        let model = ModelSpec {
+            name: "todo".to_string(),
+            table: None,
+            fields: vec![
+                id,
+                field("o'clock", FieldType::String),
+                field("$cost", FieldType::Int),
+            ],
+        };
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "name": "app",
+            "package": "com.example",
+            "use_riverpod": true,
+            "use_supabase": false,
+            "features": [{ "name": "todo", "layers": [] }],
+        }))
+        .unwrap();
+        let detail = generate_detail_screen_template(&config, &model);
+        assert!(
+            detail.contains(r"title: const Text('O\'clock'),"),
+            "{}",
+            detail
+        );
+        assert!(detail.contains(r"title: const Text('\$cost'),"));
+    }
+}
@@ -0,0 +1,80 @@
+// Dart source fragments shared by the generators
+
+/// Reserved words, which cannot name a variable, field or parameter
+pub const KEYWORDS: &[&str] = &[
+    "assert", "await", "break", "case", "catch", "class", "const", "continue", "default", "do",
+    "else", "enum", "extends", "false", "final", "finally", "for", "if", "in", "is", "new", "null",
+    "rethrow", "return", "super", "switch", "this", "throw", "true", "try", "var", "void", "while",
+    "with", "yield",
+];
+
+/// Whether `name` can be used as a Dart variable or member name as it is
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' || first == '$' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$') && !KEYWORDS.contains(&name)
+}
+
+/// Member name for a name taken from outside the app, such as a database
+/// column: reserved words get a `Value` suffix, e.g. `new` to `newValue`, and
+/// a leading digit a `field` prefix
+pub fn member_name(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("{}Value", name)
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Single-quoted Dart string literal of `s`, escaping quotes, backslashes,
+/// interpolation and line breaks
+pub fn string_literal(s: &str) -> String {
+    let mut literal = String::with_capacity(s.len() + 2);
+    literal.push('\'');
+    for c in s.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '\'' => literal.push_str("\\'"),
+            '$' => literal.push_str("\\$"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn member_name_avoids_reserved_words() {
+        assert_eq!(member_name("title"), "title");
+        assert_eq!(member_name("new"), "newValue");
+        assert_eq!(member_name("class"), "classValue");
+        assert_eq!(member_name("2fa"), "field2fa");
+        assert!(is_identifier(&member_name("in")));
+        assert!(!is_identifier("is"));
+        assert!(!is_identifier("1st"));
+        assert!(!is_identifier("a-b"));
+        assert!(!is_identifier(""));
+        assert!(is_identifier("dueAt"));
+    }
+
+    #[test]
+    fn string_literal_escapes_quotes_and_interpolation() {
+        assert_eq!(string_literal("plain"), "'plain'");
+        assert_eq!(string_literal("it's"), r"'it\'s'");
+        assert_eq!(string_literal("Costs $5"), r"'Costs \$5'");
+        assert_eq!(string_literal(r"a\b"), r"'a\\b'");
+        assert_eq!(string_literal("a\nb"), r"'a\nb'");
+    }
+}
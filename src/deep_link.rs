@@ -0,0 +1,433 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+use crate::native::{
+    self, AndroidManifest, ManifestParent, Plist, PlistValue, XcodeProject, EMPTY_PLIST,
+};
+use crate::project::ProjectConfig;
+use crate::router::write_router;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepLinks {
+    /// Domain the app links are served from, e.g. `example.com`
+    pub host: String,
+    /// Custom URL scheme, e.g. `myapp` for `myapp://todos/1`
+    #[serde(default)]
+    pub scheme: Option<String>,
+}
+
+// The host and scheme end up in the manifest, the entitlements and the
+// well-known files, which take a bare domain and a URI scheme
+pub fn validate_deep_links(links: &DeepLinks) -> Result<()> {
+    let valid_host = !links.host.is_empty()
+        && links.host.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if !valid_host {
+        bail!(
+            "Invalid host `{}`, use a bare domain like `example.com` without scheme, path or port",
+            links.host
+        );
+    }
+    if let Some(scheme) = &links.scheme {
+        let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_lowercase())
+            && scheme.chars().all(|c| {
+                c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '+' | '.' | '-')
+            });
+        if !valid_scheme {
+            bail!(
+                "Invalid URL scheme `{}`, use lowercase letters, digits, `+`, `.` and `-`",
+                scheme
+            );
+        }
+    }
+    Ok(())
+}
+
+// `:param` segments match any value
+fn path_pattern(path: &str, wildcard: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if segment.starts_with(':') {
+                wildcard
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn android_intent_filters(links: &DeepLinks, paths: &[String]) -> String {
+    let data = paths
+        .iter()
+        .map(|path| {
+            if path.contains(':') {
+                format!(
+                    "    <data android:pathPattern=\"{}\" />",
+                    native::xml_escape(&path_pattern(path, ".*"))
+                )
+            } else {
+                format!("    <data android:path=\"{}\" />", native::xml_escape(path))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut filters = format!(
+        r#"<meta-data android:name="flutter_deeplinking_enabled" android:value="true" />
+<intent-filter android:autoVerify="true">
+    <action android:name="android.intent.action.VIEW" />
+    <category android:name="android.intent.category.DEFAULT" />
+    <category android:name="android.intent.category.BROWSABLE" />
+    <data android:scheme="https" android:host="{}" />
+{}
+</intent-filter>
+"#,
+        native::xml_escape(&links.host),
+        data
+    );
+    if let Some(scheme) = &links.scheme {
+        filters.push_str(&format!(
+            r#"<intent-filter>
+    <action android:name="android.intent.action.VIEW" />
+    <category android:name="android.intent.category.DEFAULT" />
+    <category android:name="android.intent.category.BROWSABLE" />
+    <data android:scheme="{}" />
+</intent-filter>
+"#,
+            native::xml_escape(scheme)
+        ));
+    }
+    filters
+}
+
+// Info.plist URL type registering the custom scheme
+fn url_type(scheme: &str) -> PlistValue {
+    PlistValue::Dict(vec![(
+        "CFBundleURLSchemes".to_string(),
+        PlistValue::Array(vec![PlistValue::String(scheme.to_string())]),
+    )])
+}
+
+fn associated_domain(host: &str) -> PlistValue {
+    PlistValue::String(format!("applinks:{}", host))
+}
+
+// Drops the entries of a previous host and scheme the new links no longer use
+fn remove_ios_config(project_dir: &Path, previous: &DeepLinks, links: &DeepLinks) -> Result<()> {
+    if !native::has_ios(project_dir) {
+        return Ok(());
+    }
+
+    if let Some(scheme) = &previous.scheme {
+        if links.scheme.as_ref() != Some(scheme) {
+            native::edit(&native::info_plist(project_dir), |plist: &mut Plist| {
+                plist.remove_from_array("CFBundleURLTypes", &url_type(scheme))
+            })?;
+        }
+    }
+    if previous.host != links.host {
+        native::edit(&native::entitlements(project_dir), |plist: &mut Plist| {
+            plist.remove_from_array(
+                "com.apple.developer.associated-domains",
+                &associated_domain(&previous.host),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+fn write_ios_config(project_dir: &Path, links: &DeepLinks) -> Result<()> {
+    if !native::has_ios(project_dir) {
+        return Ok(());
+    }
+
+    native::edit(&native::info_plist(project_dir), |plist: &mut Plist| {
+        plist.set("FlutterDeepLinkingEnabled", PlistValue::Bool(true))?;
+        match &links.scheme {
+            Some(scheme) => plist.add_to_array("CFBundleURLTypes", url_type(scheme)),
+            None => Ok(()),
+        }
+    })?;
+
+    // Associated domains need an entitlements file the Runner target signs with
+    let entitlements = native::entitlements(project_dir);
+    if !entitlements.exists() {
+        fs::write(&entitlements, EMPTY_PLIST)?;
+    }
+    native::edit(&entitlements, |plist: &mut Plist| {
+        plist.add_to_array(
+            "com.apple.developer.associated-domains",
+            associated_domain(&links.host),
+        )
+    })?;
+
+    native::edit(
+        &native::xcode_project(project_dir),
+        |project: &mut XcodeProject| {
+            project.set_build_setting(
+                "Runner",
+                "CODE_SIGN_ENTITLEMENTS",
+                "Runner/Runner.entitlements",
+            )
+        },
+    )?;
+
+    Ok(())
+}
+
+// Files served from `https://<host>/.well-known/` so the platforms verify
+// the app links
+fn write_well_known(project_dir: &Path, config: &ProjectConfig, paths: &[String]) -> Result<()> {
+    let dir = project_dir.join("deep_links").join(".well-known");
+    fs::create_dir_all(&dir)?;
+
+    let assetlinks = json!([{
+        "relation": ["delegate_permission/common.handle_all_urls"],
+        "target": {
+            "namespace": "android_app",
+            "package_name": config.android_package(),
+            "sha256_cert_fingerprints": ["TODO: keytool -list -v -keystore <keystore>"],
+        },
+    }]);
+    fs::write(
+        dir.join("assetlinks.json"),
+        serde_json::to_string_pretty(&assetlinks)? + "\n",
+    )?;
+
+    let components = paths
+        .iter()
+        .map(|path| json!({ "/": path_pattern(path, "*") }))
+        .collect::<Vec<_>>();
+    let association = json!({
+        "applinks": {
+            "details": [{
+                "appIDs": [format!("TEAM_ID.{}", config.ios_bundle_id())],
+                "components": components,
+            }],
+        },
+    });
+    fs::write(
+        dir.join("apple-app-site-association"),
+        serde_json::to_string_pretty(&association)? + "\n",
+    )?;
+
+    Ok(())
+}
+
+// Keeps the native link configuration in sync with the routes, platforms
+// that were not created are skipped
+pub fn write_deep_links(
+    project_dir: &Path,
+    config: &ProjectConfig,
+    links: &DeepLinks,
+    paths: &[String],
+) -> Result<()> {
+    native::edit(
+        &native::android_manifest(project_dir),
+        |manifest: &mut AndroidManifest| {
+            manifest.set_block(
+                "deep_links",
+                ManifestParent::MainActivity,
+                &android_intent_filters(links, paths),
+            )
+        },
+    )?;
+
+    write_ios_config(project_dir, links)?;
+    write_well_known(project_dir, config, paths)
+}
+
+pub fn add_deep_links(project_dir: &Path, host: &str, scheme: Option<&str>) -> Result<()> {
+    let mut config = ProjectConfig::load(project_dir)?;
+
+    let links = DeepLinks {
+        host: host.to_string(),
+        scheme: scheme.map(str::to_string),
+    };
+    validate_deep_links(&links)?;
+    // The config remembers which host and scheme the native files list
+    if let Some(previous) = &config.deep_links {
+        remove_ios_config(project_dir, previous, &links)?;
+    }
+    config.deep_links = Some(links);
+
+    write_router(project_dir, &config)?;
+    config.save(project_dir)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(host: &str, scheme: Option<&str>) -> DeepLinks {
+        DeepLinks {
+            host: host.to_string(),
+            scheme: scheme.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn validates_host_and_scheme() {
+        assert!(validate_deep_links(&links("example.com", Some("myapp"))).is_ok());
+        assert!(validate_deep_links(&links("app.my-site.co.uk", Some("com.example+app"))).is_ok());
+        for host in [
+            "",
+            "https://example.com",
+            "example.com/app",
+            "example.com:8080",
+            "example..com",
+            "-example.com",
+            "example.com\"",
+        ] {
+            assert!(validate_deep_links(&links(host, None)).is_err(), "{}", host);
+        }
+        for scheme in ["", "MyApp", "1app", "my_app", "my app", "app://"] {
+            assert!(
+                validate_deep_links(&links("example.com", Some(scheme))).is_err(),
+                "{}",
+                scheme
+            );
+        }
+    }
+
+    #[test]
+    fn path_parameters_become_wildcards() {
+        assert_eq!(path_pattern("/", ".*"), "/");
+        assert_eq!(path_pattern("/todos/:id", ".*"), "/todos/.*");
+        assert_eq!(
+            path_pattern("/users/:user_id/posts/:post_id", "*"),
+            "/users/*/posts/*"
+        );
+    }
+
+    #[test]
+    fn intent_filters_list_the_paths() {
+        let paths = ["/".to_string(), "/todos/:id".to_string()];
+        let filters = android_intent_filters(&links("example.com", None), &paths);
+        assert!(filters.contains(r#"<intent-filter android:autoVerify="true">"#));
+        assert!(filters.contains(r#"<data android:scheme="https" android:host="example.com" />"#));
+        assert!(filters.contains(r#"<data android:path="/" />"#));
+        assert!(filters.contains(r#"<data android:pathPattern="/todos/.*" />"#));
+        assert_eq!(filters.matches("<intent-filter").count(), 1);
+
+        let filters = android_intent_filters(&links("example.com", Some("myapp")), &paths);
+        assert_eq!(filters.matches("<intent-filter").count(), 2);
+        assert!(filters.contains("<intent-filter>\n"));
+        assert!(filters.contains(r#"<data android:scheme="myapp" />"#));
+    }
+
+    const MANIFEST: &str = r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android">
+    <application android:label="todo">
+        <activity
+            android:name=".MainActivity"
+            android:exported="true">
+        </activity>
+    </application>
+</manifest>
+"#;
+
+    const PROJECT: &str = r#"// !$*UTF8*$!
+{
+	objects = {
+
+/* Begin XCBuildConfiguration section */
+		97C147061CF9000F007C117D /* Debug */ = {
+			isa = XCBuildConfiguration;
+			buildSettings = {
+				INFOPLIST_FILE = Runner/Info.plist;
+			};
+			name = Debug;
+		};
+/* End XCBuildConfiguration section */
+
+/* Begin XCConfigurationList section */
+		97C147051CF9000F007C117D /* Build configuration list for PBXNativeTarget "Runner" */ = {
+			isa = XCConfigurationList;
+			buildConfigurations = (
+				97C147061CF9000F007C117D /* Debug */,
+			);
+			defaultConfigurationIsVisible = 0;
+		};
+/* End XCConfigurationList section */
+	};
+}
+"#;
+
+    #[test]
+    fn rerunning_keeps_the_native_files() {
+        let dir =
+            std::env::temp_dir().join(format!("flutter_gen_deep_links_{}", std::process::id()));
+        let manifest = native::android_manifest(&dir);
+        let info_plist = native::info_plist(&dir);
+        let project = native::xcode_project(&dir);
+        fs::create_dir_all(manifest.parent().unwrap()).unwrap();
+        fs::create_dir_all(info_plist.parent().unwrap()).unwrap();
+        fs::create_dir_all(project.parent().unwrap()).unwrap();
+        fs::write(&manifest, MANIFEST).unwrap();
+        fs::write(&info_plist, EMPTY_PLIST).unwrap();
+        fs::write(&project, PROJECT).unwrap();
+
+        let config: ProjectConfig = serde_json::from_value(json!({
+            "name": "todo",
+            "package": "com.example",
+            "use_riverpod": false,
+            "use_supabase": false,
+            "features": [{ "name": "home", "layers": [] }],
+        }))
+        .unwrap();
+        let paths = ["/".to_string(), "/todos/:id".to_string()];
+        let files = [
+            manifest.clone(),
+            info_plist.clone(),
+            project.clone(),
+            native::entitlements(&dir),
+            dir.join("deep_links")
+                .join(".well-known")
+                .join("apple-app-site-association"),
+        ];
+        let read = || {
+            files
+                .iter()
+                .map(|file| fs::read_to_string(file).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let first = links("example.com", Some("myapp"));
+        write_deep_links(&dir, &config, &first, &paths).unwrap();
+        let once = read();
+        write_deep_links(&dir, &config, &first, &paths).unwrap();
+        assert_eq!(read(), once);
+
+        assert_eq!(once[0].matches("android:host=\"example.com\"").count(), 1);
+        assert_eq!(once[1].matches("<string>myapp</string>").count(), 1);
+        assert!(once[2].contains(
+            "CODE_SIGN_ENTITLEMENTS = Runner/Runner.entitlements;\n\t\t\t\tINFOPLIST_FILE"
+        ));
+        assert!(once[3].contains("<string>applinks:example.com</string>"));
+        assert!(once[4].contains("\"/\": \"/todos/*\""));
+
+        // A new host and scheme replace the previous ones
+        let second = links("app.example.com", None);
+        remove_ios_config(&dir, &first, &second).unwrap();
+        write_deep_links(&dir, &config, &second, &paths).unwrap();
+        let twice = read();
+        assert!(!twice[0].contains("android:host=\"example.com\""));
+        assert!(!twice[0].contains("android:scheme=\"myapp\""));
+        assert!(!twice[1].contains("myapp"));
+        assert_eq!(twice[2], once[2]);
+        assert!(!twice[3].contains("applinks:example.com"));
+        assert!(twice[3].contains("applinks:app.example.com"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
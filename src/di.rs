@@ -0,0 +1,178 @@
+use crate::package::OptionalPackage;
+use crate::pascal_case;
+use crate::project::ProjectConfig;
+
+// Import of `getIt` for files that resolve their dependencies from it
+pub fn import(config: &ProjectConfig) -> String {
+    if config.get_it {
+        format!("import 'package:{}/core/di/injection.dart';\n", config.name)
+    } else {
+        String::new()
+    }
+}
+
+// Expression providing a `class`, taken from get_it when the project
+// registers its dependencies there
+pub fn resolve(config: &ProjectConfig, class: &str, construct: &str) -> String {
+    if config.get_it {
+        format!("getIt<{}>()", class)
+    } else {
+        construct.to_string()
+    }
+}
+
+// Registrations on `getIt`, written as a cascade once there are several
+fn cascade(registrations: &[String]) -> String {
+    match registrations {
+        [registration] => format!("  getIt.{};", registration.replace("\n  ", "\n")),
+        _ => format!(
+            "  getIt\n{};",
+            registrations
+                .iter()
+                .map(|registration| format!("    ..{}", registration))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
+
+pub fn generate_injection_template(config: &ProjectConfig) -> String {
+    let project_name = &config.name;
+    let mut imports = vec!["import 'package:get_it/get_it.dart';".to_string()];
+    let mut registrations = Vec::new();
+
+    if config.use_supabase {
+        imports.push("import 'package:supabase_flutter/supabase_flutter.dart';".to_string());
+        imports.push(format!(
+            "import 'package:{}/core/services/auth_service.dart';",
+            project_name
+        ));
+        registrations.push(
+            "registerLazySingleton<AuthService>(\n      () => AuthService(Supabase.instance.client),\n    )"
+                .to_string(),
+        );
+    }
+    if config.uses_package(OptionalPackage::Dio) {
+        imports.push(format!(
+            "import 'package:{}/core/network/api_client.dart';",
+            project_name
+        ));
+        let token = if config.use_supabase {
+            "token: () async => getIt<AuthService>().accessToken"
+        } else {
+            "// TODO: Read the token from your auth layer\n        token: () async => null"
+        };
+        registrations.push(format!(
+            "registerLazySingleton<ApiClient>(\n      () => ApiClient(\n        {},\n      ),\n    )",
+            token
+        ));
+    }
+    if config.uses_package(OptionalPackage::ConnectivityPlus) {
+        imports.push(format!(
+            "import 'package:{}/core/services/connectivity_service.dart';",
+            project_name
+        ));
+        registrations.push(
+            "registerLazySingleton<ConnectivityService>(ConnectivityService.new)".to_string(),
+        );
+    }
+    if config.uses_package(OptionalPackage::Sqflite) {
+        imports.push(format!(
+            "import 'package:{}/core/services/database_service.dart';",
+            project_name
+        ));
+        registrations.push("registerSingleton(DatabaseService.instance)".to_string());
+    }
+    if config.uses_package(OptionalPackage::FirebaseAnalytics) {
+        imports.push(format!(
+            "import 'package:{}/core/services/analytics_service.dart';",
+            project_name
+        ));
+        registrations.push("registerSingleton(AnalyticsService.instance)".to_string());
+    }
+
+    let mut body = Vec::new();
+    if !registrations.is_empty() {
+        body.push(cascade(&registrations));
+    }
+    let mut features = Vec::new();
+    for feature in &config.features {
+        imports.push(format!(
+            "import 'package:{0}/features/{1}/{1}_injection.dart';",
+            project_name, feature.name
+        ));
+        features.push(format!("  register{}(getIt);", pascal_case(&feature.name)));
+    }
+    if !features.is_empty() {
+        body.push(features.join("\n"));
+    }
+
+    format!(
+        r#"{}
+
+final getIt = GetIt.instance;
+
+/// Registers the services, data sources and repositories, call before `runApp`.
+void configureDependencies() {{
+{}
+}}
+"#,
+        imports.join("\n"),
+        body.join("\n\n")
+    )
+}
+
+// `register<Feature>`, adding the data layer of a feature to get_it
+pub fn generate_feature_injection_template(config: &ProjectConfig, feature_name: &str) -> String {
+    let project_name = &config.name;
+    let pascal = pascal_case(feature_name);
+    let data = |file: &str| {
+        format!(
+            "import 'package:{0}/features/{1}/data/{1}_{2}.dart';",
+            project_name, feature_name, file
+        )
+    };
+
+    let mut imports = vec!["import 'package:get_it/get_it.dart';".to_string()];
+    let mut registrations = Vec::new();
+    let model = config.model(feature_name);
+    if model.is_some() && config.local_database.is_some() {
+        imports.push(data("local_data_source"));
+        imports.push(data("remote_data_source"));
+        registrations.push(format!(
+            "registerLazySingleton<{0}RemoteDataSource>({0}RemoteDataSource.new)",
+            pascal
+        ));
+        registrations.push(format!(
+            "registerLazySingleton<{0}LocalDataSource>({0}LocalDataSource.new)",
+            pascal
+        ));
+        registrations.push(format!(
+            "registerLazySingleton<{0}Repository>(\n      () => {0}Repository(remote: getIt(), local: getIt()),\n    )",
+            pascal
+        ));
+    } else if model.is_none() && config.uses_package(OptionalPackage::Dio) {
+        registrations.push(format!(
+            "registerLazySingleton<{0}Repository>(() => {0}Repository(getIt()))",
+            pascal
+        ));
+    } else {
+        registrations.push(format!(
+            "registerLazySingleton<{0}Repository>({0}Repository.new)",
+            pascal
+        ));
+    }
+    imports.push(data("repository"));
+
+    format!(
+        r#"{}
+
+void register{}(GetIt getIt) {{
+{}
+}}
+"#,
+        imports.join("\n"),
+        pascal,
+        cascade(&registrations)
+    )
+}
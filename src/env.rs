@@ -0,0 +1,170 @@
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+use crate::native;
+use crate::package::OptionalPackage;
+use crate::project::ProjectConfig;
+use crate::pubspec::{self, Pubspec};
+
+// A required key of the `.env` files, read through a getter of `Env`
+struct EnvKey {
+    name: &'static str,
+    getter: &'static str,
+    /// Value shown in `.env.example`
+    example: &'static str,
+}
+
+fn env_keys(config: &ProjectConfig) -> Vec<EnvKey> {
+    let mut keys = Vec::new();
+    if config.use_supabase {
+        keys.push(EnvKey {
+            name: "SUPABASE_URL",
+            getter: "supabaseUrl",
+            example: "https://your-project.supabase.co",
+        });
+        keys.push(EnvKey {
+            name: "SUPABASE_ANON_KEY",
+            getter: "supabaseAnonKey",
+            example: "your-anon-key",
+        });
+    }
+    if config.uses_package(OptionalPackage::Dio) {
+        keys.push(EnvKey {
+            name: "API_BASE_URL",
+            getter: "apiBaseUrl",
+            example: "https://api.example.com",
+        });
+    }
+    keys
+}
+
+pub fn uses_env(config: &ProjectConfig) -> bool {
+    !env_keys(config).is_empty()
+}
+
+pub fn flavor_env_file(flavor: &str) -> String {
+    format!(".env.{}", flavor)
+}
+
+// Files the app loads its environment from, one per flavor
+fn env_files(config: &ProjectConfig) -> Vec<String> {
+    if config.flavors.is_empty() {
+        vec![".env".to_string()]
+    } else {
+        config.flavors.iter().map(|f| flavor_env_file(f)).collect()
+    }
+}
+
+pub fn generate_env_template(config: &ProjectConfig) -> String {
+    let keys = env_keys(config);
+    let required = keys
+        .iter()
+        .map(|k| format!("    '{}',", k.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let getters = keys
+        .iter()
+        .map(|k| {
+            format!(
+                "  static String get {} => dotenv.env['{}']!;",
+                k.getter, k.name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"import 'package:flutter_dotenv/flutter_dotenv.dart';
+
+/// Values of the `.env` file, checked once at startup.
+class Env {{
+  Env._();
+
+  static const _required = [
+{}
+  ];
+
+  /// Loads [fileName] and throws when a required key is missing or empty.
+  static Future<void> load({{String fileName = '.env'}}) async {{
+    await dotenv.load(fileName: fileName);
+    final missing = _required
+        .where((key) => (dotenv.env[key] ?? '').trim().isEmpty)
+        .toList();
+    if (missing.isNotEmpty) {{
+      throw StateError(
+        'Missing ${{missing.join(', ')}} in $fileName, see .env.example',
+      );
+    }}
+  }}
+
+{}
+}}
+"#,
+        required, getters
+    )
+}
+
+// Adds the lines that are not there yet
+fn append_missing_lines(path: &Path, lines: &[&str]) -> Result<()> {
+    let Ok(source) = fs::read_to_string(path) else {
+        return Ok(());
+    };
+    let missing = lines
+        .iter()
+        .filter(|line| !source.lines().any(|l| l.trim() == **line))
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let mut updated = source.clone();
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated.push_str("\n# Environment\n");
+    for line in missing {
+        updated.push_str(&format!("{}\n", line));
+    }
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+// `.env.example` documents the keys, the env files themselves start empty
+// so a fresh checkout fails at startup until they are filled in
+pub fn write_env_files(project_dir: &Path, config: &ProjectConfig) -> Result<()> {
+    let keys = env_keys(config);
+    if keys.is_empty() {
+        return Ok(());
+    }
+
+    let example = keys
+        .iter()
+        .map(|k| format!("{}={}\n", k.name, k.example))
+        .collect::<String>();
+    fs::write(project_dir.join(".env.example"), example)?;
+
+    let files = env_files(config);
+    for file in &files {
+        let path = project_dir.join(file);
+        if !path.exists() {
+            let empty = keys
+                .iter()
+                .map(|k| format!("{}=\n", k.name))
+                .collect::<String>();
+            fs::write(path, empty)?;
+        }
+    }
+
+    // dotenv loads the files as assets
+    native::edit(&pubspec::pubspec(project_dir), |pubspec: &mut Pubspec| {
+        for file in &files {
+            pubspec.add_asset(file);
+        }
+        Ok(())
+    })?;
+    append_missing_lines(
+        &project_dir.join(".gitignore"),
+        &[".env", ".env.*", "!.env.example"],
+    )
+}
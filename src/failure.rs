@@ -0,0 +1,301 @@
+use crate::package::OptionalPackage;
+use crate::project::ProjectConfig;
+use crate::ui_kit::UiKit;
+
+fn generate_failure_template(config: &ProjectConfig) -> String {
+    let mut imports = Vec::new();
+    let mut cases = Vec::new();
+    if config.use_supabase {
+        imports.push("import 'package:supabase_flutter/supabase_flutter.dart';".to_string());
+        cases.push("      AuthException(:final message) => AuthFailure(message, cause: error),");
+        // PostgREST reports `.single()` without a row as PGRST116
+        cases.push("      PostgrestException(code: 'PGRST116') => NotFoundFailure(cause: error),");
+        cases.push(
+            "      PostgrestException(:final message) => ServerFailure(message, cause: error),",
+        );
+    }
+    if config.uses_package(OptionalPackage::Dio) {
+        imports.push(format!(
+            "import 'package:{}/core/network/api_exception.dart';",
+            config.name
+        ));
+        cases.push("      UnauthorizedException(:final message) ||");
+        cases.push("      ForbiddenException(:final message) =>");
+        cases.push("        AuthFailure(message, cause: error),");
+        cases.push("      NotFoundException() => NotFoundFailure(cause: error),");
+        cases.push("      NetworkException() || TimeoutApiException() =>");
+        cases.push("        NetworkFailure(cause: error),");
+        cases.push("      ApiException(:final message) => ServerFailure(message, cause: error),");
+    }
+    let imports = if imports.is_empty() {
+        String::new()
+    } else {
+        imports.join("\n") + "\n\n"
+    };
+
+    format!(
+        r#"{imports}/// What went wrong, in words the UI can show.
+sealed class Failure implements Exception {{
+  const Failure(this.message, {{this.cause}});
+
+  /// Maps what a data source threw to its failure.
+  factory Failure.from(Object error) {{
+    return switch (error) {{
+      final Failure failure => failure,
+{cases}
+      FormatException(:final message) => ValidationFailure(message),
+      _ => UnexpectedFailure(cause: error),
+    }};
+  }}
+
+  final String message;
+
+  /// Error the failure was created from
+  final Object? cause;
+
+  @override
+  String toString() => message;
+}}
+
+class NetworkFailure extends Failure {{
+  const NetworkFailure({{super.cause}})
+      : super('Check your internet connection and try again');
+}}
+
+class AuthFailure extends Failure {{
+  const AuthFailure(super.message, {{super.cause}});
+}}
+
+class NotFoundFailure extends Failure {{
+  const NotFoundFailure({{super.cause}}) : super('This item no longer exists');
+}}
+
+class ServerFailure extends Failure {{
+  const ServerFailure(super.message, {{super.cause}});
+}}
+
+class ValidationFailure extends Failure {{
+  const ValidationFailure(super.message);
+}}
+
+class UnexpectedFailure extends Failure {{
+  const UnexpectedFailure({{super.cause}}) : super('Something went wrong');
+}}
+"#,
+        cases = cases.join("\n"),
+    )
+}
+
+fn generate_result_template(project_name: &str) -> String {
+    format!(
+        r#"import 'package:{project_name}/core/errors/failure.dart';
+
+/// Value of an operation, or the [Failure] it ended with.
+sealed class Result<T> {{
+  const Result();
+
+  bool get isOk => this is Ok<T>;
+
+  T? get valueOrNull => switch (this) {{
+        Ok(:final value) => value,
+        Err() => null,
+      }};
+
+  Failure? get failureOrNull => switch (this) {{
+        Ok() => null,
+        Err(:final failure) => failure,
+      }};
+
+  /// The value, throwing the failure, e.g. inside a `FutureProvider`.
+  T getOrThrow() => switch (this) {{
+        Ok(:final value) => value,
+        Err(:final failure) => throw failure,
+      }};
+
+  R when<R>({{
+    required R Function(T value) ok,
+    required R Function(Failure failure) err,
+  }}) =>
+      switch (this) {{
+        Ok(:final value) => ok(value),
+        Err(:final failure) => err(failure),
+      }};
+
+  Result<R> map<R>(R Function(T value) transform) => switch (this) {{
+        Ok(:final value) => Ok(transform(value)),
+        Err(:final failure) => Err(failure),
+      }};
+}}
+
+final class Ok<T> extends Result<T> {{
+  const Ok(this.value);
+
+  final T value;
+}}
+
+final class Err<T> extends Result<T> {{
+  const Err(this.failure);
+
+  final Failure failure;
+}}
+
+/// Runs [body] and returns what it throws as a [Failure].
+Future<Result<T>> guard<T>(
+  Future<T> Function() body, {{
+  void Function(Object error, StackTrace stackTrace)? onError,
+}}) async {{
+  try {{
+    return Ok(await body());
+  }} catch (error, stackTrace) {{
+    onError?.call(error, stackTrace);
+    return Err(Failure.from(error));
+  }}
+}}
+"#
+    )
+}
+
+fn generate_failure_view_template(config: &ProjectConfig) -> String {
+    let project_name = &config.name;
+    let kit = config.ui_kit;
+    let widgets_import = kit.widgets_import();
+    let components_import = kit.components_import();
+    // `Icon` needs no Material ancestor, so every kit can use Material's icons
+    let icons_import = match kit {
+        UiKit::Cupertino | UiKit::Fluent => "\nimport 'package:flutter/material.dart' show Icons;",
+        UiKit::Shadcn | UiKit::Material => "",
+    };
+    let button = kit.button();
+    format!(
+        r#"{widgets_import}{icons_import}{components_import}
+import 'package:{project_name}/core/errors/failure.dart';
+
+/// Message of a [Failure] with a retry button.
+class FailureView extends StatelessWidget {{
+  const FailureView({{super.key, required this.failure, this.onRetry}});
+
+  /// Shows whatever an `AsyncValue` failed with.
+  FailureView.of(Object error, {{Key? key, VoidCallback? onRetry}})
+      : this(key: key, failure: Failure.from(error), onRetry: onRetry);
+
+  final Failure failure;
+  final VoidCallback? onRetry;
+
+  IconData get _icon => switch (failure) {{
+        NetworkFailure() => Icons.wifi_off,
+        AuthFailure() => Icons.lock_outline,
+        NotFoundFailure() => Icons.search_off,
+        _ => Icons.error_outline,
+      }};
+
+  @override
+  Widget build(BuildContext context) {{
+    return Center(
+      child: Padding(
+        padding: const EdgeInsets.all(16),
+        child: Column(
+          mainAxisSize: MainAxisSize.min,
+          children: [
+            Icon(_icon, size: 48),
+            const SizedBox(height: 12),
+            Text(failure.message, textAlign: TextAlign.center),
+            if (onRetry != null) ...[
+              const SizedBox(height: 16),
+              {button}(
+                onPressed: onRetry,
+                child: const Text('Try again'),
+              ),
+            ],
+          ],
+        ),
+      ),
+    );
+  }}
+}}
+"#
+    )
+}
+
+// Files below `lib/core`
+pub fn failure_files(config: &ProjectConfig) -> Vec<(&'static str, String)> {
+    vec![
+        ("errors/failure.dart", generate_failure_template(config)),
+        ("errors/result.dart", generate_result_template(&config.name)),
+        (
+            "widgets/failure_view.dart",
+            generate_failure_view_template(config),
+        ),
+    ]
+}
+
+// AuthService returning the reason an auth call failed
+pub fn generate_auth_service_template(project_name: &str) -> String {
+    format!(
+        r#"import 'package:logging/logging.dart';
+import 'package:supabase_flutter/supabase_flutter.dart';
+import 'package:{project_name}/core/errors/failure.dart';
+import 'package:{project_name}/core/errors/result.dart';
+
+class AuthService {{
+  AuthService(this.supabase);
+  final SupabaseClient supabase;
+  final _logger = Logger('AuthService');
+
+  void _logError(Object error, StackTrace stackTrace) =>
+      _logger.severe('Auth error', error, stackTrace);
+
+  // Sign In
+  Future<Result<User>> signIn(String email, String password) {{
+    return guard(() async {{
+      final response = await supabase.auth.signInWithPassword(
+        email: email,
+        password: password,
+      );
+      final user = response.user;
+      if (user == null) throw const AuthFailure('Sign in failed');
+      _logger.info('Signed in ${{user.id}}');
+      return user;
+    }}, onError: _logError);
+  }}
+
+  // Sign Up
+  Future<Result<User>> signUp(String email, String password) {{
+    return guard(() async {{
+      final response = await supabase.auth.signUp(
+        email: email,
+        password: password,
+      );
+      final user = response.user;
+      if (user == null) throw const AuthFailure('Sign up failed');
+      _logger.info('Signed up ${{user.id}}');
+      return user;
+    }}, onError: _logError);
+  }}
+
+  // Forgot password
+  Future<Result<void>> resetPassword(String email) {{
+    return guard(() async {{
+      await supabase.auth.resetPasswordForEmail(email);
+      _logger.info('Password reset email sent to: $email');
+    }}, onError: _logError);
+  }}
+
+  // Sign Out
+  Future<Result<void>> signOut() {{
+    return guard(() async {{
+      await supabase.auth.signOut();
+      _logger.info('User signed out successfully');
+    }}, onError: _logError);
+  }}
+
+  // Get Current User
+  User? getCurrentUser() => supabase.auth.currentUser;
+
+  // Check if Logged In
+  bool isLoggedIn() => supabase.auth.currentUser != null;
+
+  // Token of the current session, sent along with API requests
+  String? get accessToken => supabase.auth.currentSession?.accessToken;
+}}"#
+    )
+}
@@ -0,0 +1,379 @@
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::env::{flavor_env_file, uses_env};
+use crate::native::{self, Gradle, XcodeProject, BUILD_CONFIGURATIONS};
+use crate::pascal_case;
+use crate::project::ProjectConfig;
+
+// Flavor names end up in Gradle tasks, Xcode configurations and Dart enums
+pub fn validate_flavors(flavors: &[String]) -> Result<()> {
+    for (i, flavor) in flavors.iter().enumerate() {
+        let valid = flavor.starts_with(|c: char| c.is_ascii_lowercase())
+            && flavor
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+        if !valid {
+            bail!(
+                "Invalid flavor `{}`, use lowercase letters and digits",
+                flavor
+            );
+        }
+        // Android reserves source sets starting with `test`
+        if flavor.starts_with("test") {
+            bail!("Flavor `{}` must not start with `test`", flavor);
+        }
+        if flavors[..i].contains(flavor) {
+            bail!("Flavor `{}` is listed twice", flavor);
+        }
+    }
+    Ok(())
+}
+
+fn is_production(flavor: &str) -> bool {
+    matches!(flavor, "prod" | "production")
+}
+
+// Production keeps the package name, other flavors install next to it
+fn id_suffix(flavor: &str) -> String {
+    if is_production(flavor) {
+        String::new()
+    } else {
+        format!(".{}", flavor)
+    }
+}
+
+fn generate_flavor_config_template(config: &ProjectConfig) -> String {
+    let flavors = &config.flavors;
+    // Flavors load their own env file when the app has one
+    let with_env = uses_env(config);
+    let values = flavors
+        .iter()
+        .map(|f| {
+            if with_env {
+                format!("  {}('{}', '{}')", f, pascal_case(f), flavor_env_file(f))
+            } else {
+                format!("  {}('{}')", f, pascal_case(f))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    let (constructor, fields) = if with_env {
+        (
+            "this.title, this.envFile",
+            "  final String title;\n  final String envFile;",
+        )
+    } else {
+        ("this.title", "  final String title;")
+    };
+    let is_production = match flavors.iter().find(|f| is_production(f)) {
+        Some(production) => format!(
+            "\n\n  static bool get isProduction => current == Flavor.{};",
+            production
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        r#"enum Flavor {{
+{};
+
+  const Flavor({});
+
+{}
+}}
+
+class FlavorConfig {{
+  FlavorConfig._();
+
+  static Flavor current = Flavor.{};{}
+}}
+"#,
+        values, constructor, fields, flavors[0], is_production
+    )
+}
+
+fn generate_entrypoint_template(flavor: &str) -> String {
+    format!(
+        r#"import 'core/constants/flavor_config.dart';
+import 'main.dart';
+
+void main() => bootstrap(Flavor.{});
+"#,
+        flavor
+    )
+}
+
+fn android_flavors(config: &ProjectConfig, kts: bool) -> String {
+    let mut block = if kts {
+        "flavorDimensions += \"environment\"\nproductFlavors {\n".to_string()
+    } else {
+        "flavorDimensions \"environment\"\nproductFlavors {\n".to_string()
+    };
+    for flavor in &config.flavors {
+        let suffix = id_suffix(flavor);
+        if kts {
+            block.push_str(&format!(
+                "    create(\"{}\") {{\n        dimension = \"environment\"\n",
+                flavor
+            ));
+            if !suffix.is_empty() {
+                block.push_str(&format!("        applicationIdSuffix = \"{}\"\n", suffix));
+            }
+        } else {
+            block.push_str(&format!(
+                "    {} {{\n        dimension \"environment\"\n",
+                flavor
+            ));
+            if !suffix.is_empty() {
+                block.push_str(&format!("        applicationIdSuffix \"{}\"\n", suffix));
+            }
+        }
+        block.push_str("    }\n");
+    }
+    block.push_str("}\n");
+    block
+}
+
+// `flutter run --flavor <name>` builds the scheme of the same name with the
+// `<config>-<name>` build configurations
+fn write_ios_flavors(project_dir: &Path, config: &ProjectConfig) -> Result<()> {
+    if !native::has_ios(project_dir) {
+        return Ok(());
+    }
+    let ios = project_dir.join("ios");
+    let bundle_id = config.ios_bundle_id();
+
+    for flavor in &config.flavors {
+        let flavor_bundle_id = format!("{}{}", bundle_id, id_suffix(flavor));
+        for build in BUILD_CONFIGURATIONS {
+            let name = format!("{}-{}", build, flavor);
+            fs::write(
+                ios.join("Flutter").join(format!("{}.xcconfig", name)),
+                format!(
+                    "#include? \"Pods/Target Support Files/Pods-Runner/Pods-Runner.{}.xcconfig\"\n#include \"Generated.xcconfig\"\n\nPRODUCT_BUNDLE_IDENTIFIER = {}\n",
+                    name.to_lowercase(),
+                    flavor_bundle_id
+                ),
+            )?;
+        }
+
+        native::edit(
+            &native::xcode_project(project_dir),
+            |project: &mut XcodeProject| project.add_flavor(flavor, &bundle_id, &flavor_bundle_id),
+        )?;
+
+        let schemes = ios
+            .join("Runner.xcodeproj")
+            .join("xcshareddata")
+            .join("xcschemes");
+        let scheme = schemes.join(format!("{}.xcscheme", flavor));
+        if let (false, Ok(runner)) = (
+            scheme.exists(),
+            fs::read_to_string(schemes.join("Runner.xcscheme")),
+        ) {
+            let mut runner = runner;
+            for build in BUILD_CONFIGURATIONS {
+                runner = runner.replace(
+                    &format!("buildConfiguration = \"{}\"", build),
+                    &format!("buildConfiguration = \"{}-{}\"", build, flavor),
+                );
+            }
+            fs::write(scheme, runner)?;
+        }
+    }
+
+    // CocoaPods builds configurations it does not know in release mode
+    let podfile = ios.join("Podfile");
+    if let Ok(source) = fs::read_to_string(&podfile) {
+        let mut lines = Vec::new();
+        for line in source.lines() {
+            lines.push(line.to_string());
+            // Flavored configurations build in the mode of their base one
+            for build in BUILD_CONFIGURATIONS {
+                let Some((indent, mode)) = line.split_once(&format!("'{}' => ", build)) else {
+                    continue;
+                };
+                for flavor in &config.flavors {
+                    let entry = format!("'{}-{}' => {}", build, flavor, mode);
+                    if !source.contains(&entry) {
+                        lines.push(format!("{}{}", indent, entry));
+                    }
+                }
+            }
+        }
+        let updated = lines.join("\n") + "\n";
+        if updated != source {
+            fs::write(&podfile, updated)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Entrypoints and native build flavors of `config.flavors`
+pub fn write_flavors(project_dir: &Path, config: &ProjectConfig) -> Result<()> {
+    if config.flavors.is_empty() {
+        return Ok(());
+    }
+    let lib_path = project_dir.join("lib");
+
+    fs::write(
+        lib_path.join("core/constants/flavor_config.dart"),
+        generate_flavor_config_template(config),
+    )?;
+    for flavor in &config.flavors {
+        fs::write(
+            lib_path.join(format!("main_{}.dart", flavor)),
+            generate_entrypoint_template(flavor),
+        )?;
+    }
+
+    native::edit(&native::app_gradle(project_dir), |gradle: &mut Gradle| {
+        let block = android_flavors(config, gradle.is_kts());
+        gradle.set_block(&["android"], "flavors", &block)
+    })?;
+    write_ios_flavors(project_dir, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(flavors: &[&str]) -> ProjectConfig {
+        serde_json::from_value(serde_json::json!({
+            "name": "todo",
+            "package": "com.example",
+            "use_riverpod": false,
+            "use_supabase": false,
+            "features": [{ "name": "home", "layers": [] }],
+            "flavors": flavors,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn validates_flavors() {
+        let flavors = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert!(validate_flavors(&flavors(&["dev", "staging2", "prod"])).is_ok());
+        for invalid in [
+            &["Dev"][..],
+            &["2dev"],
+            &["my-dev"],
+            &["testing"],
+            &["dev", "dev"],
+        ] {
+            assert!(
+                validate_flavors(&flavors(invalid)).is_err(),
+                "{:?}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn product_flavors_suffix_all_but_production() {
+        let config = config(&["dev", "prod"]);
+        assert_eq!(
+            android_flavors(&config, false),
+            "flavorDimensions \"environment\"\nproductFlavors {\n    dev {\n        dimension \"environment\"\n        applicationIdSuffix \".dev\"\n    }\n    prod {\n        dimension \"environment\"\n    }\n}\n"
+        );
+        assert_eq!(
+            android_flavors(&config, true),
+            "flavorDimensions += \"environment\"\nproductFlavors {\n    create(\"dev\") {\n        dimension = \"environment\"\n        applicationIdSuffix = \".dev\"\n    }\n    create(\"prod\") {\n        dimension = \"environment\"\n    }\n}\n"
+        );
+    }
+
+    const PROJECT: &str = r#"// !$*UTF8*$!
+{
+	objects = {
+
+/* Begin PBXFileReference section */
+/* End PBXFileReference section */
+
+/* Begin PBXGroup section */
+		9740EEB11CF90186004384FC /* Flutter */ = {
+			isa = PBXGroup;
+			children = (
+			);
+			name = Flutter;
+			sourceTree = "<group>";
+		};
+/* End PBXGroup section */
+
+/* Begin XCBuildConfiguration section */
+		97C147061CF9000F007C117D /* Debug */ = {
+			isa = XCBuildConfiguration;
+			buildSettings = {
+				PRODUCT_BUNDLE_IDENTIFIER = com.example.todo;
+			};
+			name = Debug;
+		};
+/* End XCBuildConfiguration section */
+
+/* Begin XCConfigurationList section */
+		97C147051CF9000F007C117D /* Build configuration list for PBXNativeTarget "Runner" */ = {
+			isa = XCConfigurationList;
+			buildConfigurations = (
+				97C147061CF9000F007C117D /* Debug */,
+			);
+			defaultConfigurationIsVisible = 0;
+		};
+/* End XCConfigurationList section */
+	};
+}
+"#;
+
+    const SCHEME: &str = r#"<Scheme>
+   <LaunchAction
+      buildConfiguration = "Debug">
+   </LaunchAction>
+   <ArchiveAction
+      buildConfiguration = "Release">
+   </ArchiveAction>
+</Scheme>
+"#;
+
+    #[test]
+    fn ios_flavors_get_xcconfigs_and_schemes() {
+        let dir = std::env::temp_dir().join(format!("flutter_gen_flavors_{}", std::process::id()));
+        let ios = dir.join("ios");
+        let schemes = ios
+            .join("Runner.xcodeproj")
+            .join("xcshareddata")
+            .join("xcschemes");
+        fs::create_dir_all(ios.join("Runner")).unwrap();
+        fs::create_dir_all(ios.join("Flutter")).unwrap();
+        fs::create_dir_all(&schemes).unwrap();
+        fs::write(native::xcode_project(&dir), PROJECT).unwrap();
+        fs::write(schemes.join("Runner.xcscheme"), SCHEME).unwrap();
+
+        let config = config(&["dev", "prod"]);
+        write_ios_flavors(&dir, &config).unwrap();
+        let project = fs::read_to_string(native::xcode_project(&dir)).unwrap();
+        write_ios_flavors(&dir, &config).unwrap();
+        assert_eq!(
+            fs::read_to_string(native::xcode_project(&dir)).unwrap(),
+            project
+        );
+
+        let xcconfig =
+            fs::read_to_string(ios.join("Flutter").join("Release-dev.xcconfig")).unwrap();
+        assert!(xcconfig.contains("Pods-Runner.release-dev.xcconfig"));
+        assert!(xcconfig.contains("#include \"Generated.xcconfig\""));
+        assert!(xcconfig.ends_with("PRODUCT_BUNDLE_IDENTIFIER = com.example.todo.dev\n"));
+        let xcconfig = fs::read_to_string(ios.join("Flutter").join("Debug-prod.xcconfig")).unwrap();
+        assert!(xcconfig.ends_with("PRODUCT_BUNDLE_IDENTIFIER = com.example.todo\n"));
+
+        assert!(project.contains("/* Debug-dev */ = {"));
+        assert!(project.contains("/* Debug-prod */ = {"));
+
+        let scheme = fs::read_to_string(schemes.join("dev.xcscheme")).unwrap();
+        assert!(scheme.contains("buildConfiguration = \"Debug-dev\""));
+        assert!(scheme.contains("buildConfiguration = \"Release-dev\""));
+        assert!(schemes.join("prod.xcscheme").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
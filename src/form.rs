@@ -0,0 +1,1054 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::dart;
+use crate::l10n;
+use crate::project::{ProjectConfig, Screen, ScreenKind};
+use crate::router::write_router;
+use crate::ui_kit::{indent, UiKit};
+use crate::widget::validate_name;
+use crate::{camel_case, generate_controller_template, pascal_case};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputKind {
+    Text,
+    Email,
+    Password,
+    Number,
+    Date,
+    Dropdown(Vec<String>),
+    Checkbox,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Validator {
+    Required,
+    Min(f64),
+    Max(f64),
+    Regex(String),
+    /// Value must equal the named field, e.g. a password confirmation
+    Match(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormFieldSpec {
+    pub name: String,
+    pub input: InputKind,
+    #[serde(default)]
+    pub validators: Vec<Validator>,
+}
+
+// Human readable label for a field, `due_at` -> `Due at`
+pub fn label(name: &str) -> String {
+    let words = name.replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => words,
+    }
+}
+
+impl FormFieldSpec {
+    // Parses `name:type[:rule,rule,...]`, for example
+    // `password:password:required,min=8` or `role:dropdown(admin|editor):required`.
+    // `regex=` must be the last rule since the pattern may contain commas.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut parts = spec.splitn(3, ':');
+        let name = parts.next().unwrap_or_default().trim().to_string();
+        let kind = parts.next().unwrap_or("text").trim();
+        let rules = parts.next().unwrap_or_default();
+
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            bail!("Invalid field name in `{}`", spec);
+        }
+        // The name becomes a field of the form data and a state member
+        if !dart::is_identifier(&camel_case(&name)) {
+            bail!(
+                "Field name `{}` is not a valid Dart name, it may not start with a digit or be a reserved word",
+                name
+            );
+        }
+
+        let input = match kind {
+            "text" => InputKind::Text,
+            "email" => InputKind::Email,
+            "password" => InputKind::Password,
+            "number" => InputKind::Number,
+            "date" => InputKind::Date,
+            "checkbox" => InputKind::Checkbox,
+            _ if kind.starts_with("dropdown(") && kind.ends_with(')') => {
+                let mut options: Vec<String> = Vec::new();
+                for option in kind["dropdown(".len()..kind.len() - 1].split('|') {
+                    let option = option.trim();
+                    if option.is_empty() {
+                        continue;
+                    }
+                    if options.iter().any(|o| o == option) {
+                        bail!("Duplicate dropdown option `{}` in `{}`", option, spec);
+                    }
+                    options.push(option.to_string());
+                }
+                if options.is_empty() {
+                    bail!("Dropdown `{}` needs at least one option", name);
+                }
+                InputKind::Dropdown(options)
+            }
+            _ => bail!(
+                "Unknown field type `{}`. Expected text, email, password, number, date, checkbox or dropdown(a|b)",
+                kind
+            ),
+        };
+
+        let mut validators = Vec::new();
+        let mut rest = rules.trim();
+        while !rest.is_empty() {
+            if let Some(pattern) = rest.strip_prefix("regex=") {
+                validators.push(Validator::Regex(pattern.to_string()));
+                break;
+            }
+
+            let (rule, tail) = rest.split_once(',').unwrap_or((rest, ""));
+            rest = tail.trim();
+
+            let (key, value) = rule.split_once('=').unwrap_or((rule, ""));
+            let number = || {
+                value
+                    .parse::<f64>()
+                    .with_context(|| format!("`{}` needs a number in `{}`", key, spec))
+            };
+            validators.push(match key.trim() {
+                "required" => Validator::Required,
+                "min" => Validator::Min(number()?),
+                "max" => Validator::Max(number()?),
+                "match" => Validator::Match(value.trim().to_string()),
+                other => bail!("Unknown validator `{}` in `{}`", other, spec),
+            });
+        }
+
+        let field = FormFieldSpec {
+            name,
+            input,
+            validators,
+        };
+
+        let text_only = field.validators.iter().any(|v| {
+            matches!(
+                v,
+                Validator::Min(_) | Validator::Max(_) | Validator::Regex(_)
+            )
+        });
+        if text_only && !field.is_text() {
+            bail!("min, max and regex only apply to text, email, password and number fields");
+        }
+
+        Ok(field)
+    }
+
+    // Same name as the model field for forms built from a model's columns
+    pub fn dart_name(&self) -> String {
+        crate::model::field_name(&self.name)
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.validators.contains(&Validator::Required)
+    }
+
+    // Text-like inputs keep their value in a TextEditingController
+    fn is_text(&self) -> bool {
+        matches!(
+            self.input,
+            InputKind::Text | InputKind::Email | InputKind::Password | InputKind::Number
+        )
+    }
+
+    fn value_expr(&self) -> String {
+        if self.is_text() {
+            format!("_{}Controller.text", self.dart_name())
+        } else {
+            format!("_{}", self.dart_name())
+        }
+    }
+
+    fn data_type(&self) -> &'static str {
+        match self.input {
+            InputKind::Text | InputKind::Email | InputKind::Password => "String",
+            InputKind::Number => "num?",
+            InputKind::Date => "DateTime?",
+            InputKind::Dropdown(_) => "String?",
+            InputKind::Checkbox => "bool",
+        }
+    }
+
+    fn data_expr(&self) -> String {
+        match self.input {
+            InputKind::Number => format!("num.tryParse({})", self.value_expr()),
+            _ => self.value_expr(),
+        }
+    }
+
+    // Validators of Flutter's `FormField`s receive a nullable value, shadcn_ui's
+    // text and checkbox fields a non-null one
+    fn validator(&self, fields: &[FormFieldSpec], kit: UiKit) -> Option<String> {
+        let label = label(&self.name);
+        let mut checks = Vec::new();
+
+        let matches = self.validators.iter().filter_map(|v| match v {
+            Validator::Match(target) => Some(target),
+            _ => None,
+        });
+
+        if !self.is_text() {
+            if self.is_required() {
+                checks.push(match self.input {
+                    InputKind::Checkbox => format!("if (!value) return '{} is required';", label),
+                    _ => format!("if (value == null) return '{} is required';", label),
+                });
+            }
+            for target in matches {
+                let other = fields.iter().find(|f| &f.name == target)?;
+                checks.push(format!(
+                    "if (value != {}) return '{} must match {}';",
+                    other.value_expr(),
+                    label,
+                    crate::form::label(&other.name)
+                ));
+            }
+        } else {
+            if self.is_required() {
+                checks.push(format!(
+                    "if (value.isEmpty) return '{} is required';",
+                    label
+                ));
+            }
+            if self.input == InputKind::Email {
+                checks.push(format!(
+                    "if (value.isNotEmpty && !RegExp(r'^[^@\\s]+@[^@\\s]+\\.[^@\\s]+$').hasMatch(value)) {{\n  return '{} must be a valid email';\n}}",
+                    label
+                ));
+            }
+            if self.input == InputKind::Number {
+                checks.push(format!(
+                    "final number = num.tryParse(value);\nif (value.isNotEmpty && number == null) {{\n  return '{} must be a number';\n}}",
+                    label
+                ));
+            }
+            for validator in &self.validators {
+                let check = match (validator, &self.input) {
+                    (Validator::Min(min), InputKind::Number) => format!(
+                        "if (number != null && number < {0}) {{\n  return '{1} must be at least {0}';\n}}",
+                        min, label
+                    ),
+                    (Validator::Max(max), InputKind::Number) => format!(
+                        "if (number != null && number > {0}) {{\n  return '{1} must be at most {0}';\n}}",
+                        max, label
+                    ),
+                    (Validator::Min(min), _) => format!(
+                        "if (value.isNotEmpty && value.length < {0}) {{\n  return '{1} must be at least {0} characters';\n}}",
+                        min, label
+                    ),
+                    (Validator::Max(max), _) => format!(
+                        "if (value.length > {0}) {{\n  return '{1} must be at most {0} characters';\n}}",
+                        max, label
+                    ),
+                    (Validator::Regex(pattern), _) => format!(
+                        "if (value.isNotEmpty && !RegExp({}).hasMatch(value)) {{\n  return '{} is invalid';\n}}",
+                        raw_string(pattern),
+                        label
+                    ),
+                    (Validator::Match(target), _) => {
+                        let other = fields.iter().find(|f| &f.name == target)?;
+                        format!(
+                            "if (value != {}) return '{} must match {}';",
+                            other.value_expr(),
+                            label,
+                            crate::form::label(&other.name)
+                        )
+                    }
+                    (Validator::Required, _) => continue,
+                };
+                checks.push(check);
+            }
+        }
+
+        if checks.is_empty() {
+            return None;
+        }
+        let default = match self.input {
+            InputKind::Checkbox => Some("false"),
+            _ if self.is_text() => Some("''"),
+            _ => None,
+        };
+        let parameter = match default {
+            Some(default) if kit.nullable_validators() => {
+                checks.insert(0, format!("final value = input ?? {};", default));
+                "input"
+            }
+            _ => "value",
+        };
+
+        let body = checks
+            .join("\n")
+            .lines()
+            .map(|line| format!("                {}", line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(format!(
+            "({}) {{\n{}\n                return null;\n              }}",
+            parameter, body
+        ))
+    }
+
+    fn widget(
+        &self,
+        fields: &[FormFieldSpec],
+        config: &ProjectConfig,
+        screen: (&str, &str),
+    ) -> String {
+        let kit = config.ui_kit;
+        let name = self.dart_name();
+        let (feature, screen) = screen;
+        let label = Label::new(
+            config,
+            &l10n::key(feature, screen, &format!("{}_label", self.name)),
+            &label(&self.name),
+        );
+        let validator = self
+            .validator(fields, kit)
+            .map(|v| format!("\n              validator: {},", v))
+            .unwrap_or_default();
+        let extra = match self.input {
+            InputKind::Email => "\n              keyboardType: TextInputType.emailAddress,",
+            InputKind::Password => "\n              obscureText: true,",
+            InputKind::Number => "\n              keyboardType: const TextInputType.numberWithOptions(decimal: true),",
+            _ => "",
+        };
+
+        match kit {
+            UiKit::Shadcn => self.shad_widget(&name, &label, extra, &validator),
+            UiKit::Material => self.material_widget(&name, &label, extra, &validator),
+            UiKit::Cupertino => self.cupertino_widget(&name, &label, extra, &validator),
+            UiKit::Fluent => self.fluent_widget(&name, &label, extra, &validator),
+        }
+    }
+
+    // `option` receives the Dart strings of each option's value and label
+    fn options(&self, option: impl Fn(&str, &str) -> String) -> String {
+        match &self.input {
+            InputKind::Dropdown(options) => options
+                .iter()
+                .map(|o| option(&dart::string_literal(o), &dart::string_literal(&label(o))))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+
+    fn shad_widget(&self, name: &str, label: &Label, extra: &str, validator: &str) -> String {
+        let text = &label.text;
+        match &self.input {
+            InputKind::Checkbox => format!(
+                r#"            ShadCheckboxFormField(
+              id: '{id}',
+              initialValue: _{name},
+              inputLabel: {text},
+              onChanged: (value) => _{name} = value,{validator}
+            ),"#,
+                id = self.name
+            ),
+            InputKind::Date => format!(
+                r#"            ShadDatePickerFormField(
+              id: '{id}',
+              label: {text},
+              initialValue: _{name},
+              onChanged: (value) => _{name} = value,{validator}
+            ),"#,
+                id = self.name
+            ),
+            InputKind::Dropdown(_) => format!(
+                r#"            ShadSelectFormField<String>(
+              id: '{id}',
+              label: {text},
+              initialValue: _{name},
+              placeholder: const Text('Select {lower}'),
+              options: const [
+{options}
+              ],
+              selectedOptionBuilder: (context, value) => Text(value),
+              onChanged: (value) => _{name} = value,{validator}
+            ),"#,
+                id = self.name,
+                lower = label.plain.to_lowercase(),
+                options = self.options(|value, text| format!(
+                    "                ShadOption(value: {}, child: Text({})),",
+                    value, text
+                ))
+            ),
+            _ => format!(
+                r#"            ShadInputFormField(
+              id: '{id}',
+              controller: _{name}Controller,
+              label: {text},{extra}{validator}
+            ),"#,
+                id = self.name
+            ),
+        }
+    }
+
+    fn material_widget(&self, name: &str, label: &Label, extra: &str, validator: &str) -> String {
+        let text = &label.text;
+        let decoration = format!(
+            "{}InputDecoration(labelText: {})",
+            if label.is_literal() { "const " } else { "" },
+            label.string
+        );
+        match &self.input {
+            InputKind::Checkbox => format!(
+                r#"            FormField<bool>(
+              initialValue: _{name},{validator}
+              builder: (field) => CheckboxListTile(
+                value: field.value,
+                title: {text},
+                subtitle: field.hasError ? Text(field.errorText!) : null,
+                onChanged: (value) {{
+                  field.didChange(value);
+                  _{name} = value ?? false;
+                }},
+              ),
+            ),"#
+            ),
+            InputKind::Date => format!(
+                r#"            FormField<DateTime>(
+              initialValue: _{name},{validator}
+              builder: (field) => ListTile(
+                title: {text},
+                subtitle: Text(
+                  field.errorText ??
+                      field.value?.toString().split(' ').first ??
+                      'Not set',
+                ),
+                onTap: () async {{
+                  final picked = await showDatePicker(
+                    context: context,
+                    initialDate: field.value ?? DateTime.now(),
+                    firstDate: DateTime(1900),
+                    lastDate: DateTime(2100),
+                  );
+                  if (picked == null) return;
+                  field.didChange(picked);
+                  _{name} = picked;
+                }},
+              ),
+            ),"#
+            ),
+            InputKind::Dropdown(_) => format!(
+                r#"            DropdownButtonFormField<String>(
+              value: _{name},
+              decoration: {decoration},
+              hint: const Text('Select {lower}'),
+              items: const [
+{options}
+              ],
+              onChanged: (value) => _{name} = value,{validator}
+            ),"#,
+                lower = label.plain.to_lowercase(),
+                options = self.options(|value, text| format!(
+                    "                DropdownMenuItem(value: {}, child: Text({})),",
+                    value, text
+                ))
+            ),
+            _ => format!(
+                r#"            TextFormField(
+              controller: _{name}Controller,
+              decoration: {decoration},{extra}{validator}
+            ),"#
+            ),
+        }
+    }
+
+    fn cupertino_widget(&self, name: &str, label: &Label, extra: &str, validator: &str) -> String {
+        let Label { text, string, .. } = label;
+        // Cupertino has no form fields besides text, so the others wrap a
+        // `CupertinoFormRow` that shows the validation error
+        let row = |value_type: &str, child: &str| {
+            format!(
+                r#"            FormField<{value_type}>(
+              initialValue: _{name},{validator}
+              builder: (field) => CupertinoFormRow(
+                prefix: {text},
+                error: field.hasError ? Text(field.errorText!) : null,
+                child: {child},
+              ),
+            ),"#
+            )
+        };
+        match &self.input {
+            InputKind::Checkbox => row(
+                "bool",
+                &format!(
+                    r#"CupertinoSwitch(
+                  value: field.value ?? false,
+                  onChanged: (value) {{
+                    field.didChange(value);
+                    _{name} = value;
+                  }},
+                )"#
+                ),
+            ),
+            InputKind::Date => row(
+                "DateTime",
+                &format!(
+                    r#"CupertinoButton(
+                  onPressed: () => showCupertinoModalPopup<void>(
+                    context: context,
+                    builder: (context) => SizedBox(
+                      height: 216,
+                      child: CupertinoDatePicker(
+                        mode: CupertinoDatePickerMode.date,
+                        backgroundColor: CupertinoColors.systemBackground,
+                        initialDateTime: field.value,
+                        onDateTimeChanged: (value) {{
+                          field.didChange(value);
+                          _{name} = value;
+                        }},
+                      ),
+                    ),
+                  ),
+                  child: Text(
+                    field.value?.toString().split(' ').first ?? 'Select',
+                  ),
+                )"#
+                ),
+            ),
+            InputKind::Dropdown(_) => row(
+                "String",
+                &format!(
+                    r#"CupertinoSlidingSegmentedControl<String>(
+                  groupValue: field.value,
+                  children: const {{
+{options}
+                  }},
+                  onValueChanged: (value) {{
+                    field.didChange(value);
+                    _{name} = value;
+                  }},
+                )"#,
+                    options = self.options(|value, text| format!(
+                        "                    {}: Text({}),",
+                        value, text
+                    ))
+                ),
+            ),
+            _ => format!(
+                r#"            CupertinoTextFormFieldRow(
+              controller: _{name}Controller,
+              prefix: {text},
+              placeholder: {string},{extra}{validator}
+            ),"#
+            ),
+        }
+    }
+
+    fn fluent_widget(&self, name: &str, label: &Label, extra: &str, validator: &str) -> String {
+        let Label { text, string, .. } = label;
+        // Fields without a fluent_ui form field show their error below
+        let field = |value_type: &str, child: &str| {
+            format!(
+                r#"            FormField<{value_type}>(
+              initialValue: _{name},{validator}
+              builder: (field) => Column(
+                crossAxisAlignment: CrossAxisAlignment.start,
+                children: [
+                  {child},
+                  if (field.hasError) Text(field.errorText!),
+                ],
+              ),
+            ),"#
+            )
+        };
+        match &self.input {
+            InputKind::Checkbox => field(
+                "bool",
+                &format!(
+                    r#"Checkbox(
+                    checked: field.value,
+                    content: {text},
+                    onChanged: (value) {{
+                      field.didChange(value);
+                      _{name} = value ?? false;
+                    }},
+                  )"#
+                ),
+            ),
+            InputKind::Date => field(
+                "DateTime",
+                &format!(
+                    r#"DatePicker(
+                    header: {string},
+                    selected: field.value,
+                    onChanged: (value) {{
+                      field.didChange(value);
+                      _{name} = value;
+                    }},
+                  )"#
+                ),
+            ),
+            InputKind::Dropdown(_) => format!(
+                r#"            InfoLabel(
+              label: {string},
+              child: ComboboxFormField<String>(
+                value: _{name},
+                placeholder: const Text('Select {lower}'),
+                items: const [
+{options}
+                ],
+                onChanged: (value) => setState(() => _{name} = value),{validator}
+              ),
+            ),"#,
+                lower = label.plain.to_lowercase(),
+                options = self.options(|value, text| format!(
+                    "                  ComboBoxItem(value: {}, child: Text({})),",
+                    value, text
+                )),
+                validator = indent(validator, 2)
+            ),
+            _ => format!(
+                r#"            InfoLabel(
+              label: {string},
+              child: TextFormBox(
+                controller: _{name}Controller,{fields}
+              ),
+            ),"#,
+                fields = indent(&format!("{}{}", extra, validator), 2)
+            ),
+        }
+    }
+}
+
+// Label of a field, a literal or the field's localized message
+struct Label {
+    /// `Text` widget showing the label
+    text: String,
+    /// Dart string of the label
+    string: String,
+    /// English text, for hints such as `Select role`
+    plain: String,
+}
+
+impl Label {
+    fn new(config: &ProjectConfig, key: &str, plain: &str) -> Self {
+        Label {
+            text: l10n::text(config, key, plain),
+            string: l10n::message(config, key, plain),
+            plain: plain.to_string(),
+        }
+    }
+
+    fn is_literal(&self) -> bool {
+        self.string.starts_with('\'')
+    }
+}
+
+// Dart raw string literal for a regex, picking a quote the pattern lacks.
+// Patterns holding both quotes fall back to an escaped string.
+fn raw_string(pattern: &str) -> String {
+    if !pattern.contains('\'') {
+        format!("r'{}'", pattern)
+    } else if !pattern.contains('"') {
+        format!("r\"{}\"", pattern)
+    } else {
+        dart::string_literal(pattern)
+    }
+}
+
+// State, cleanup and widgets for a set of fields. Screens wrap these in the
+// kit's form and decide how the values are submitted.
+pub struct FormParts {
+    pub declarations: String,
+    pub disposals: String,
+    pub widgets: String,
+}
+
+// `screen` is the feature and screen the labels' messages are keyed by
+pub fn render_fields(
+    config: &ProjectConfig,
+    fields: &[FormFieldSpec],
+    screen: (&str, &str),
+) -> FormParts {
+    let mut declarations = Vec::new();
+    let mut disposals = Vec::new();
+    let mut widgets = Vec::new();
+
+    for field in fields {
+        let name = field.dart_name();
+        match field.input {
+            InputKind::Checkbox => declarations.push(format!("  bool _{} = false;", name)),
+            InputKind::Date => declarations.push(format!("  DateTime? _{};", name)),
+            InputKind::Dropdown(_) => declarations.push(format!("  String? _{};", name)),
+            _ => {
+                declarations.push(format!(
+                    "  final _{}Controller = TextEditingController();",
+                    name
+                ));
+                disposals.push(format!("    _{}Controller.dispose();", name));
+            }
+        }
+        widgets.push(field.widget(fields, config, screen));
+    }
+
+    FormParts {
+        declarations: declarations.join("\n"),
+        disposals: disposals.join("\n"),
+        widgets: widgets.join("\n"),
+    }
+}
+
+pub fn add_form(
+    project_dir: &Path,
+    feature_name: &str,
+    screen_name: &str,
+    field_specs: &[String],
+) -> Result<()> {
+    validate_name("screen", screen_name)?;
+    let mut config = ProjectConfig::load(project_dir)?;
+
+    if !config.use_riverpod {
+        bail!("Form screens submit through the feature's Riverpod controller, but this project does not use Riverpod");
+    }
+    if !config.features.iter().any(|f| f.name == feature_name) {
+        bail!("Unknown feature `{}`", feature_name);
+    }
+
+    let fields = if field_specs.is_empty() {
+        // Fall back to the columns of the feature's model
+        match config.model(feature_name) {
+            Some(model) => crate::crud::model_form_fields(model),
+            None => bail!("Declare the form's fields with --field name:type[:rules]"),
+        }
+    } else {
+        field_specs
+            .iter()
+            .map(|spec| FormFieldSpec::parse(spec))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    for (i, field) in fields.iter().enumerate() {
+        if fields[..i]
+            .iter()
+            .any(|f| f.dart_name() == field.dart_name())
+        {
+            bail!("Duplicate field `{}`", field.name);
+        }
+        for validator in &field.validators {
+            if let Validator::Match(target) = validator {
+                if !fields.iter().any(|f| &f.name == target) {
+                    bail!("`{}` must match unknown field `{}`", field.name, target);
+                }
+            }
+        }
+    }
+
+    if config.has_route(screen_name) {
+        bail!("A route named `{}` already exists", screen_name);
+    }
+    let feature_path = project_dir.join("lib").join("features").join(feature_name);
+    let screen_file = feature_path
+        .join("presentation")
+        .join(format!("{}_screen.dart", screen_name));
+    if screen_file.exists() {
+        bail!("A screen named `{}` already exists", screen_name);
+    }
+    for dir in ["domain", "logic", "presentation"] {
+        fs::create_dir_all(feature_path.join(dir))?;
+    }
+
+    fs::write(
+        feature_path
+            .join("domain")
+            .join(format!("{}_form_data.dart", screen_name)),
+        generate_form_data_template(screen_name, &fields),
+    )?;
+    fs::write(
+        screen_file,
+        generate_form_screen_template(&config, feature_name, screen_name, &fields),
+    )?;
+
+    // Route the submission through the feature's controller
+    let controller_path = feature_path
+        .join("logic")
+        .join(format!("{}_controller.dart", feature_name));
+    let controller = match fs::read_to_string(&controller_path) {
+        Ok(content) => content,
+        Err(_) => generate_controller_template(feature_name),
+    };
+    let method = format!("submit{}", pascal_case(screen_name));
+    if !controller.contains(&format!("{}(", method)) {
+        let import = format!(
+            "import 'package:{}/features/{}/domain/{}_form_data.dart';",
+            config.name, feature_name, screen_name
+        );
+        let handler = format!(
+            "  Future<void> {}({}FormData data) async {{\n    // TODO: Handle {} submission\n  }}\n",
+            method,
+            pascal_case(screen_name),
+            label(screen_name).to_lowercase()
+        );
+        fs::write(
+            &controller_path,
+            insert_method(&controller, &import, &handler),
+        )?;
+    }
+
+    let feature = config
+        .feature_mut(feature_name)
+        .expect("feature checked above");
+    feature.screens.push(Screen {
+        name: screen_name.to_string(),
+        widget: screen_name.to_string(),
+        path: screen_name.replace('_', "-"),
+        params: Vec::new(),
+        children: Vec::new(),
+        kind: ScreenKind::Form,
+        fields,
+        guards: Vec::new(),
+    });
+
+    write_router(project_dir, &config)?;
+    l10n::write_arb_files(project_dir, &config)?;
+    config.save(project_dir)?;
+
+    Ok(())
+}
+
+// Adds an import after the existing ones and a method at the end of the last
+// class in a generated Dart file.
+fn insert_method(source: &str, import: &str, method: &str) -> String {
+    let mut source = source.trim_end().to_string();
+
+    if !source.contains(import) {
+        let end_of_imports = source
+            .match_indices("import ")
+            .last()
+            .and_then(|(i, _)| source[i..].find('\n').map(|n| i + n + 1))
+            .unwrap_or(0);
+        source.insert_str(end_of_imports, &format!("{}\n", import));
+    }
+
+    if let Some(close) = source.rfind('}') {
+        source.insert_str(close, &format!("\n{}", method));
+    }
+
+    source + "\n"
+}
+
+fn generate_form_data_template(screen_name: &str, fields: &[FormFieldSpec]) -> String {
+    let class_name = format!("{}FormData", pascal_case(screen_name));
+    let params = fields
+        .iter()
+        .map(|f| format!("    required this.{},", f.dart_name()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let declarations = fields
+        .iter()
+        .map(|f| format!("  final {} {};", f.data_type(), f.dart_name()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"class {class_name} {{
+  const {class_name}({{
+{params}
+  }});
+
+{declarations}
+}}
+"#
+    )
+}
+
+fn generate_form_screen_template(
+    config: &ProjectConfig,
+    feature_name: &str,
+    screen_name: &str,
+    fields: &[FormFieldSpec],
+) -> String {
+    let kit = config.ui_kit;
+    let project_name = &config.name;
+    let pascal = pascal_case(screen_name);
+    let controller = camel_case(feature_name);
+    let FormParts {
+        declarations,
+        disposals,
+        widgets,
+    } = render_fields(config, fields, (feature_name, screen_name));
+
+    let data = fields
+        .iter()
+        .map(|f| format!("          {}: {},", f.dart_name(), f.data_expr()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let widgets_import = kit.widgets_import();
+    let components_import = kit.components_import();
+    let form_state = kit.form_state();
+    let validate = kit.validate();
+    let show_error = kit.show_error("'$e'", 8);
+    let l10n_import = l10n::import(config);
+    let lookup = l10n::lookup(config);
+    let scaffold = kit.scaffold(
+        &l10n::text(
+            config,
+            &l10n::key(feature_name, screen_name, "title"),
+            &label(screen_name),
+        ),
+        &format!(
+            r#"{}(
+        key: _formKey,
+        child: ListView(
+          padding: const EdgeInsets.all(16),
+          children: [
+{}
+            const SizedBox(height: 24),
+            {}(
+              onPressed: _isSubmitting ? null : _submit,
+              child: {},
+            ),
+          ],
+        ),
+      )"#,
+            kit.form(),
+            widgets,
+            kit.button(),
+            l10n::text(config, "submit", "Submit")
+        ),
+    );
+
+    format!(
+        r#"{widgets_import}
+import 'package:flutter_riverpod/flutter_riverpod.dart';{components_import}
+import 'package:{project_name}/features/{feature_name}/domain/{screen_name}_form_data.dart';
+import 'package:{project_name}/features/{feature_name}/logic/{feature_name}_controller.dart';{l10n_import}
+
+class {pascal}Screen extends ConsumerStatefulWidget {{
+  const {pascal}Screen({{super.key}});
+
+  @override
+  ConsumerState<{pascal}Screen> createState() => _{pascal}ScreenState();
+}}
+
+class _{pascal}ScreenState extends ConsumerState<{pascal}Screen> {{
+  final _formKey = GlobalKey<{form_state}>();
+{declarations}
+  bool _isSubmitting = false;
+
+  @override
+  void dispose() {{
+{disposals}
+    super.dispose();
+  }}
+
+  Future<void> _submit() async {{
+    if (!_formKey.currentState!.{validate}) return;
+
+    setState(() => _isSubmitting = true);
+    try {{
+      await ref.read({controller}Controller.notifier).submit{pascal}(
+        {pascal}FormData(
+{data}
+        ),
+      );
+    }} catch (e) {{
+      if (mounted) {{
+{show_error}
+      }}
+    }} finally {{
+      if (mounted) setState(() => _isSubmitting = false);
+    }}
+  }}
+
+  @override
+  Widget build(BuildContext context) {{{lookup}
+    return {scaffold};
+  }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_field_specs() {
+        let field = FormFieldSpec::parse("password:password:required,min=8").unwrap();
+        assert_eq!(field.name, "password");
+        assert_eq!(field.input, InputKind::Password);
+        assert_eq!(field.validators, [Validator::Required, Validator::Min(8.0)]);
+
+        let field = FormFieldSpec::parse("code:text:required,regex=^[a-z]{2,4}$").unwrap();
+        assert_eq!(
+            field.validators,
+            [
+                Validator::Required,
+                Validator::Regex("^[a-z]{2,4}$".to_string())
+            ]
+        );
+
+        let field = FormFieldSpec::parse("role:dropdown(admin| editor |):required").unwrap();
+        assert_eq!(
+            field.input,
+            InputKind::Dropdown(vec!["admin".to_string(), "editor".to_string()])
+        );
+        assert_eq!(field.dart_name(), "role");
+
+        assert!(FormFieldSpec::parse("due_at:date").unwrap().dart_name() == "dueAt");
+    }
+
+    #[test]
+    fn rejects_invalid_field_specs() {
+        for spec in [
+            "",
+            "first-name:text",
+            "class:text",
+            "new:text",
+            "2fa:text",
+            "age:slider",
+            "age:number:min=x",
+            "done:checkbox:min=1",
+            "role:dropdown()",
+            "role:dropdown(a|a)",
+            "name:text:unknown",
+        ] {
+            assert!(FormFieldSpec::parse(spec).is_err(), "accepted `{}`", spec);
+        }
+    }
+
+    #[test]
+    fn escapes_dropdown_options() {
+        let field = FormFieldSpec::parse("price:dropdown(it's|$5)").unwrap();
+        let options = field.options(|value, text| format!("{} {}", value, text));
+        assert_eq!(options, "'it\\'s' 'It\\'s'\n'\\$5' '\\$5'");
+    }
+
+    #[test]
+    fn regex_literal_survives_quotes() {
+        assert_eq!(raw_string(r"^\d+$"), r"r'^\d+$'");
+        assert_eq!(raw_string("^'a'$"), r#"r"^'a'$""#);
+        assert_eq!(raw_string(r#"^['"]\d$"#), r#"'^[\'"]\\d\$'"#);
+    }
+
+    #[test]
+    fn inserts_controller_method_once_per_import() {
+        let source = "import 'a.dart';\n\nclass A {\n  void a() {}\n}\n";
+        let updated = insert_method(source, "import 'b.dart';", "  void b() {}\n");
+        assert_eq!(
+            updated,
+            "import 'a.dart';\nimport 'b.dart';\n\nclass A {\n  void a() {}\n\n  void b() {}\n}\n"
+        );
+        let again = insert_method(&updated, "import 'b.dart';", "  void c() {}\n");
+        assert_eq!(again.matches("import 'b.dart';").count(), 1);
+    }
+}
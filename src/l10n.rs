@@ -0,0 +1,270 @@
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::crud::model_form_fields;
+use crate::dart;
+use crate::form::label;
+use crate::project::{ProjectConfig, ScreenKind};
+use crate::{camel_case, pascal_case};
+
+/// Locale of `app_en.arb`, the template the other ARB files translate
+pub const TEMPLATE_LOCALE: &str = "en";
+
+const L10N_YAML: &str = r#"arb-dir: lib/l10n
+template-arb-file: app_en.arb
+output-localization-file: app_localizations.dart
+synthetic-package: false
+nullable-getter: false
+untranslated-messages-file: untranslated_messages.json
+"#;
+
+// Declared locales behind the template locale, without duplicates
+pub fn with_template(locales: Vec<String>) -> Vec<String> {
+    let mut all = vec![TEMPLATE_LOCALE.to_string()];
+    for locale in locales {
+        if !all.contains(&locale) {
+            all.push(locale);
+        }
+    }
+    all
+}
+
+// Locales as ARB files name them, `de`, `pt_BR` or `zh_Hant`
+pub fn validate_locales(locales: &[String]) -> Result<()> {
+    for locale in locales {
+        let mut parts = locale.split('_');
+        let language = parts.next().unwrap_or_default();
+        let valid_language =
+            (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase());
+        let valid_rest = parts.all(|part| {
+            let region = part.len() == 2 && part.chars().all(|c| c.is_ascii_uppercase());
+            let area = part.len() == 3 && part.chars().all(|c| c.is_ascii_digit());
+            let script = part.len() == 4
+                && part.starts_with(|c: char| c.is_ascii_uppercase())
+                && part[1..].chars().all(|c| c.is_ascii_lowercase());
+            region || area || script
+        });
+        if !valid_language || !valid_rest {
+            bail!(
+                "Invalid locale `{}`, use a language code with an optional region such as `de` or `pt_BR`",
+                locale
+            );
+        }
+    }
+    Ok(())
+}
+
+pub fn is_localized(config: &ProjectConfig) -> bool {
+    !config.locales.is_empty()
+}
+
+// Import of `AppLocalizations` on its own line after a newline
+pub fn import(config: &ProjectConfig) -> String {
+    if is_localized(config) {
+        format!(
+            "\nimport 'package:{}/l10n/app_localizations.dart';",
+            config.name
+        )
+    } else {
+        String::new()
+    }
+}
+
+// Statement a `build` method starts with to look messages up as `l10n.<key>`
+pub fn lookup(config: &ProjectConfig) -> &'static str {
+    if is_localized(config) {
+        "\n    final l10n = AppLocalizations.of(context);"
+    } else {
+        ""
+    }
+}
+
+// ARB key of a message on a feature's screen, e.g. `registerSignUpEmailLabel`.
+// The feature's own screen and screens prefixed with it drop the repetition.
+pub fn key(feature: &str, screen: &str, name: &str) -> String {
+    let screen = if screen == feature {
+        ""
+    } else {
+        screen
+            .strip_prefix(&format!("{}_", feature))
+            .unwrap_or(screen)
+    };
+    let parts: Vec<&str> = [feature, screen, name]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect();
+    camel_case(&parts.join("_"))
+}
+
+// Dart string for a message, looked up when the app is localized
+pub fn message(config: &ProjectConfig, key: &str, text: &str) -> String {
+    if is_localized(config) {
+        format!("l10n.{}", key)
+    } else {
+        dart::string_literal(text)
+    }
+}
+
+// `Text` widget showing a message, const while it is a literal
+pub fn text(config: &ProjectConfig, key: &str, text: &str) -> String {
+    if is_localized(config) {
+        format!("Text(l10n.{})", key)
+    } else {
+        format!("const Text({})", dart::string_literal(text))
+    }
+}
+
+// Every message the generated screens look up, with its English text
+fn messages(config: &ProjectConfig) -> BTreeMap<String, String> {
+    let mut messages = BTreeMap::new();
+    messages.insert("appTitle".to_string(), label(&config.name));
+    messages.insert("submit".to_string(), "Submit".to_string());
+    messages.insert("save".to_string(), "Save".to_string());
+
+    for feature in &config.features {
+        let name = &feature.name;
+        let pascal = pascal_case(name);
+        messages.insert(key(name, name, "title"), pascal.clone());
+        messages.insert(key(name, name, "body"), format!("{}Screen", pascal));
+
+        // CRUD screens are named after the feature rather than the route
+        let list = format!("{}_list", name);
+        if let (Some(model), true) = (
+            config.model(name),
+            feature.root_screen.as_ref() == Some(&list),
+        ) {
+            messages.insert(key(name, "list", "title"), pascal.clone());
+            messages.insert(key(name, "detail", "title"), pascal.clone());
+            messages.insert(key(name, "form", "new_title"), format!("New {}", pascal));
+            messages.insert(key(name, "form", "edit_title"), format!("Edit {}", pascal));
+            for field in model_form_fields(model) {
+                messages.insert(
+                    key(name, "form", &format!("{}_label", field.name)),
+                    label(&field.name),
+                );
+            }
+        }
+
+        for screen in feature.screens.iter().filter(|s| s.widget == s.name) {
+            if screen.kind == ScreenKind::Form {
+                messages.insert(key(name, &screen.name, "title"), label(&screen.name));
+                for field in &screen.fields {
+                    messages.insert(
+                        key(name, &screen.name, &format!("{}_label", field.name)),
+                        label(&field.name),
+                    );
+                }
+            } else {
+                let pascal = pascal_case(&screen.name);
+                messages.insert(key(name, &screen.name, "title"), pascal.clone());
+                messages.insert(key(name, &screen.name, "body"), format!("{}Screen", pascal));
+            }
+        }
+    }
+
+    messages
+}
+
+// Adds missing messages to the template ARB file. Messages that are already
+// there keep their text, so edits survive.
+pub fn write_arb_files(project_dir: &Path, config: &ProjectConfig) -> Result<()> {
+    if !is_localized(config) {
+        return Ok(());
+    }
+
+    let l10n_yaml = project_dir.join("l10n.yaml");
+    if !l10n_yaml.exists() {
+        fs::write(&l10n_yaml, L10N_YAML)?;
+    }
+
+    add_messages(project_dir, config, &messages(config))
+}
+
+fn arb_path(project_dir: &Path, locale: &str) -> PathBuf {
+    project_dir
+        .join("lib")
+        .join("l10n")
+        .join(format!("app_{}.arb", locale))
+}
+
+pub fn read_arb(project_dir: &Path, locale: &str) -> Result<Map<String, Value>> {
+    let path = arb_path(project_dir, locale);
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display())),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Map::new()),
+        Err(err) => Err(err).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+// Messages are only added to the template, gen-l10n lists the ones other
+// locales still lack in its untranslated messages file
+pub fn add_messages(
+    project_dir: &Path,
+    config: &ProjectConfig,
+    messages: &BTreeMap<String, String>,
+) -> Result<()> {
+    fs::create_dir_all(project_dir.join("lib").join("l10n"))?;
+    for locale in &config.locales {
+        let mut arb = read_arb(project_dir, locale)?;
+        arb.insert("@@locale".to_string(), Value::String(locale.clone()));
+        if locale == TEMPLATE_LOCALE {
+            for (key, text) in messages {
+                arb.entry(key.clone())
+                    .or_insert_with(|| Value::String(text.clone()));
+            }
+        }
+        fs::write(
+            arb_path(project_dir, locale),
+            serde_json::to_string_pretty(&arb)? + "\n",
+        )?;
+    }
+    Ok(())
+}
+
+// Regenerates `AppLocalizations` from the ARB files
+pub fn gen_l10n(project_dir: &Path) -> Result<()> {
+    Command::new("flutter")
+        .current_dir(project_dir)
+        .arg("gen-l10n")
+        .status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_escaped_without_localization() {
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "name": "app",
+            "package": "com.example",
+            "use_riverpod": true,
+            "use_supabase": false,
+            "features": [{ "name": "home", "layers": [] }],
+        }))
+        .unwrap();
+        assert_eq!(
+            message(&config, "price", "Costs $5, it's cheap"),
+            r"'Costs \$5, it\'s cheap'"
+        );
+        assert_eq!(text(&config, "title", "Tom's"), r"const Text('Tom\'s')");
+    }
+
+    #[test]
+    fn only_missing_arb_files_read_as_empty() {
+        let dir = std::env::temp_dir().join(format!("flutter_gen_arb_{}", std::process::id()));
+        assert!(read_arb(&dir, "en").unwrap().is_empty());
+
+        // A directory in place of the file fails to read
+        fs::create_dir_all(arb_path(&dir, "en")).unwrap();
+        assert!(read_arb(&dir, "en").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
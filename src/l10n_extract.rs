@@ -0,0 +1,783 @@
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::l10n;
+use crate::project::ProjectConfig;
+
+// Widgets whose first argument is the text they show
+const TEXT_WIDGETS: [&str; 2] = ["Text", "SelectableText"];
+
+// Named arguments that take a string shown to the user
+const TEXT_ARGUMENTS: [&str; 7] = [
+    "labelText",
+    "hintText",
+    "helperText",
+    "errorText",
+    "tooltip",
+    "placeholder",
+    "semanticLabel",
+];
+
+// Words of a string that make it into its key, `Forgot your password?`
+// becomes `forgotYourPassword`
+const KEY_WORDS: usize = 4;
+
+/// Outcome of moving hard-coded strings into the template ARB file
+pub struct Extraction {
+    /// New ARB keys with their English text
+    pub messages: BTreeMap<String, String>,
+    /// Call sites that look their text up now
+    pub rewritten: usize,
+    /// Strings left in place or worth a second look, with their location
+    pub ambiguous: Vec<String>,
+    /// Locales that have yet to translate the new keys
+    pub untranslated: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Word(String),
+    Text(Literal),
+    Symbol(char),
+}
+
+#[derive(Debug, PartialEq)]
+struct Literal {
+    value: String,
+    interpolated: bool,
+}
+
+struct Token {
+    kind: Kind,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    fn is_word(&self, word: &str) -> bool {
+        matches!(&self.kind, Kind::Word(w) if w == word)
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        self.kind == Kind::Symbol(symbol)
+    }
+}
+
+// Bracket the parser is inside of
+struct Scope {
+    token: usize,
+    callee: Option<String>,
+    // `const` keyword making the call or collection constant
+    constant: Option<usize>,
+    build: bool,
+}
+
+// String found in a `build` method that can be looked up instead
+struct Occurrence {
+    token: usize,
+    build: usize,
+    constants: Vec<usize>,
+}
+
+struct Scan {
+    tokens: Vec<Token>,
+    occurrences: Vec<Occurrence>,
+    // Closing brace of every `build` body by its opening one
+    builds: BTreeMap<usize, usize>,
+    // Token of a string that stays as it is, with the reason
+    skipped: Vec<(usize, &'static str)>,
+}
+
+/// Moves the strings of `Text` widgets and text arguments on the feature
+/// screens into `app_en.arb` and rewrites them to `l10n.<key>` lookups
+pub fn extract(project_dir: &Path) -> Result<Extraction> {
+    let config = ProjectConfig::load(project_dir)?;
+    if !l10n::is_localized(&config) {
+        bail!("The project is not localized, create it with `--locales` to extract strings");
+    }
+
+    // Keys of the template that hold plain messages, not `@` metadata
+    let mut known: BTreeMap<String, String> = l10n::read_arb(project_dir, l10n::TEMPLATE_LOCALE)?
+        .into_iter()
+        .filter(|(key, _)| !key.starts_with('@'))
+        .filter_map(|(key, value)| match value {
+            Value::String(text) => Some((key, text)),
+            _ => None,
+        })
+        .collect();
+    let mut extraction = Extraction {
+        messages: BTreeMap::new(),
+        rewritten: 0,
+        ambiguous: Vec::new(),
+        untranslated: Vec::new(),
+    };
+
+    for (feature, path) in screen_files(project_dir)? {
+        let source = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let screen = stem.strip_suffix("_screen").unwrap_or(stem);
+        let location = |token: &Token| {
+            format!(
+                "{}:{}",
+                path.strip_prefix(project_dir).unwrap_or(&path).display(),
+                source[..token.start].matches('\n').count() + 1
+            )
+        };
+
+        let scan = scan(&source);
+        for (token, reason) in &scan.skipped {
+            let token = &scan.tokens[*token];
+            extraction.ambiguous.push(format!(
+                "{} {} {}",
+                location(token),
+                &source[token.start..token.end],
+                reason
+            ));
+        }
+        if scan.occurrences.is_empty() {
+            continue;
+        }
+
+        let mut edits = Vec::new();
+        let mut constants = BTreeSet::new();
+        let mut builds = BTreeSet::new();
+        for occurrence in &scan.occurrences {
+            let token = &scan.tokens[occurrence.token];
+            let Kind::Text(literal) = &token.kind else {
+                continue;
+            };
+            let base = l10n::key(&feature, screen, &key_name(&literal.value));
+            let key = unique_key(&base, &literal.value, &known);
+            if !known.contains_key(&key) {
+                if key != base {
+                    extraction.ambiguous.push(format!(
+                        "{} `{}` holds another message, used `{}`",
+                        location(token),
+                        base,
+                        key
+                    ));
+                }
+                if let Some((other, _)) = known.iter().find(|(_, text)| **text == literal.value) {
+                    extraction.ambiguous.push(format!(
+                        "{} `{}` repeats the message of `{}`",
+                        location(token),
+                        key,
+                        other
+                    ));
+                }
+                known.insert(key.clone(), literal.value.clone());
+                extraction
+                    .messages
+                    .insert(key.clone(), literal.value.clone());
+            }
+            edits.push((token.start, token.end, format!("l10n.{}", key)));
+            constants.extend(occurrence.constants.iter().copied());
+            builds.insert(occurrence.build);
+        }
+
+        // `l10n` is not a constant, so neither is anything around it
+        for constant in constants {
+            let start = scan.tokens[constant].start;
+            edits.push((start, scan.tokens[constant + 1].start, String::new()));
+        }
+        for build in builds {
+            let open = &scan.tokens[build];
+            let close = &scan.tokens[scan.builds[&build]];
+            if !source[open.end..close.start].contains("final l10n =") {
+                edits.push((open.end, open.end, l10n::lookup(&config).to_string()));
+            }
+        }
+        if !source.contains("l10n/app_localizations.dart") {
+            let at = imports_end(&source);
+            let import = l10n::import(&config);
+            let import = if at == 0 {
+                format!("{}\n", import.trim_start())
+            } else {
+                import
+            };
+            edits.push((at, at, import));
+        }
+
+        extraction.rewritten += scan.occurrences.len();
+        fs::write(&path, apply(&source, edits))?;
+    }
+
+    // Other locales are left to translators rather than filled with English
+    l10n::add_messages(project_dir, &config, &extraction.messages)?;
+    if !extraction.messages.is_empty() {
+        extraction.untranslated = config
+            .locales
+            .into_iter()
+            .filter(|locale| locale != l10n::TEMPLATE_LOCALE)
+            .collect();
+    }
+    Ok(extraction)
+}
+
+// Screens directly in a feature's `presentation` folder, by feature
+fn screen_files(project_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let features = project_dir.join("lib").join("features");
+    let mut files = Vec::new();
+    for feature in sorted_entries(&features)? {
+        let Some(name) = feature.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let presentation = feature.join("presentation");
+        if !presentation.is_dir() {
+            continue;
+        }
+        for file in sorted_entries(&presentation)? {
+            if file.extension().is_some_and(|ext| ext == "dart") {
+                files.push((name.to_string(), file));
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect::<Vec<_>>();
+    entries.sort();
+    Ok(entries)
+}
+
+// Snake case name of a message from its first words
+fn key_name(text: &str) -> String {
+    let words: Vec<String> = text
+        .replace(['\'', '’'], "")
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(KEY_WORDS)
+        .map(|word| word.to_ascii_lowercase())
+        .collect();
+    if words.is_empty() {
+        "text".to_string()
+    } else {
+        words.join("_")
+    }
+}
+
+// Key for `text`, numbered when `base` already holds a different message
+fn unique_key(base: &str, text: &str, known: &BTreeMap<String, String>) -> String {
+    let mut key = base.to_string();
+    let mut number = 1;
+    while known.get(&key).is_some_and(|existing| existing != text) {
+        number += 1;
+        key = format!("{}{}", base, number);
+    }
+    key
+}
+
+// Position after the last import directive
+fn imports_end(source: &str) -> usize {
+    let mut offset = 0;
+    let mut end = 0;
+    for line in source.split_inclusive('\n') {
+        if line.starts_with("import ") {
+            end = source[offset..]
+                .find(';')
+                .map_or(offset + line.trim_end().len(), |semicolon| {
+                    offset + semicolon + 1
+                });
+        }
+        offset += line.len();
+    }
+    end
+}
+
+// Applies `(start, end, replacement)` edits that do not overlap
+fn apply(source: &str, mut edits: Vec<(usize, usize, String)>) -> String {
+    let mut result = source.to_string();
+    edits.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+    for (start, end, replacement) in edits {
+        result.replace_range(start..end, &replacement);
+    }
+    result
+}
+
+fn scan(source: &str) -> Scan {
+    let tokens = tokenize(source);
+    let mut scan = Scan {
+        tokens: Vec::new(),
+        occurrences: Vec::new(),
+        builds: BTreeMap::new(),
+        skipped: Vec::new(),
+    };
+    let mut stack: Vec<Scope> = Vec::new();
+    let mut last_closed = None;
+    // Depth of a `const` declaration the parser is inside of
+    let mut constant_declaration = None;
+
+    for (k, token) in tokens.iter().enumerate() {
+        match &token.kind {
+            Kind::Symbol(open @ ('(' | '[' | '{')) => {
+                let (callee, before) = callee(&tokens, k);
+                let constant = before
+                    .checked_sub(1)
+                    .filter(|&c| tokens[c].is_word("const"));
+                let build = *open == '{'
+                    && k > 0
+                    && tokens[k - 1].is_symbol(')')
+                    && last_closed.is_some_and(|paren: usize| {
+                        paren >= 2
+                            && tokens[paren - 1].is_word("build")
+                            && tokens[paren - 2].is_word("Widget")
+                    });
+                stack.push(Scope {
+                    token: k,
+                    callee,
+                    constant,
+                    build,
+                });
+            }
+            Kind::Symbol(')' | ']' | '}') => {
+                if let Some(scope) = stack.pop() {
+                    if tokens[scope.token].is_symbol('(') {
+                        last_closed = Some(scope.token);
+                    }
+                    if scope.build {
+                        scan.builds.insert(scope.token, k);
+                    }
+                }
+                if constant_declaration.is_some_and(|depth| stack.len() < depth) {
+                    constant_declaration = None;
+                }
+            }
+            Kind::Symbol(';') if constant_declaration == Some(stack.len()) => {
+                constant_declaration = None;
+            }
+            Kind::Word(word) if word == "const" && is_declaration(&tokens, k) => {
+                constant_declaration = Some(stack.len());
+            }
+            Kind::Text(literal) => {
+                let prev = k.checked_sub(1).map(|p| &tokens[p]);
+                let widget = prev.is_some_and(|p| p.is_symbol('('))
+                    && stack.last().is_some_and(|scope| {
+                        scope.token == k - 1
+                            && scope
+                                .callee
+                                .as_deref()
+                                .is_some_and(|callee| TEXT_WIDGETS.contains(&callee))
+                    });
+                let argument = prev.is_some_and(|p| p.is_symbol(':'))
+                    && k >= 2
+                    && TEXT_ARGUMENTS
+                        .iter()
+                        .any(|argument| tokens[k - 2].is_word(argument));
+                if !widget && !argument {
+                    continue;
+                }
+
+                // Empty strings and bare interpolations have nothing to translate
+                if literal.value.trim().is_empty() {
+                    continue;
+                }
+                let build = stack.iter().rposition(|scope| scope.build);
+                let alone = tokens
+                    .get(k + 1)
+                    .is_some_and(|next| next.is_symbol(',') || next.is_symbol(')'));
+                let reason = if !alone {
+                    Some("is part of a longer expression")
+                } else if literal.interpolated {
+                    Some("interpolates values, add it with a placeholder")
+                } else if literal.value.contains(['{', '}']) {
+                    Some("has braces, which ARB reads as placeholders")
+                } else if build.is_none() {
+                    Some("is outside a build method, which has no context to look it up")
+                } else if constant_declaration.is_some() {
+                    Some("is in a const declaration")
+                } else {
+                    None
+                };
+                match (reason, build) {
+                    (None, Some(build)) => scan.occurrences.push(Occurrence {
+                        token: k,
+                        build: stack[build].token,
+                        constants: stack[build..]
+                            .iter()
+                            .filter_map(|scope| scope.constant)
+                            .collect(),
+                    }),
+                    (Some(reason), _) => scan.skipped.push((k, reason)),
+                    (None, None) => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    scan.tokens = tokens;
+    scan
+}
+
+// Name called with the bracket at `open`, such as `Text` or `Text.rich`,
+// and the index of its first token
+fn callee(tokens: &[Token], open: usize) -> (Option<String>, usize) {
+    let mut start = open;
+    // Type arguments, as in `<Widget>[` or `Provider<int>(`
+    let arrow = start >= 2
+        && tokens[start - 2].is_symbol('=')
+        && tokens[start - 2].end == tokens[start - 1].start;
+    if start > 0 && tokens[start - 1].is_symbol('>') && !arrow {
+        let mut depth = 0;
+        while start > 0 {
+            start -= 1;
+            if tokens[start].is_symbol('>') {
+                depth += 1;
+            } else if tokens[start].is_symbol('<') {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+    }
+    let end = start;
+    while start > 0 && matches!(tokens[start - 1].kind, Kind::Word(_)) {
+        start -= 1;
+        if start < 2 || !tokens[start - 1].is_symbol('.') {
+            break;
+        }
+        start -= 1;
+    }
+    if start == end || tokens[start].is_word("const") {
+        return (None, end);
+    }
+    let name = tokens[start..end]
+        .iter()
+        .map(|token| match &token.kind {
+            Kind::Word(word) => word.as_str(),
+            _ => ".",
+        })
+        .collect::<String>();
+    (Some(name), start)
+}
+
+// `const` starting a declaration like `const title = ...` rather than a call
+fn is_declaration(tokens: &[Token], at: usize) -> bool {
+    let mut k = at + 1;
+    while tokens
+        .get(k)
+        .is_some_and(|token| matches!(token.kind, Kind::Word(_)))
+    {
+        k += 1;
+    }
+    k > at + 1 && tokens.get(k).is_some_and(|token| token.is_symbol('='))
+}
+
+// Words, strings and symbols of Dart source without whitespace and comments
+fn tokenize(source: &str) -> Vec<Token> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if source[i..].starts_with("//") {
+            i = source[i..].find('\n').map_or(bytes.len(), |end| i + end);
+        } else if source[i..].starts_with("/*") {
+            i = source[i + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |end| i + 2 + end + 2);
+        } else if c == b'r' && matches!(bytes.get(i + 1), Some(b'\'' | b'"')) {
+            let (end, literal) = string(source, i + 1, true);
+            i = end;
+            tokens.push(Token {
+                kind: Kind::Text(literal),
+                start,
+                end,
+            });
+        } else if c == b'\'' || c == b'"' {
+            let (end, literal) = string(source, i, false);
+            i = end;
+            tokens.push(Token {
+                kind: Kind::Text(literal),
+                start,
+                end,
+            });
+        } else if c.is_ascii_alphanumeric() || c == b'_' || c == b'$' {
+            while i < bytes.len()
+                && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'$')
+            {
+                i += 1;
+            }
+            tokens.push(Token {
+                kind: Kind::Word(source[start..i].to_string()),
+                start,
+                end: i,
+            });
+        } else {
+            let symbol = source[i..].chars().next().unwrap_or_default();
+            i += symbol.len_utf8();
+            tokens.push(Token {
+                kind: Kind::Symbol(symbol),
+                start,
+                end: i,
+            });
+        }
+    }
+    tokens
+}
+
+// String literal starting with the quote at `start`, with its escapes
+// resolved, and the position after it
+fn string(source: &str, start: usize, raw: bool) -> (usize, Literal) {
+    let bytes = source.as_bytes();
+    let quote = bytes[start];
+    let triple = bytes.get(start + 1) == Some(&quote) && bytes.get(start + 2) == Some(&quote);
+    let delimiter = if triple { 3 } else { 1 };
+    let mut literal = Literal {
+        value: String::new(),
+        interpolated: false,
+    };
+    let mut i = start + delimiter;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c == quote && (!triple || bytes[i..].starts_with(&[quote; 3])) {
+            return (i + delimiter, literal);
+        }
+        if c == b'\n' && !triple {
+            break;
+        }
+        if c == b'\\' && !raw {
+            let escaped = source[i + 1..].chars().next().unwrap_or_default();
+            i += 1 + escaped.len_utf8();
+            match escaped {
+                'n' => literal.value.push('\n'),
+                't' => literal.value.push('\t'),
+                'r' => literal.value.push('\r'),
+                'u' => {
+                    let (digits, len) = match source[i..].strip_prefix('{') {
+                        Some(rest) => {
+                            let close = rest.find('}').unwrap_or(rest.len());
+                            (&rest[..close], close + 2)
+                        }
+                        None => (source.get(i..i + 4).unwrap_or_default(), 4),
+                    };
+                    if let Some(c) = u32::from_str_radix(digits, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                    {
+                        literal.value.push(c);
+                    }
+                    i += len;
+                }
+                other => literal.value.push(other),
+            }
+        } else if c == b'$' && !raw {
+            literal.interpolated = true;
+            i += 1;
+            if bytes.get(i) == Some(&b'{') {
+                let mut depth = 0;
+                while i < bytes.len() {
+                    match bytes[i] {
+                        b'{' => depth += 1,
+                        b'}' => depth -= 1,
+                        _ => {}
+                    }
+                    i += 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            } else {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+            }
+        } else {
+            let c = source[i..].chars().next().unwrap_or_default();
+            literal.value.push(c);
+            i += c.len_utf8();
+        }
+    }
+    // Escapes at the end of an unterminated string step past it
+    (i.min(bytes.len()), literal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: &str = r#"import 'package:flutter/material.dart';
+
+class HomeScreen extends StatelessWidget {
+  const HomeScreen({super.key, required this.name});
+
+  final String name;
+
+  static const tooltip = 'Not shown';
+
+  @override
+  Widget build(BuildContext context) {
+    const banner = Text('Banner');
+
+    return const Column(
+      children: [
+        Text('Hello'), // Text('Comment')
+        Text('Hi $name'),
+        Text('Total: ' + name),
+        Text(''),
+        Text('{count} items'),
+        TextField(decoration: InputDecoration(labelText: 'Email')),
+      ],
+    );
+  }
+}
+
+Widget footer() => const Text('Footer');
+"#;
+
+    fn texts(source: &str) -> Vec<(String, bool)> {
+        tokenize(source)
+            .into_iter()
+            .filter_map(|token| match token.kind {
+                Kind::Text(literal) => Some((literal.value, literal.interpolated)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tokenizes_strings_without_comments() {
+        let source = r#"Text('It\'s') // 'comment'
+/* "block" */ r'\n' "a $b" '${c.d} e' '''one
+two''' 'é\u{1F600}\t'"#;
+        assert_eq!(
+            texts(source),
+            [
+                ("It's".to_string(), false),
+                (r"\n".to_string(), false),
+                ("a ".to_string(), true),
+                (" e".to_string(), true),
+                ("one\ntwo".to_string(), false),
+                ("é😀\t".to_string(), false),
+            ]
+        );
+
+        let tokens = tokenize("Text.rich(x)");
+        assert!(tokens[0].is_word("Text"));
+        assert!(tokens[1].is_symbol('.'));
+        assert_eq!(callee(&tokens, 3), (Some("Text.rich".to_string()), 0));
+    }
+
+    #[test]
+    fn unterminated_strings_end_at_the_line() {
+        let tokens = tokenize("Text('open\nnext");
+        assert_eq!(tokens.len(), 4);
+        assert!(tokens[3].is_word("next"));
+    }
+
+    #[test]
+    fn unterminated_strings_end_at_the_source() {
+        for source in ["'open\\", "'open\\u", "'open\\u{1F6"] {
+            assert_eq!(string(source, 0, false).0, source.len(), "{}", source);
+            assert_eq!(tokenize(source).len(), 1);
+        }
+    }
+
+    #[test]
+    fn finds_the_strings_to_look_up() {
+        let scan = scan(SCREEN);
+        let value = |token: usize| match &scan.tokens[token].kind {
+            Kind::Text(literal) => literal.value.clone(),
+            _ => String::new(),
+        };
+        let found = scan
+            .occurrences
+            .iter()
+            .map(|occurrence| value(occurrence.token))
+            .collect::<Vec<_>>();
+        assert_eq!(found, ["Hello", "Email"]);
+        let skipped = scan
+            .skipped
+            .iter()
+            .map(|(token, reason)| (value(*token), *reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            [
+                ("Banner".to_string(), "is in a const declaration"),
+                (
+                    "Hi ".to_string(),
+                    "interpolates values, add it with a placeholder"
+                ),
+                ("Total: ".to_string(), "is part of a longer expression"),
+                (
+                    "{count} items".to_string(),
+                    "has braces, which ARB reads as placeholders"
+                ),
+                (
+                    "Footer".to_string(),
+                    "is outside a build method, which has no context to look it up"
+                ),
+            ]
+        );
+        assert_eq!(scan.builds.len(), 1);
+    }
+
+    #[test]
+    fn rewrites_lookups_without_const() {
+        let scan = scan(SCREEN);
+        let mut edits = Vec::new();
+        for occurrence in &scan.occurrences {
+            let token = &scan.tokens[occurrence.token];
+            edits.push((token.start, token.end, "l10n.key".to_string()));
+            for &constant in &occurrence.constants {
+                edits.push((
+                    scan.tokens[constant].start,
+                    scan.tokens[constant + 1].start,
+                    String::new(),
+                ));
+            }
+        }
+        edits.sort();
+        edits.dedup();
+        let rewritten = apply(SCREEN, edits);
+        assert!(rewritten.contains("    return Column(\n"));
+        assert!(rewritten.contains("        Text(l10n.key), // Text('Comment')\n"));
+        assert!(rewritten.contains("InputDecoration(labelText: l10n.key)"));
+        assert!(rewritten.contains("const banner = Text('Banner');"));
+        assert!(rewritten.contains("const Text('Footer')"));
+    }
+
+    #[test]
+    fn names_keys_after_the_first_words() {
+        assert_eq!(key_name("Forgot your password?"), "forgot_your_password");
+        assert_eq!(
+            key_name("Don't have an account yet?"),
+            "dont_have_an_account"
+        );
+        assert_eq!(key_name("!!!"), "text");
+
+        let known = BTreeMap::from([
+            ("homeHello".to_string(), "Hello".to_string()),
+            ("homeHello2".to_string(), "Hello!".to_string()),
+        ]);
+        assert_eq!(unique_key("homeHello", "Hello", &known), "homeHello");
+        assert_eq!(unique_key("homeHello", "Hello!", &known), "homeHello2");
+        assert_eq!(unique_key("homeHello", "Hello?", &known), "homeHello3");
+    }
+
+    #[test]
+    fn finds_the_end_of_the_imports() {
+        let source = "import 'a.dart';\nimport 'b.dart'\n    show B;\n\nclass A {}\n";
+        assert_eq!(
+            &source[..imports_end(source)],
+            "import 'a.dart';\nimport 'b.dart'\n    show B;"
+        );
+        assert_eq!(imports_end("class A {}\n"), 0);
+    }
+}
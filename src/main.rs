@@ -1,42 +1,486 @@
-use anyhow::Result;
-use clap::Parser;
+mod crud;
+mod dart;
+mod deep_link;
+mod di;
+mod env;
+mod failure;
+mod flavor;
+mod form;
+mod l10n;
+mod l10n_extract;
+mod model;
+mod native;
+mod navigation;
+mod network;
+mod package;
+mod permission;
+mod platform;
+mod project;
+mod pubspec;
+mod router;
+mod schema;
+mod storage;
+mod supabase;
+mod theme;
+mod tokens;
+mod ui_kit;
+mod widget;
+
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
 use colored::*;
-use inquire::{Confirm, Text};
+use inquire::{Confirm, MultiSelect, Select, Text};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use deep_link::DeepLinks;
+use model::generate_model_template;
+use navigation::{NavStyle, Navigation};
+use package::OptionalPackage;
+use permission::{AppPermission, DeclaredPermission};
+use platform::TargetPlatform;
+use project::{Feature, ProjectConfig};
+use pubspec::Pubspec;
+use router::RouterKind;
+use storage::LocalDatabase;
+use supabase::generate_repository_template;
+use theme::DesignTokens;
+use ui_kit::UiKit;
+use widget::{generate_screen_template, WidgetKind};
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     /// Name of the Flutter project
     #[arg(short, long)]
     name: Option<String>,
+
+    /// Platforms to create, e.g. `android,ios,web`
+    #[arg(long, value_enum, value_delimiter = ',')]
+    platforms: Option<Vec<TargetPlatform>>,
+
+    /// Runtime permissions the app requests, e.g. `camera,location`
+    #[arg(long, value_enum, value_delimiter = ',')]
+    permissions: Option<Vec<AppPermission>>,
+
+    /// Supabase migrations directory or schema dump to generate models from
+    #[arg(long)]
+    schema: Option<PathBuf>,
+
+    /// Features shown as navigation tabs, e.g. `home,search,profile`
+    #[arg(long, value_delimiter = ',')]
+    tabs: Option<Vec<String>>,
+
+    /// Navigation used for the tabs
+    #[arg(long, value_enum)]
+    nav: Option<NavStyle>,
+
+    /// Routing package the generated app uses
+    #[arg(long, value_enum)]
+    router: Option<RouterKind>,
+
+    /// Generate type-safe `@TypedGoRoute` classes with go_router_builder
+    #[arg(long)]
+    typed_routes: bool,
+
+    /// Domain the app handles links from, e.g. `example.com`
+    #[arg(long)]
+    deep_link_host: Option<String>,
+
+    /// Custom URL scheme the app opens, e.g. `myapp`
+    #[arg(long, requires = "deep_link_host")]
+    deep_link_scheme: Option<String>,
+
+    /// Write pubspec.yaml without running `flutter pub get` or build_runner
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Optional packages to add to the defaults, e.g. `sqflite,share_plus`
+    #[arg(long = "with", value_enum, value_delimiter = ',')]
+    with_packages: Vec<OptionalPackage>,
+
+    /// Default packages to leave out, e.g. `flutter_native_splash`
+    #[arg(long = "without", value_enum, value_delimiter = ',')]
+    without_packages: Vec<OptionalPackage>,
+
+    /// Build flavors with their own entrypoint and app id, e.g. `dev,staging,prod`
+    #[arg(long, value_delimiter = ',')]
+    flavors: Option<Vec<String>>,
+
+    /// Return typed failures in a `Result` from services, repositories and controllers
+    #[arg(long)]
+    typed_errors: bool,
+
+    /// Register repositories, data sources and services with get_it
+    #[arg(long)]
+    get_it: bool,
+
+    /// Design system of the app and its generated widgets
+    #[arg(long, value_enum)]
+    ui_kit: Option<UiKit>,
+
+    /// Locales besides English to create ARB files for, e.g. `de,pt_BR`.
+    /// Pass `en` alone for an English-only localized app.
+    #[arg(long, value_delimiter = ',')]
+    locales: Option<Vec<String>>,
+
+    /// Database caching the schema's models for offline use
+    #[arg(long, value_enum)]
+    local_db: Option<LocalDatabase>,
+
+    /// Seed color of the Material 3 theme, e.g. `#6750A4`
+    #[arg(long, conflicts_with = "theme_tokens")]
+    seed_color: Option<String>,
+
+    /// Design tokens JSON with colors, typography, spacing and radii
+    #[arg(long)]
+    theme_tokens: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
 }
 
-#[derive(Debug)]
-struct Feature {
-    name: String,
-    layers: Vec<String>,
+#[derive(Subcommand)]
+enum Commands {
+    /// Add generated code to the project in the current directory
+    Add {
+        #[command(subcommand)]
+        target: AddCommand,
+    },
+    /// Design tokens of the project in the current directory
+    Tokens {
+        #[command(subcommand)]
+        action: TokensCommand,
+    },
+    /// Localization of the project in the current directory
+    L10n {
+        #[command(subcommand)]
+        action: L10nCommand,
+    },
 }
 
-impl Feature {
-    fn new(name: &str) -> Self {
-        Feature {
-            name: name.to_string(),
-            layers: vec![
-                "data".to_string(),
-                "presentation".to_string(),
-                "domain".to_string(),
-                "logic".to_string(),
-            ],
-        }
-    }
+#[derive(Subcommand)]
+enum TokensCommand {
+    /// Regenerate AppColors, AppTypography, AppSpacing, AppRadii and the theme
+    /// from a W3C or Style Dictionary token file
+    Import {
+        /// Token file, e.g. `tokens.json`
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum L10nCommand {
+    /// Move hard-coded strings of the feature screens into `app_en.arb` and
+    /// look them up with `AppLocalizations`
+    Extract,
+}
+
+#[derive(Subcommand)]
+enum AddCommand {
+    /// List, detail and create/edit screens for a feature with a model
+    Crud {
+        /// Feature to generate the screens for
+        feature: String,
+    },
+    /// Form screen with typed, validated fields
+    Form {
+        /// Feature the form belongs to
+        feature: String,
+        /// Name of the form screen, e.g. `sign_up`
+        screen: String,
+        /// Field as `name:type[:rule,...]`, e.g. `password:password:required,min=8`.
+        /// Types: text, email, password, number, date, checkbox, dropdown(a|b).
+        /// Rules: required, min=N, max=N, match=<field>, regex=<pattern> (last).
+        #[arg(short, long = "field")]
+        fields: Vec<String>,
+    },
+    /// Screen routed below its feature
+    Screen {
+        /// Feature the screen belongs to
+        feature: String,
+        /// Name of the screen, e.g. `order_history`
+        screen: String,
+        #[arg(short, long, value_enum, default_value_t = WidgetKind::Stateless)]
+        kind: WidgetKind,
+        /// Route path relative to the feature, defaults to the screen name.
+        /// `:param` segments are passed to the screen.
+        #[arg(long)]
+        path: Option<String>,
+        /// Optional query parameter as `name[:string|int|double|bool]`
+        #[arg(short, long)]
+        query: Vec<String>,
+    },
+    /// Widget in the feature's `presentation/widgets` folder
+    Widget {
+        /// Feature the widget belongs to
+        feature: String,
+        /// Name of the widget, e.g. `order_tile`
+        widget: String,
+        #[arg(short, long, value_enum, default_value_t = WidgetKind::Stateless)]
+        kind: WidgetKind,
+    },
+    /// Shell route with one navigation tab per feature
+    Tabs {
+        /// Features shown as tabs, in order
+        #[arg(required = true, num_args = 2..)]
+        features: Vec<String>,
+        #[arg(long, value_enum, default_value_t = NavStyle::BottomBar)]
+        style: NavStyle,
+    },
+    /// Guards a feature or screen route has to pass
+    Guards {
+        /// Feature or screen route name
+        route: String,
+        /// Guard such as authenticated, guest, onboarded, role:admin|editor or
+        /// flag:<name>, in the order they run. `public` removes all guards.
+        #[arg(required = true)]
+        guards: Vec<String>,
+    },
+    /// Runtime permissions with their AndroidManifest and Info.plist entries
+    Permissions {
+        /// Permission as `name[:rationale]`, e.g. `camera:Scan receipts.`
+        #[arg(required = true)]
+        permissions: Vec<String>,
+        /// Remove the permissions instead
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Display name and minimum Android SDK of the native projects
+    AppInfo {
+        /// Name shown below the app icon
+        #[arg(long)]
+        display_name: Option<String>,
+        /// Oldest Android API level the app supports, e.g. `23`
+        #[arg(long)]
+        min_sdk: Option<u32>,
+    },
+    /// Android app links and iOS universal links for the routes
+    DeepLinks {
+        /// Domain the links are served from, e.g. `example.com`
+        host: String,
+        /// Custom URL scheme, e.g. `myapp`
+        #[arg(long)]
+        scheme: Option<String>,
+    },
+    /// Font family bundled with the app
+    Font {
+        /// Family name used as `fontFamily`, e.g. `Inter`
+        family: String,
+        /// Font file as `path[:weight][:italic]`, e.g. `assets/fonts/Inter-Bold.ttf:700`
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    match cli.command {
+        Some(Commands::Add { target }) => add(target, cli.offline),
+        Some(Commands::Tokens {
+            action: TokensCommand::Import { file },
+        }) => {
+            let skipped = tokens::import_tokens(Path::new("."), &file)?;
+            warn_skipped_tokens(&skipped);
+            println!(
+                "{}",
+                format!("Imported design tokens from {}", file.display()).green()
+            );
+            Ok(())
+        }
+        Some(Commands::L10n {
+            action: L10nCommand::Extract,
+        }) => extract_strings(cli.offline),
+        None => create_project(cli),
+    }
+}
+
+fn add(target: AddCommand, offline: bool) -> Result<()> {
+    let project_dir = Path::new(".");
+    let config = ProjectConfig::load(project_dir)?;
+    let codegen = router::backend(&config).uses_codegen(&config);
+    let pubspec_before = fs::read_to_string(pubspec::pubspec(project_dir)).ok();
+
+    match target {
+        AddCommand::Crud { feature } => {
+            crud::add_crud(project_dir, &feature)?;
+            println!(
+                "{}",
+                format!("Added CRUD screens for feature: {}", feature).green()
+            );
+        }
+        AddCommand::Form {
+            feature,
+            screen,
+            fields,
+        } => {
+            form::add_form(project_dir, &feature, &screen, &fields)?;
+            println!(
+                "{}",
+                format!("Added form screen {} to feature: {}", screen, feature).green()
+            );
+        }
+        AddCommand::Screen {
+            feature,
+            screen,
+            kind,
+            path,
+            query,
+        } => {
+            widget::add_screen(
+                project_dir,
+                &feature,
+                &screen,
+                kind,
+                path.as_deref(),
+                &query,
+            )?;
+            println!(
+                "{}",
+                format!("Added screen {} to feature: {}", screen, feature).green()
+            );
+        }
+        AddCommand::Widget {
+            feature,
+            widget,
+            kind,
+        } => {
+            widget::add_widget(project_dir, &feature, &widget, kind)?;
+            println!(
+                "{}",
+                format!("Added widget {} to feature: {}", widget, feature).green()
+            );
+        }
+        AddCommand::Tabs { features, style } => {
+            let tabs = features.join(", ");
+            navigation::add_tabs(project_dir, features, style)?;
+            println!("{}", format!("Added navigation tabs: {}", tabs).green());
+        }
+        AddCommand::Guards { route, guards } => {
+            router::guard::add_guards(project_dir, &route, &guards)?;
+            println!("{}", format!("Updated guards of route: {}", route).green());
+        }
+        AddCommand::Permissions {
+            permissions,
+            remove,
+        } => {
+            permission::add_permissions(project_dir, &permissions, remove)?;
+            let action = if remove { "Removed" } else { "Added" };
+            println!(
+                "{}",
+                format!("{} permissions: {}", action, permissions.join(", ")).green()
+            );
+        }
+        AddCommand::AppInfo {
+            display_name,
+            min_sdk,
+        } => {
+            if display_name.is_none() && min_sdk.is_none() {
+                bail!("Pass --display-name and/or --min-sdk");
+            }
+            native::add_app_info(project_dir, display_name.as_deref(), min_sdk)?;
+            println!("{}", "Updated the native projects".green());
+        }
+        AddCommand::DeepLinks { host, scheme } => {
+            deep_link::add_deep_links(project_dir, &host, scheme.as_deref())?;
+            println!(
+                "{}",
+                format!(
+                    "Configured deep links for {}. Serve deep_links/.well-known from https://{}",
+                    host, host
+                )
+                .green()
+            );
+        }
+        AddCommand::Font { family, files } => {
+            let fonts = files
+                .iter()
+                .map(|file| pubspec::FontAsset::parse(file))
+                .collect::<Result<Vec<_>>>()?;
+            for font in &fonts {
+                if !project_dir.join(&font.asset).exists() {
+                    println!(
+                        "{}",
+                        format!("Font file {} does not exist yet", font.asset).yellow()
+                    );
+                }
+            }
+            pubspec::add_font(project_dir, &family, &fonts)?;
+            println!("{}", format!("Added font family: {}", family).green());
+        }
+    }
+
+    if offline {
+        return Ok(());
+    }
+    // Packages the command added are resolved before the code using them is built
+    if fs::read_to_string(pubspec::pubspec(project_dir)).ok() != pubspec_before {
+        pubspec::pub_get(project_dir)?;
+    }
+    // Generated routes are only usable once build_runner has run again
+    if codegen {
+        router::run_build_runner(project_dir)?;
+    }
+
+    Ok(())
+}
+
+// Tokens of a token file the theme has no place for
+fn warn_skipped_tokens(skipped: &[String]) {
+    if !skipped.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Skipped tokens without a place in the theme: {}",
+                skipped.join(", ")
+            )
+            .yellow()
+        );
+    }
+}
+
+fn extract_strings(offline: bool) -> Result<()> {
+    let project_dir = Path::new(".");
+    let extraction = l10n_extract::extract(project_dir)?;
+
+    for (key, text) in &extraction.messages {
+        println!("  {}: {}", key, text);
+    }
+    println!(
+        "{}",
+        format!(
+            "Extracted {} strings into {} ARB keys",
+            extraction.rewritten,
+            extraction.messages.len()
+        )
+        .green()
+    );
+    if !extraction.untranslated.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Translate them in the ARB files of {}, gen-l10n lists them in untranslated_messages.json",
+                extraction.untranslated.join(", ")
+            )
+            .yellow()
+        );
+    }
+    if !extraction.ambiguous.is_empty() {
+        println!("{}", "Check these strings:".yellow());
+        for line in &extraction.ambiguous {
+            println!("{}", format!("  {}", line).yellow());
+        }
+    }
+    // `AppLocalizations` only has getters for the new keys once regenerated
+    if !offline && !extraction.messages.is_empty() {
+        l10n::gen_l10n(project_dir)?;
+    }
+    Ok(())
+}
+
+fn create_project(cli: Cli) -> Result<()> {
     // Get project name
     let project_name = match cli.name {
         Some(name) => name,
@@ -50,6 +494,20 @@ fn main() -> Result<()> {
         .with_default("com.example.my_flutter_app")
         .prompt()?;
 
+    // Get target platforms
+    let platforms = match cli.platforms {
+        Some(platforms) => platforms,
+        None => MultiSelect::new(
+            "Which platforms do you want to target?",
+            TargetPlatform::ALL.to_vec(),
+        )
+        .with_default(&[0, 1])
+        .prompt()?,
+    };
+    if platforms.is_empty() {
+        bail!("Select at least one platform");
+    }
+
     // Create Flutter project
     println!("{}", "Creating Flutter project...".green());
     Command::new("flutter")
@@ -59,7 +517,11 @@ fn main() -> Result<()> {
             "--org",
             &package_name,
             "--platforms",
-            "android,ios",
+            &platforms
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
             "--no-pub",
         ])
         .status()?;
@@ -90,23 +552,297 @@ fn main() -> Result<()> {
         .with_default(true)
         .prompt()?;
 
+    // Ask for the routing package
+    let router = match cli.router {
+        Some(router) => router,
+        None if use_riverpod => Select::new(
+            "Which router do you want to use?",
+            vec![RouterKind::GoRouter, RouterKind::AutoRoute],
+        )
+        .prompt()?,
+        None => RouterKind::GoRouter,
+    };
+    // The typed router is a Riverpod provider built from go_router_builder output
+    if cli.typed_routes && router != RouterKind::GoRouter {
+        bail!("Typed routes are generated by go_router_builder, so --typed-routes needs go_router");
+    }
+    if cli.typed_routes && !use_riverpod {
+        bail!("The typed router is a Riverpod provider, so --typed-routes needs Riverpod");
+    }
+    let typed_routes = cli.typed_routes
+        || (use_riverpod
+            && router == RouterKind::GoRouter
+            && Confirm::new("Do you want type-safe routes with go_router_builder?")
+                .with_default(false)
+                .prompt()?);
+
+    // Without Riverpod's providers get_it is the natural container
+    let get_it = cli.get_it
+        || Confirm::new("Do you want to use get_it for dependency injection?")
+            .with_default(!use_riverpod)
+            .prompt()?;
+
+    let ui_kit = match cli.ui_kit {
+        Some(kit) => kit,
+        None => Select::new("Which UI kit do you want to use?", UiKit::ALL.to_vec()).prompt()?,
+    };
+
     let use_supabase = Confirm::new("Do you want to use Supabase?")
         .with_default(false)
         .prompt()?;
 
+    let typed_errors = cli.typed_errors
+        || Confirm::new("Do you want typed failures and a Result type instead of exceptions?")
+            .with_default(false)
+            .prompt()?;
+
+    // Read tables from the Supabase schema
+    let mut models = Vec::new();
+    if use_supabase {
+        let schema_path = match cli.schema {
+            Some(path) => Some(path),
+            None => {
+                let path =
+                    Text::new("Path to Supabase migrations or schema dump (leave empty to skip):")
+                        .with_default("supabase/migrations")
+                        .prompt()?;
+                (!path.trim().is_empty()).then(|| PathBuf::from(path.trim()))
+            }
+        };
+
+        if let Some(path) = schema_path {
+            if path.exists() {
+                models = schema::load_schema(&path)?;
+                for model in &models {
+                    if !features.iter().any(|f| f.name == model.name) {
+                        features.push(Feature::new(&model.name));
+                    }
+                    println!(
+                        "{}",
+                        format!("Added model for table: {}", model.name).green()
+                    );
+                }
+            } else {
+                println!(
+                    "{}",
+                    format!("Schema not found at {}, skipping models", path.display()).yellow()
+                );
+            }
+        }
+    }
+
+    // Ask for navigation tabs
+    let tab_candidates = features
+        .iter()
+        .map(|f| f.name.clone())
+        .filter(|name| !router::AUTH_FEATURES.contains(&name.as_str()))
+        .collect::<Vec<_>>();
+    let tabs = match cli.tabs {
+        Some(tabs) => tabs,
+        None if use_riverpod
+            && tab_candidates.len() >= 2
+            && Confirm::new("Do you want tab navigation?")
+                .with_default(false)
+                .prompt()? =>
+        {
+            MultiSelect::new("Which features should be tabs?", tab_candidates).prompt()?
+        }
+        None => Vec::new(),
+    };
+    let navigation = if tabs.is_empty() {
+        None
+    } else {
+        let style = match cli.nav {
+            Some(style) => style,
+            None => Select::new(
+                "How should the tabs be shown?",
+                vec![NavStyle::BottomBar, NavStyle::Rail],
+            )
+            .prompt()?,
+        };
+        Some(Navigation { style, tabs })
+    };
+
+    // Ask for runtime permissions, the permission utility is a Riverpod notifier
+    let permissions = match cli.permissions {
+        Some(permissions) if !permissions.is_empty() && !use_riverpod => {
+            bail!("The permission utility is a Riverpod notifier, so --permissions needs Riverpod")
+        }
+        Some(permissions) => permissions,
+        None if use_riverpod && platforms.iter().any(|p| p.is_mobile()) => MultiSelect::new(
+            "Which permissions does the app need?",
+            AppPermission::ALL.to_vec(),
+        )
+        .prompt()?,
+        None => Vec::new(),
+    };
+
+    // Ask for app links, they follow the generated routes
+    let deep_links = match cli.deep_link_host {
+        Some(host) => Some(DeepLinks {
+            host,
+            scheme: cli.deep_link_scheme,
+        }),
+        None if Confirm::new("Do you want to configure deep links?")
+            .with_default(false)
+            .prompt()? =>
+        {
+            let host = Text::new("Which domain should open the app?")
+                .with_default("example.com")
+                .prompt()?;
+            let scheme = Text::new("Custom URL scheme (leave empty to skip):").prompt()?;
+            Some(DeepLinks {
+                host,
+                scheme: (!scheme.trim().is_empty()).then(|| scheme.trim().to_string()),
+            })
+        }
+        None => None,
+    };
+    if let Some(links) = &deep_links {
+        deep_link::validate_deep_links(links)?;
+    }
+
+    // Ask for build flavors
+    let flavors = match cli.flavors {
+        Some(flavors) => flavors,
+        None if Confirm::new("Do you want dev, staging and prod flavors?")
+            .with_default(false)
+            .prompt()? =>
+        {
+            vec!["dev".to_string(), "staging".to_string(), "prod".to_string()]
+        }
+        None => Vec::new(),
+    };
+    flavor::validate_flavors(&flavors)?;
+
+    // English is the template locale the other ARB files translate
+    let locales = match cli.locales {
+        Some(locales) => l10n::with_template(locales),
+        None if Confirm::new("Do you want to localize the app with ARB files?")
+            .with_default(false)
+            .prompt()? =>
+        {
+            let others = Text::new("Locales besides English, e.g. `de,fr` (leave empty for none):")
+                .prompt()?;
+            l10n::with_template(
+                others
+                    .split(',')
+                    .map(|locale| locale.trim().to_string())
+                    .filter(|locale| !locale.is_empty())
+                    .collect(),
+            )
+        }
+        None => Vec::new(),
+    };
+    l10n::validate_locales(&locales)?;
+
+    // Ask for optional packages, the flags skip the prompt
+    let available = package::available_packages(&platforms);
+    let packages = if cli.with_packages.is_empty() && cli.without_packages.is_empty() {
+        let defaults = available
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| package::default_packages(&platforms).contains(p))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        MultiSelect::new(
+            "Which optional packages do you want?",
+            available.iter().map(|p| p.label()).collect(),
+        )
+        .with_default(&defaults)
+        .raw_prompt()?
+        .into_iter()
+        .map(|option| available[option.index])
+        .collect()
+    } else {
+        package::selected_packages(&platforms, &cli.with_packages, &cli.without_packages)?
+    };
+
+    // Ask for a local database, it caches the models read from the schema
+    let other_codegen = use_riverpod || typed_routes || router == RouterKind::AutoRoute;
+    let local_database = match cli.local_db {
+        Some(_) if models.is_empty() => {
+            bail!("The local database caches the models of the Supabase schema, but there are none")
+        }
+        Some(db) => Some(db),
+        None if !models.is_empty()
+            && Confirm::new("Do you want a local database for offline caching?")
+                .with_default(false)
+                .prompt()? =>
+        {
+            Some(
+                Select::new(
+                    "Which local database do you want to use?",
+                    LocalDatabase::available(&platforms, other_codegen),
+                )
+                .prompt()?,
+            )
+        }
+        None => None,
+    };
+    if let Some(db) = local_database {
+        if db == LocalDatabase::Isar && other_codegen {
+            bail!("isar_generator needs an older analyzer than riverpod_generator, pick another database or leave out Riverpod");
+        }
+        if !LocalDatabase::available(&platforms, other_codegen).contains(&db) {
+            bail!("{} needs Android or iOS among the platforms", db);
+        }
+    }
+
+    // The theme is generated from design tokens or a seed color
+    let theme = match (&cli.theme_tokens, cli.seed_color) {
+        (Some(path), _) => {
+            let (tokens, skipped) = DesignTokens::read(path)?;
+            warn_skipped_tokens(&skipped);
+            tokens
+        }
+        (None, Some(seed)) => DesignTokens::from_seed(&seed)?,
+        (None, None) => DesignTokens::from_seed(
+            &Text::new("Seed color of the Material 3 theme:")
+                .with_default(theme::DEFAULT_SEED)
+                .prompt()?,
+        )?,
+    };
+
+    let config = ProjectConfig {
+        name: project_name,
+        package: package_name,
+        use_riverpod,
+        use_supabase,
+        platforms,
+        features,
+        models,
+        navigation,
+        router,
+        typed_routes,
+        deep_links,
+        permissions: permissions
+            .into_iter()
+            .map(DeclaredPermission::new)
+            .collect(),
+        flavors,
+        packages,
+        typed_errors,
+        local_database,
+        get_it,
+        ui_kit,
+        locales,
+        theme,
+    };
+    if let Some(navigation) = &config.navigation {
+        navigation::validate_tabs(&config, &navigation.tabs)?;
+    }
+
     // Create project structure
-    create_project_structure(&project_name, &features, use_riverpod, use_supabase)?;
+    create_project_structure(&config, cli.offline)?;
 
     println!("{}", "Project structure created successfully!".green());
     Ok(())
 }
 
-fn create_project_structure(
-    project_name: &str,
-    features: &[Feature],
-    use_riverpod: bool,
-    use_supabase: bool,
-) -> Result<()> {
+fn create_project_structure(config: &ProjectConfig, offline: bool) -> Result<()> {
+    let project_name = config.name.as_str();
+    let features = &config.features;
     let lib_path = Path::new(project_name).join("lib");
 
     // Create base directories
@@ -136,67 +872,113 @@ fn create_project_structure(
         }
 
         // Create basic files for each feature
-        create_feature_files(&feature_path, &feature.name, use_riverpod)?;
+        create_feature_files(&feature_path, &feature.name, config)?;
     }
 
     // Create core files
-    create_core_files(&lib_path, use_supabase, project_name)?;
+    create_core_files(&lib_path, config)?;
 
     // Create app files
-    create_app_files(
-        &lib_path,
-        use_riverpod,
-        use_supabase,
-        features,
-        project_name,
-    )?;
-
-    // Create .env file if using Supabase
-    if use_supabase {
-        fs::write(
-            Path::new(project_name).join(".env"),
-            "SUPABASE_URL=your_supabase_url\nSUPABASE_ANON_KEY=your_supabase_anon_key\n",
-        )?;
+    create_app_files(&lib_path, config)?;
+    router::write_router(Path::new(project_name), config)?;
+    if config.requests_permissions() {
+        permission::write_native_permissions(Path::new(project_name), config, &[])?;
     }
+    flavor::write_flavors(Path::new(project_name), config)?;
+    l10n::write_arb_files(Path::new(project_name), config)?;
+    env::write_env_files(Path::new(project_name), config)?;
+    package::write_tool_configs(Path::new(project_name), config)?;
+
+    // Remember the choices for later commands
+    config.save(Path::new(project_name))?;
 
     // Run flutter pub commands
-    run_flutter_commands(project_name, use_supabase, use_riverpod)?;
+    run_flutter_commands(config, offline)?;
 
     Ok(())
 }
 
-fn create_feature_files(feature_path: &Path, feature_name: &str, use_riverpod: bool) -> Result<()> {
-    // Create basic files
-    let files = vec![
-        (
-            "data",
-            format!("{}_repository.dart", feature_name),
-            format!(
-                "class {}Repository {{\n  // TODO: Implement repository\n}}",
-                pascal_case(feature_name)
-            ),
+fn create_feature_files(
+    feature_path: &Path,
+    feature_name: &str,
+    config: &ProjectConfig,
+) -> Result<()> {
+    // Features backed by a table get a typed model and a Supabase repository,
+    // other features call the API when the project has a client for it
+    let (repository, model) = match config.model(feature_name) {
+        Some(model) if config.local_database.is_some() => (
+            storage::generate_cached_repository_template(config, model),
+            generate_model_template(model),
         ),
-        (
-            "domain",
-            format!("{}_model.dart", feature_name),
+        Some(model) => (
+            generate_repository_template(config, model),
+            generate_model_template(model),
+        ),
+        None => (
+            if config.uses_package(OptionalPackage::Dio) {
+                network::generate_api_repository_template(config, feature_name)
+            } else {
+                format!(
+                    "class {}Repository {{\n  // TODO: Implement repository\n}}",
+                    pascal_case(feature_name)
+                )
+            },
             format!(
                 "class {}Model {{\n  // TODO: Implement model\n}}",
                 pascal_case(feature_name)
             ),
         ),
+    };
+
+    // Create basic files
+    let mut files = vec![
+        (
+            "data",
+            format!("{}_repository.dart", feature_name),
+            repository,
+        ),
+        ("domain", format!("{}_model.dart", feature_name), model),
         (
             "presentation",
             format!("{}_screen.dart", feature_name),
-            generate_screen_template(feature_name),
+            generate_screen_template(
+                config,
+                feature_name,
+                feature_name,
+                WidgetKind::Stateless,
+                &[],
+            ),
         ),
     ];
 
+    // Cached models read through their Supabase table into the local copy
+    if let (Some(model), Some(db)) = (config.model(feature_name), config.local_database) {
+        files.push((
+            "data",
+            format!("{}_remote_data_source.dart", feature_name),
+            supabase::generate_remote_data_source_template(config, model),
+        ));
+        files.push((
+            "data",
+            format!("{}_local_data_source.dart", feature_name),
+            storage::generate_local_data_source_template(config, db, model),
+        ));
+    }
+
     for (dir, filename, content) in files {
         fs::write(feature_path.join(dir).join(filename), content)?;
     }
 
+    // Each feature registers its own data layer with get_it
+    if config.get_it {
+        fs::write(
+            feature_path.join(format!("{}_injection.dart", feature_name)),
+            di::generate_feature_injection_template(config, feature_name),
+        )?;
+    }
+
     // Create controller if using Riverpod
-    if use_riverpod {
+    if config.use_riverpod {
         fs::write(
             feature_path
                 .join("logic")
@@ -208,210 +990,46 @@ fn create_feature_files(feature_path: &Path, feature_name: &str, use_riverpod: b
     Ok(())
 }
 
-fn create_core_files(lib_path: &Path, use_supabase: bool, project_name: &str) -> Result<()> {
-    let mut core_files: Vec<(&str, String)> = vec![
-        (
-            "constants/app_theme.dart",
-            r#"import 'package:flutter/material.dart';
-import 'package:shadcn_ui/shadcn_ui.dart';
-
-class AppColors {
-  AppColors._init();
-  static AppColors instance = AppColors._init();
-
-  final _theme = ShadThemeData(
-    brightness: Brightness.light,
-    colorScheme: const ShadSlateColorScheme.light(),
-  );
-
-  final _themeDark = ShadThemeData(
-    brightness: Brightness.dark,
-    colorScheme: const ShadSlateColorScheme.dark(),
-  );
-
-  ShadThemeData get theme => _theme;
-  ShadThemeData get themeDark => _themeDark;
-}"#
-            .to_string(),
-        ),
-        (
-            "constants/app_colors.dart",
-            "class AppColors {\n  // TODO: Define app colors\n}".to_string(),
-        ),
-        (
+fn create_core_files(lib_path: &Path, config: &ProjectConfig) -> Result<()> {
+    let project_name = config.name.as_str();
+    let mut core_files: Vec<(&str, String)> = vec![(
+        "widgets/custom_button.dart",
+        generate_custom_button_template(config.ui_kit),
+    )];
+    // Localized apps keep their strings in the ARB files instead
+    if !l10n::is_localized(config) {
+        core_files.push((
             "constants/app_strings.dart",
             "class AppStrings {\n  // TODO: Define app strings\n}".to_string(),
-        ),
-        (
-            "utilities/logging.dart",
-            "class Logger {\n  // TODO: Implement logging\n}".to_string(),
-        ),
-        (
-            "utilities/permissions.dart",
-            r#"import 'dart:io' show Platform, exit;
-
-import 'package:device_info_plus/device_info_plus.dart';
-import 'package:flutter/material.dart';
-import 'package:flutter/services.dart';
-import 'package:hooks_riverpod/hooks_riverpod.dart';
-import 'package:permission_handler/permission_handler.dart';
-
-final permissionUtilProvider =
-    StateNotifierProvider<PermissionUtil, bool>((ref) => PermissionUtil());
-
-class PermissionUtil extends StateNotifier<bool> {
-  PermissionUtil() : super(false);
-
-  Future<List<Permission>> get _requiredPermissions async {
-    if (Platform.isAndroid) {
-      if (await _getAndroidSdkVersion() >= 33) {
-        // Android 13 and above
-        return [
-          Permission.photos,
-          Permission.videos,
-          Permission.activityRecognition,
-        ];
-      } else if (await _getAndroidSdkVersion() >= 29) {
-        // Android 10-12
-        return [
-          Permission.storage,
-          Permission.activityRecognition,
-        ];
-      } else {
-        // Below Android 10
-        return [
-          Permission.storage,
-          Permission.activityRecognition,
-        ];
-      }
-    } else if (Platform.isIOS) {
-      return [
-        Permission.photos,
-        Permission.sensors,
-      ];
-    }
-    return [];
-  }
-
-  // Helper method to get Android SDK version
-  Future<int> _getAndroidSdkVersion() async {
-    try {
-      if (Platform.isAndroid) {
-        final deviceInfo = DeviceInfoPlugin();
-        final androidInfo = await deviceInfo.androidInfo;
-        return androidInfo.version.sdkInt;
-      }
-    } catch (e) {
-      print('Error getting Android SDK version: $e');
+        ));
     }
-    return 29; // Default to Android 10 for safety
-  }
-
-  Future<bool> requestPermissions() async {
-    try {
-      final permissions = await _requiredPermissions;
-      if (permissions.isEmpty) return false;
 
-      final statuses = await permissions.request();
-      return statuses.values.every((status) => status.isGranted);
-    } catch (e) {
-      print('Error requesting permissions: $e');
-      return false;
+    core_files.extend(theme::constants_files(config));
+    core_files.extend(package::glue_files(config));
+    core_files.extend(storage::local_database_files(config));
+    if config.typed_errors {
+        core_files.extend(failure::failure_files(config));
     }
-  }
-
-  Future<bool> checkPermissions() async {
-    try {
-      final permissions = await _requiredPermissions;
-      if (permissions.isEmpty) return false;
-
-      final statuses = await Future.wait(
-        permissions.map((permission) => permission.status),
-      );
-      return statuses.every((status) => status.isGranted);
-    } catch (e) {
-      print('Error checking permissions: $e');
-      return false;
+    if config.get_it {
+        core_files.push(("di/injection.dart", di::generate_injection_template(config)));
     }
-  }
 
-  Future<void> openSettings() async {
-    try {
-      await openAppSettings();
-    } catch (e) {
-      print('Error opening settings: $e');
+    // Runtime permissions are only requested on Android and iOS
+    if config.requests_permissions() {
+        core_files.push((
+            "utilities/permissions.dart",
+            permission::generate_permissions_template(config),
+        ));
     }
-  }
-
-  Future<void> checkAndRequestPermissions(
-    BuildContext context,
-    WidgetRef ref,
-  ) async {
-    final hasPermissions = await checkPermissions();
-    if (!hasPermissions) {
-      final granted = await requestPermissions();
-      if (!granted) {
-        // Show dialog if permissions are not granted
-        if (context.mounted) {
-          await showPermissionDialog(context, ref);
-        }
-      } else {
-        state = true;
-      }
-    } else {
-      state = true;
+    if env::uses_env(config) {
+        core_files.push(("constants/env.dart", env::generate_env_template(config)));
     }
-  }
-
-  Future<void> showPermissionDialog(BuildContext context, WidgetRef ref) async {
-    return showDialog(
-      context: context,
-      barrierDismissible: false,
-      builder: (BuildContext context) {
-        return AlertDialog(
-          content: const Text(
-              'This app needs access to storage and activity recognition to track your steps. '
-              'Please grant the required permissions in settings.'),
-          actions: <Widget>[
-            TextButton(
-              child: const Text('Open Settings'),
-              onPressed: () async {
-                Navigator.of(context).pop();
-                await openSettings();
-                if (context.mounted) {
-                  await checkAndRequestPermissions(context, ref);
-                }
-              },
-            ),
-            TextButton(
-              child: const Text('Exit App'),
-              onPressed: () async {
-                try {
-                  await SystemChannels.platform
-                      .invokeMethod('SystemNavigator.pop');
-                } catch (e) {
-                  exit(0);
-                }
-              },
-            ),
-          ],
-        );
-      },
-    );
-  }
-}"#
-            .to_string(),
-        ),
-        (
-            "widgets/custom_button.dart",
-            generate_custom_button_template(),
-        ),
-    ];
 
     // Add auth service files if Supabase is enabled
-    if use_supabase {
-        core_files.push((
-            "services/auth_service.dart",
+    if config.use_supabase {
+        let auth_service = if config.typed_errors {
+            failure::generate_auth_service_template(project_name)
+        } else {
             r#"import 'package:logging/logging.dart';
 import 'package:supabase_flutter/supabase_flutter.dart';
 
@@ -488,324 +1106,127 @@ class AuthService {
 
   // Check if Logged In
   bool isLoggedIn() => supabase.auth.currentUser != null;
+
+  // Token of the current session, sent along with API requests
+  String? get accessToken => supabase.auth.currentSession?.accessToken;
 }"#
-            .to_string(),
-        ));
+            .to_string()
+        };
+        core_files.push(("services/auth_service.dart", auth_service));
+    }
 
+    // The provider hands out get_it's instance when both are used
+    if config.use_supabase && config.use_riverpod {
+        let (client_import, body) = if config.get_it {
+            (
+                di::import(config),
+                "  return getIt<AuthService>();".to_string(),
+            )
+        } else {
+            (
+                "import 'package:supabase_flutter/supabase_flutter.dart';\n".to_string(),
+                "  final supabase = Supabase.instance.client;\n  return AuthService(supabase);"
+                    .to_string(),
+            )
+        };
         core_files.push((
             "services/auth_service_provider.dart",
             format!(
                 r#"import 'package:hooks_riverpod/hooks_riverpod.dart';
 import 'package:riverpod_annotation/riverpod_annotation.dart';
 import 'package:{}/core/services/auth_service.dart';
-import 'package:supabase_flutter/supabase_flutter.dart';
-
+{}
 part 'auth_service_provider.g.dart';
 
 @Riverpod(keepAlive: true)
 AuthService authService(Ref ref) {{
-  final supabase = Supabase.instance.client;
-  return AuthService(supabase);
+{}
 }}"#,
-                project_name
+                project_name, client_import, body
             )
             .to_string(),
         ));
     }
 
     for (path, content) in core_files {
-        fs::write(lib_path.join("core").join(path), content)?;
+        let path = lib_path.join("core").join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
     }
 
     Ok(())
 }
 
-fn create_app_files(
-    lib_path: &Path,
-    use_riverpod: bool,
-    use_supabase: bool,
-    features: &[Feature],
-    project_name: &str,
-) -> Result<()> {
-    let app_files: Vec<(&str, String)> = vec![
-        ("app/app.dart", generate_app_template(use_riverpod)),
-        (
-            "app/router.dart",
-            generate_router_template(project_name, use_riverpod, features),
-        ),
-        (
-            "theme/app_theme.dart",
-            "import 'package:flutter/material.dart';\n\n// TODO: Implement theme".to_string(),
-        ),
-    ];
+fn create_app_files(lib_path: &Path, config: &ProjectConfig) -> Result<()> {
+    let mut app_files: Vec<(String, String)> =
+        vec![("app/app.dart".to_string(), generate_app_template(config))];
+    for (path, content) in theme::theme_files(config) {
+        app_files.push((format!("theme/{}", path), content));
+    }
+
+    if let Some(navigation) = &config.navigation {
+        app_files.push((
+            "app/app_shell.dart".to_string(),
+            navigation::generate_app_shell_template(config, navigation),
+        ));
+    }
 
     for (path, content) in app_files {
         fs::write(lib_path.join(path), content)?;
     }
 
     // Create main.dart
-    fs::write(
-        lib_path.join("main.dart"),
-        generate_main_template(use_supabase),
-    )?;
+    fs::write(lib_path.join("main.dart"), generate_main_template(config))?;
 
     Ok(())
 }
 
-fn generate_router_template(
-    project_name: &str,
-    use_riverpod: bool,
-    features: &[Feature],
-) -> String {
-    if !use_riverpod {
-        return "import 'package:go_router/go_router.dart';\n\n// TODO: Implement router"
-            .to_string();
-    }
-
-    let mut imports = vec![
-        "import 'package:go_router/go_router.dart'".to_string(),
-        "import 'package:hooks_riverpod/hooks_riverpod.dart'".to_string(),
-    ];
+fn run_flutter_commands(config: &ProjectConfig, offline: bool) -> Result<()> {
+    let project_dir = Path::new(&config.name);
 
-    let mut routes = Vec::new();
-    let mut auth_routes = Vec::new();
-    let mut has_auth = false;
+    let (dependencies, dev_dependencies) = package::dependencies(config);
+    let router = router::backend(config);
 
-    // Check for auth-related features
-    for feature in features {
-        match feature.name.as_str() {
-            "auth" | "login" | "register" => {
-                has_auth = true;
-                imports.push(format!(
-                    "import 'package:{}/core/services/auth_service_provider.dart'",
-                    project_name
-                ));
-
-                if feature.name == "login" || feature.name == "auth" {
-                    imports.push(format!(
-                        "import 'package:{}/features/auth/presentation/login_screen.dart'",
-                        project_name
-                    ));
-                    auth_routes.push(
-                        r#"GoRoute(
-    path: 'login',
-    name: 'login',
-    builder: (context, state) => const LoginScreen(),
-),"#
-                        .to_string(),
-                    );
-                }
-                if feature.name == "register" {
-                    imports.push(format!(
-                        "import 'package:{}/features/auth/presentation/register_screen.dart'",
-                        project_name
-                    ));
-                    auth_routes.push(
-                        r#"GoRoute(
-    path: 'register',
-    name: 'register',
-    builder: (context, state) => const RegisterScreen(),
-),"#
-                        .to_string(),
-                    );
-                }
-                if feature.name == "auth" {
-                    imports.push(format!(
-                        "import 'package:{}/features/auth/presentation/forgot_password_screen.dart'",
-                        project_name
-                    ));
-                    auth_routes.push(
-                        r#"GoRoute(
-    path: 'forgot-password',
-    name: 'forgotPassword',
-    builder: (context, state) => const ForgotPasswordScreen(),
-),"#
-                        .to_string(),
-                    );
-                }
-            }
-            "home" => {
-                imports.push(format!(
-                    "import 'package:{}/features/home/presentation/home_screen.dart'",
-                    project_name
-                ));
-                routes.push(
-                    r#"GoRoute(
-    path: '/',
-    name: 'home',
-    builder: (context, state) => const HomeScreen(),
-),"#
-                    .to_string(),
-                );
-            }
-            "profile" => {
-                imports.push(format!(
-                    "import 'package:{}/features/profile/presentation/profile_screen.dart'",
-                    project_name
-                ));
-                routes.push(
-                    r#"GoRoute(
-    path: '/profile',
-    name: 'profile',
-    builder: (context, state) => const ProfileScreen(),
-),"#
-                    .to_string(),
-                );
-            }
-            "settings" => {
-                imports.push(format!(
-                    "import 'package:{}/features/settings/presentation/settings_screen.dart'",
-                    project_name
-                ));
-                routes.push(
-                    r#"GoRoute(
-    path: '/settings',
-    name: 'settings',
-    builder: (context, state) => const SettingsScreen(),
-),"#
-                    .to_string(),
-                );
-            }
-            _ => {}
+    // Versions come from the bundled catalog so no network is needed
+    native::edit(&pubspec::pubspec(project_dir), |pubspec: &mut Pubspec| {
+        for package in &dependencies {
+            pubspec.add_dependency(package)?;
         }
-    }
+        for package in &dev_dependencies {
+            pubspec.add_dev_dependency(package)?;
+        }
+        pubspec.set_flutter("uses-material-design", "true");
+        // gen-l10n turns the ARB files into `AppLocalizations`
+        if l10n::is_localized(config) {
+            pubspec.set_flutter("generate", "true");
+        }
+        Ok(())
+    })?;
+    println!(
+        "{}",
+        format!(
+            "Pinned dependencies from catalog {}",
+            pubspec::CATALOG_VERSION
+        )
+        .green()
+    );
 
-    // Add default home route if not specified
-    if !routes.iter().any(|r| r.contains("path: '/'")) {
-        routes.insert(
-            0,
-            r#"GoRoute(
-    path: '/',
-    name: 'home',
-    builder: (context, state) => const HomeScreen(),
-),"#
-            .to_string(),
+    if offline {
+        println!(
+            "{}",
+            "Skipped flutter pub get, run it and build_runner before building the app".yellow()
         );
-        imports.push(format!(
-            "import 'package:{}/features/home/presentation/home_screen.dart'",
-            project_name
-        ));
-    }
-
-    let auth_redirect = if has_auth {
-        r#"
-    redirect: (context, state) {
-        final isLoggedIn = authService.isLoggedIn();
-        final location = state.matchedLocation;
-        
-        // List of auth-related paths
-        final authPaths = ['/auth', '/auth/login', '/auth/register', '/auth/forgot-password'];
-        
-        // If user is not logged in and trying to access protected routes
-        if (!isLoggedIn && !authPaths.contains(location)) {
-            return '/auth/login';
-        }
-        
-        // If user is logged in and trying to access auth routes
-        if (isLoggedIn && authPaths.contains(location)) {
-            return '/';
-        }
-        
-        return null;
-    },"#
-    } else {
-        ""
-    };
-
-    let auth_service = if has_auth {
-        "final authService = ref.read(authServiceProvider);"
-    } else {
-        ""
-    };
-
-    format!(
-        r#"{}
-
-final goRouterProvider = Provider<GoRouter>((ref) {{
-    {}
-    return GoRouter(
-        initialLocation: '/',{}
-        routes: [
-            {}
-            {}
-            {}
-        ],
-    );
-}});"#,
-        imports.join(";\n"),
-        auth_service,
-        auth_redirect,
-        routes.join(",\n            "),
-        if has_auth {
-            format!(
-                r#"GoRoute(
-                path: '/auth',
-                name: 'auth',
-                builder: (context, state) => const LoginScreen(),
-                routes: [
-                    {}
-                ],
-            ),"#,
-                auth_routes.join("\n                    ")
-            )
-        } else {
-            "".to_string()
-        },
-        if has_auth { "" } else { "," }
-    )
-}
-fn run_flutter_commands(project_name: &str, use_supabase: bool, use_riverpod: bool) -> Result<()> {
-    let project_dir = Path::new(project_name);
-
-    // Base dependencies
-    let mut cmd = Command::new("flutter");
-    cmd.current_dir(project_dir).args([
-        "pub",
-        "add",
-        "connectivity_plus",
-        "device_info_plus",
-        "flutter_background_service",
-        "flutter_dotenv",
-        "flutter_launcher_icons",
-        "flutter_native_splash",
-        "go_router",
-        "logging",
-        "path",
-        "permission_handler",
-        "shadcn_ui",
-        "share_plus",
-        "simple_circular_progress_bar",
-        "sqflite",
-    ]);
-
-    if use_riverpod {
-        cmd.arg("hooks_riverpod");
-        cmd.arg("riverpod_annotation");
-        cmd.arg("flutter_riverpod");
-        cmd.arg("flutter_hooks");
-        cmd.arg("hooks_riverpod");
+        return Ok(());
     }
+    pubspec::pub_get(project_dir)?;
 
-    if use_supabase {
-        cmd.arg("supabase_flutter");
+    // Generated routes and databases live in build_runner output
+    if router.uses_codegen(config) || storage::uses_codegen(config) {
+        router::run_build_runner(project_dir)?;
     }
 
-    cmd.status()?;
-
-    // Dev dependencies
-    Command::new("flutter")
-        .current_dir(project_dir)
-        .args([
-            "pub",
-            "add",
-            "--dev",
-            "build_runner",
-            "flutter_lints",
-            "riverpod_generator",
-            "very_good_analysis",
-        ])
-        .status()?;
-
     Ok(())
 }
 
@@ -827,30 +1248,13 @@ fn pascal_case(s: &str) -> String {
     result
 }
 
-fn generate_screen_template(feature_name: &str) -> String {
-    format!(
-        r#"import 'package:flutter/material.dart';
-
-class {}Screen extends StatelessWidget {{
-  const {}Screen({{super.key}});
-
-  @override
-  Widget build(BuildContext context) {{
-    return Scaffold(
-      appBar: AppBar(
-        title: const Text('{}'),
-      ),
-      body: const Center(
-        child: Text('{}Screen'),
-      ),
-    );
-  }}
-}}"#,
-        pascal_case(feature_name),
-        pascal_case(feature_name),
-        pascal_case(feature_name),
-        pascal_case(feature_name),
-    )
+fn camel_case(s: &str) -> String {
+    let pascal = pascal_case(s);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => pascal,
+    }
 }
 
 fn generate_controller_template(feature_name: &str) -> String {
@@ -870,7 +1274,7 @@ class {}Notifier extends StateNotifier<{}State> {{
 
   // TODO: Implement methods
 }}"#,
-        feature_name,
+        camel_case(feature_name),
         pascal_case(feature_name),
         pascal_case(feature_name),
         pascal_case(feature_name),
@@ -882,41 +1286,134 @@ class {}Notifier extends StateNotifier<{}State> {{
     )
 }
 
-fn generate_custom_button_template() -> String {
-    r#"import 'package:flutter/material.dart';
+fn generate_custom_button_template(kit: UiKit) -> String {
+    let indicator = match kit {
+        UiKit::Cupertino => "const CupertinoActivityIndicator()".to_string(),
+        _ => format!(
+            "const SizedBox.square(\n              dimension: 16,\n              child: {}(strokeWidth: 2),\n            )",
+            kit.progress_indicator().trim_end_matches("()")
+        ),
+    };
+    format!(
+        r#"{}{}
 
-class CustomButton extends StatelessWidget {
+class CustomButton extends StatelessWidget {{
   final String text;
   final VoidCallback onPressed;
   final bool isLoading;
 
-  const CustomButton({
+  const CustomButton({{
     super.key,
     required this.text,
     required this.onPressed,
     this.isLoading = false,
-  });
+  }});
 
   @override
-  Widget build(BuildContext context) {
-    return ElevatedButton(
+  Widget build(BuildContext context) {{
+    return {}(
       onPressed: isLoading ? null : onPressed,
       child: isLoading
-          ? const CircularProgressIndicator()
+          ? {}
           : Text(text),
     );
-  }
-}"#
-    .to_string()
+  }}
+}}"#,
+        kit.widgets_import(),
+        kit.components_import(),
+        kit.button(),
+        indicator
+    )
 }
 
-fn generate_app_template(_use_riverpod: bool) -> String {
+fn generate_app_template(config: &ProjectConfig) -> String {
+    let router_config = router::backend(config).router_config(config);
+    let localized = l10n::is_localized(config);
+    // Material widgets in Cupertino and Fluent apps need Material localizations
+    let localizations = if localized {
+        "\n      localizationsDelegates: AppLocalizations.localizationsDelegates,\n      supportedLocales: AppLocalizations.supportedLocales,\n      onGenerateTitle: (context) => AppLocalizations.of(context).appTitle,"
+    } else if matches!(config.ui_kit, UiKit::Cupertino | UiKit::Fluent) {
+        "\n      localizationsDelegates: const [DefaultMaterialLocalizations.delegate],"
+    } else {
+        ""
+    };
+    let (imports, app) = match config.ui_kit {
+        UiKit::Shadcn => (
+            "import 'package:flutter/material.dart';\nimport 'package:flutter_riverpod/flutter_riverpod.dart';\nimport 'package:shadcn_ui/shadcn_ui.dart';",
+            format!(
+                r#"ShadApp.router(
+      debugShowCheckedModeBanner: false,
+      theme: AppTheme.shadLight,
+      darkTheme: AppTheme.shadDark,
+      themeMode: themeMode,{}
+      materialThemeBuilder: (context, theme) =>
+          theme.brightness == Brightness.dark ? AppTheme.dark : AppTheme.light,
+      routerConfig: {},
+    )"#,
+                localizations, router_config
+            ),
+        ),
+        UiKit::Material => (
+            "import 'package:flutter/material.dart';\nimport 'package:flutter_riverpod/flutter_riverpod.dart';",
+            format!(
+                r#"MaterialApp.router(
+      debugShowCheckedModeBanner: false,
+      theme: AppTheme.light,
+      darkTheme: AppTheme.dark,
+      themeMode: themeMode,{}
+      routerConfig: {},
+    )"#,
+                localizations, router_config
+            ),
+        ),
+        // The Material widgets of the generated screens still get a theme,
+        // localizations and snack bars
+        UiKit::Cupertino => (
+            "import 'package:flutter/cupertino.dart';\nimport 'package:flutter/material.dart';\nimport 'package:flutter_riverpod/flutter_riverpod.dart';",
+            format!(
+                r#"CupertinoApp.router(
+      debugShowCheckedModeBanner: false,
+      theme: AppTheme.cupertino(themeMode),{}
+      builder: (context, child) => Theme(
+        data: CupertinoTheme.brightnessOf(context) == Brightness.dark
+            ? AppTheme.dark
+            : AppTheme.light,
+        child: ScaffoldMessenger(child: child!),
+      ),
+      routerConfig: {},
+    )"#,
+                localizations, router_config
+            ),
+        ),
+        UiKit::Fluent => (
+            if localized {
+                "import 'package:fluent_ui/fluent_ui.dart';\nimport 'package:flutter/material.dart'\n    show ScaffoldMessenger, Theme, ThemeMode;\nimport 'package:flutter_riverpod/flutter_riverpod.dart';"
+            } else {
+                "import 'package:fluent_ui/fluent_ui.dart';\nimport 'package:flutter/material.dart'\n    show DefaultMaterialLocalizations, ScaffoldMessenger, Theme, ThemeMode;\nimport 'package:flutter_riverpod/flutter_riverpod.dart';"
+            },
+            format!(
+                r#"FluentApp.router(
+      debugShowCheckedModeBanner: false,
+      theme: AppTheme.fluentLight,
+      darkTheme: AppTheme.fluentDark,
+      themeMode: themeMode,{}
+      builder: (context, child) => Theme(
+        data: FluentTheme.of(context).brightness == Brightness.dark
+            ? AppTheme.dark
+            : AppTheme.light,
+        child: ScaffoldMessenger(child: child!),
+      ),
+      routerConfig: {},
+    )"#,
+                localizations, router_config
+            ),
+        ),
+    };
+
     format!(
-        r#"import 'package:flutter/material.dart';
-import 'package:flutter_riverpod/flutter_riverpod.dart';
-import 'package:go_router/go_router.dart';
-import 'package:shadcn_ui/shadcn_ui.dart';
-import '../core/constants/app_colors.dart';
+        r#"{}{}
+import '../theme/app_theme.dart';
+import 'router.dart';
 
 final themeModeProvider = StateProvider<ThemeMode>((ref) => ThemeMode.dark);
 
@@ -926,40 +1423,89 @@ class App extends ConsumerWidget {{
   @override
   Widget build(BuildContext context, WidgetRef ref) {{
     final themeMode = ref.watch(themeModeProvider);
-    final goRouter = ref.watch(goRouterProvider);
 
-    return ShadApp.router(
-      debugShowCheckedModeBanner: false,
-      darkTheme: AppColors.instance.themeDark,
-      theme: AppColors.instance.theme,
-      themeMode: themeMode,
-      routerConfig: goRouter,
-    );
+    return {};
   }}
-}}"#
+}}"#,
+        imports,
+        if localized {
+            "\nimport '../l10n/app_localizations.dart';"
+        } else {
+            ""
+        },
+        app
     )
 }
 
-fn generate_main_template(use_supabase: bool) -> String {
-    let supabase_imports = if use_supabase {
-        "import 'package:flutter_dotenv/flutter_dotenv.dart';
-import 'package:supabase_flutter/supabase_flutter.dart';"
-    } else {
-        ""
-    };
-
-    let supabase_init = if use_supabase {
-        r#"  // Ensure Flutter binding is initialized
-  WidgetsFlutterBinding.ensureInitialized();
-  // Load .env file
-  await dotenv.load();
-  // Supabase init
+fn generate_main_template(config: &ProjectConfig) -> String {
+    let uses_firebase = config.uses_package(OptionalPackage::FirebaseAnalytics);
+    let mut imports = String::new();
+    let mut init = String::new();
+    if uses_firebase {
+        imports.push_str("import 'package:firebase_core/firebase_core.dart';\n");
+    }
+    if config.use_supabase {
+        imports.push_str("import 'package:supabase_flutter/supabase_flutter.dart';\n");
+    }
+    if config.uses_package(OptionalPackage::Logging) {
+        imports.push_str("import 'core/utilities/logging.dart';\n");
+        init.push_str("  setupLogging();\n");
+    }
+    if env::uses_env(config) {
+        imports.push_str("import 'core/constants/env.dart';\n");
+        // Flavors load their own env file
+        let env_file = if config.flavors.is_empty() {
+            ""
+        } else {
+            "fileName: flavor.envFile"
+        };
+        init.push_str(&format!(
+            "  // Load .env file, fails when a required key is missing\n  await Env.load({});\n",
+            env_file
+        ));
+    }
+    if uses_firebase {
+        init.push_str(
+            "  // Uses the config files of `flutterfire configure`\n  await Firebase.initializeApp();\n",
+        );
+    }
+    if config.use_supabase {
+        init.push_str(
+            r#"  // Supabase init
   await Supabase.initialize(
-    url: dotenv.env['SUPABASE_URL'] ?? '',
-    anonKey: dotenv.env['SUPABASE_ANON_KEY'] ?? '',
-  );"#
-    } else {
-        ""
+    url: Env.supabaseUrl,
+    anonKey: Env.supabaseAnonKey,
+  );
+"#,
+        );
+    }
+    let opens_database = config.local_database.is_some_and(|db| db.needs_init());
+    if opens_database {
+        imports.push_str("import 'core/services/local_database.dart';\n");
+        init.push_str("  // Open the local database\n  await LocalDatabase.init();\n");
+    }
+    if config.get_it {
+        imports.push_str("import 'core/di/injection.dart';\n");
+        init.push_str("  // Register services and repositories\n  configureDependencies();\n");
+    }
+    // Plugins are only usable once the binding is initialized
+    if env::uses_env(config) || uses_firebase || opens_database {
+        init.insert_str(
+            0,
+            "  // Ensure Flutter binding is initialized\n  WidgetsFlutterBinding.ensureInitialized();\n",
+        );
+    }
+
+    // Each `main_<flavor>.dart` bootstraps the app with its flavor
+    let (signature, entrypoint) = match config.flavors.first() {
+        Some(flavor) => {
+            imports.push_str("import 'core/constants/flavor_config.dart';\n");
+            (
+                "Future<void> bootstrap(Flavor flavor) async {\n  FlavorConfig.current = flavor;",
+                format!("\n\nvoid main() => bootstrap(Flavor.{});", flavor),
+            )
+        }
+        None => ("void main() async {", String::new()),
     };
 
     format!(
@@ -967,15 +1513,13 @@ import 'package:supabase_flutter/supabase_flutter.dart';"
 import 'package:flutter_riverpod/flutter_riverpod.dart';
 import 'app/app.dart';
 {}
-
-void main() async {{
 {}
-  runApp(
+{}  runApp(
     const ProviderScope(
       child: App(),
     ),
   );
-}}"#,
-        supabase_imports, supabase_init,
+}}{}"#,
+        imports, signature, init, entrypoint,
     )
 }
@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{camel_case, dart, pascal_case};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    Int,
+    Double,
+    Bool,
+    String,
+    DateTime,
+    Json,
+}
+
+impl FieldType {
+    pub fn dart_type(self) -> &'static str {
+        match self {
+            FieldType::Int => "int",
+            FieldType::Double => "double",
+            FieldType::Bool => "bool",
+            FieldType::String => "String",
+            FieldType::DateTime => "DateTime",
+            FieldType::Json => "Map<String, dynamic>",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldSpec {
+    /// Column name as it appears in the database / JSON payload
+    pub column: String,
+    pub field_type: FieldType,
+    #[serde(default)]
+    pub is_list: bool,
+    #[serde(default)]
+    pub nullable: bool,
+    #[serde(default)]
+    pub primary_key: bool,
+    /// Set when the database fills the value in (defaults, identity, serial)
+    #[serde(default)]
+    pub has_default: bool,
+}
+
+impl FieldSpec {
+    pub fn dart_name(&self) -> String {
+        field_name(&self.column)
+    }
+
+    pub fn dart_type(&self) -> String {
+        if self.is_list {
+            format!("List<{}>", self.field_type.dart_type())
+        } else {
+            self.field_type.dart_type().to_string()
+        }
+    }
+
+    // Fields the database can fill in are optional on the Dart side so that
+    // new rows can be built before they are inserted.
+    pub fn is_optional(&self) -> bool {
+        self.nullable || self.has_default
+    }
+
+    fn json_decode_expr(&self) -> String {
+        let value = format!("json[{}]", dart::string_literal(&self.column));
+        let optional = self.is_optional();
+        match (self.is_list, self.field_type) {
+            (true, field_type) => format!(
+                "({} as List<dynamic>{}){}.map((e) => {}).toList()",
+                value,
+                opt(optional),
+                opt(optional),
+                element_from_json(field_type, "e")
+            ),
+            (false, FieldType::DateTime) => {
+                if optional {
+                    format!(
+                        "{} == null ? null : DateTime.parse({} as String)",
+                        value, value
+                    )
+                } else {
+                    format!("DateTime.parse({} as String)", value)
+                }
+            }
+            (false, FieldType::Double) => {
+                format!(
+                    "({} as num{}){}.toDouble()",
+                    value,
+                    opt(optional),
+                    opt(optional)
+                )
+            }
+            (false, field_type) => {
+                format!("{} as {}{}", value, field_type.dart_type(), opt(optional))
+            }
+        }
+    }
+
+    fn json_encode_expr(&self) -> String {
+        let name = self.dart_name();
+        match (self.is_list, self.field_type) {
+            (false, FieldType::DateTime) => {
+                format!("{}{}.toIso8601String()", name, opt(self.is_optional()))
+            }
+            (true, FieldType::DateTime) => format!(
+                "{}{}.map((e) => e.toIso8601String()).toList()",
+                name,
+                opt(self.is_optional())
+            ),
+            _ => name,
+        }
+    }
+}
+
+// Dart field of a column. Quoted columns may hold any character and reserved
+// words such as `new` or `class` cannot name a field, the column itself stays
+// the JSON key.
+pub fn field_name(column: &str) -> String {
+    let words: Vec<&str> = column
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .collect();
+    dart::member_name(&camel_case(&words.join("_")))
+}
+
+fn opt(optional: bool) -> &'static str {
+    if optional {
+        "?"
+    } else {
+        ""
+    }
+}
+
+fn element_from_json(field_type: FieldType, var: &str) -> String {
+    match field_type {
+        FieldType::DateTime => format!("DateTime.parse({} as String)", var),
+        FieldType::Double => format!("({} as num).toDouble()", var),
+        other => format!("{} as {}", var, other.dart_type()),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSpec {
+    /// Feature the model belongs to
+    pub name: String,
+    /// Backing table, if the model was read from a database schema
+    #[serde(default)]
+    pub table: Option<String>,
+    pub fields: Vec<FieldSpec>,
+}
+
+impl ModelSpec {
+    pub fn class_name(&self) -> String {
+        format!("{}Model", pascal_case(&self.name))
+    }
+
+    pub fn primary_key(&self) -> Option<&FieldSpec> {
+        self.fields
+            .iter()
+            .find(|f| f.primary_key)
+            .or_else(|| self.fields.iter().find(|f| f.column == "id"))
+    }
+
+    // Tables without a primary key fall back to their first column
+    pub fn key(&self) -> &FieldSpec {
+        self.primary_key().unwrap_or(&self.fields[0])
+    }
+}
+
+pub fn generate_model_template(model: &ModelSpec) -> String {
+    let class_name = model.class_name();
+
+    let params = model
+        .fields
+        .iter()
+        .map(|f| {
+            if f.is_optional() {
+                format!("    this.{},", f.dart_name())
+            } else {
+                format!("    required this.{},", f.dart_name())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let from_json = model
+        .fields
+        .iter()
+        .map(|f| format!("        {}: {},", f.dart_name(), f.json_decode_expr()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let declarations = model
+        .fields
+        .iter()
+        .map(|f| {
+            format!(
+                "  final {}{} {};",
+                f.dart_type(),
+                opt(f.is_optional()),
+                f.dart_name()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Database-filled columns are left out when unset so inserts pick up
+    // the column defaults.
+    let to_json = model
+        .fields
+        .iter()
+        .map(|f| {
+            if f.has_default {
+                format!(
+                    "        if ({} != null) {}: {},",
+                    f.dart_name(),
+                    dart::string_literal(&f.column),
+                    f.json_encode_expr()
+                )
+            } else {
+                format!(
+                    "        {}: {},",
+                    dart::string_literal(&f.column),
+                    f.json_encode_expr()
+                )
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let copy_with_params = model
+        .fields
+        .iter()
+        .map(|f| format!("    {}? {},", f.dart_type(), f.dart_name()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let copy_with_body = model
+        .fields
+        .iter()
+        .map(|f| format!("      {0}: {0} ?? this.{0},", f.dart_name()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"class {class_name} {{
+  const {class_name}({{
+{params}
+  }});
+
+  factory {class_name}.fromJson(Map<String, dynamic> json) => {class_name}(
+{from_json}
+      );
+
+{declarations}
+
+  Map<String, dynamic> toJson() => {{
+{to_json}
+      }};
+
+  {class_name} copyWith({{
+{copy_with_params}
+  }}) {{
+    return {class_name}(
+{copy_with_body}
+    );
+  }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(column: &str) -> FieldSpec {
+        FieldSpec {
+            column: column.to_string(),
+            field_type: FieldType::String,
+            is_list: false,
+            nullable: false,
+            primary_key: false,
+            has_default: false,
+        }
+    }
+
+    #[test]
+    fn reserved_columns_get_safe_field_names() {
+        assert_eq!(field("due_at").dart_name(), "dueAt");
+        assert_eq!(field("new").dart_name(), "newValue");
+        assert_eq!(field("class").dart_name(), "classValue");
+        assert_eq!(field("Due Date").dart_name(), "dueDate");
+
+        let model = ModelSpec {
+            name: "todo".to_string(),
+            table: Some("todos".to_string()),
+            fields: vec![field("in")],
+        };
+        let template = generate_model_template(&model);
+        assert!(template.contains("final String inValue;"));
+        assert!(template.contains("inValue: json['in'] as String,"));
+        assert!(template.contains("'in': inValue,"));
+    }
+}
@@ -0,0 +1,297 @@
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+use super::NativeFile;
+
+pub struct Gradle {
+    path: PathBuf,
+    source: String,
+}
+
+impl NativeFile for Gradle {
+    fn parse(path: &Path, source: String) -> Result<Self> {
+        Ok(Gradle {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+fn line_start(source: &str, pos: usize) -> usize {
+    source[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn indent_at(source: &str, pos: usize) -> String {
+    let line = &source[line_start(source, pos)..];
+    line[..line.len() - line.trim_start().len()].to_string()
+}
+
+// Index of the `}` closing the brace at `open`
+fn matching_brace(source: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in source[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Lines directly inside `start..end`, nested blocks skipped
+fn top_level_lines(source: &str, start: usize, end: usize) -> Vec<(usize, &str)> {
+    let mut lines = Vec::new();
+    let mut depth = 0;
+    let mut pos = start;
+    for line in source[start..end].split_inclusive('\n') {
+        if depth == 0 {
+            lines.push((pos, line));
+        }
+        depth += line.matches('{').count() as i32;
+        depth -= line.matches('}').count() as i32;
+        pos += line.len();
+    }
+    lines
+}
+
+// Whether a line assigns or calls `key`, e.g. `minSdk = 21` or `minSdkVersion 21`
+fn is_setting(line: &str, key: &str) -> bool {
+    line.trim_start()
+        .strip_prefix(key)
+        .is_some_and(|rest| rest.starts_with([' ', '=', '(', '\t']))
+}
+
+impl Gradle {
+    pub fn is_kts(&self) -> bool {
+        self.path.extension().is_some_and(|ext| ext == "kts")
+    }
+
+    // Content range of the nested block, from after `{` to its `}`
+    fn block(&self, path: &[&str]) -> Option<(usize, usize)> {
+        let (mut start, mut end) = (0, self.source.len());
+        for name in path {
+            let (offset, line) =
+                top_level_lines(&self.source, start, end)
+                    .into_iter()
+                    .find(|(_, line)| {
+                        line.trim_start()
+                            .strip_prefix(name)
+                            .is_some_and(|rest| rest.trim_start().starts_with('{'))
+                    })?;
+            let open = offset + line.find('{')?;
+            start = open + 1;
+            end = matching_brace(&self.source, open)?;
+        }
+        Some((start, end))
+    }
+
+    // Creates the missing blocks of `path`
+    fn ensure_block(&mut self, path: &[&str]) -> Result<(usize, usize)> {
+        for depth in 1..=path.len() {
+            if self.block(&path[..depth]).is_some() {
+                continue;
+            }
+            let (_, end) = match self.block(&path[..depth - 1]) {
+                Some(parent) => parent,
+                None => bail!(
+                    "Could not find {} in {}",
+                    path.join("."),
+                    self.path.display()
+                ),
+            };
+            let indent = "    ".repeat(depth - 1);
+            let at = line_start(&self.source, end);
+            self.source
+                .insert_str(at, &format!("{indent}{} {{\n{indent}}}\n", path[depth - 1]));
+        }
+        match self.block(path) {
+            Some(block) => Ok(block),
+            None => bail!(
+                "Could not find {} in {}",
+                path.join("."),
+                self.path.display()
+            ),
+        }
+    }
+
+    // Indent of the content of the block closing at `end`
+    fn content_indent(&self, end: usize) -> String {
+        format!("{}    ", indent_at(&self.source, end))
+    }
+
+    /// Adds or updates `key = value` in the block at `path`
+    pub fn set(&mut self, path: &[&str], key: &str, value: &str) -> Result<()> {
+        let (start, end) = self.ensure_block(path)?;
+        let indent = self.content_indent(end);
+        let setting = format!("{}{} = {}\n", indent, key, value);
+        let existing = top_level_lines(&self.source, start, end)
+            .into_iter()
+            .find(|(_, line)| is_setting(line, key))
+            .map(|(offset, line)| (offset, offset + line.len()));
+        match existing {
+            Some((line_start, line_end)) => {
+                self.source.replace_range(line_start..line_end, &setting)
+            }
+            None => self
+                .source
+                .insert_str(line_start(&self.source, end), &setting),
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, path: &[&str], key: &str) -> Result<()> {
+        let Some((start, end)) = self.block(path) else {
+            return Ok(());
+        };
+        let existing = top_level_lines(&self.source, start, end)
+            .into_iter()
+            .find(|(_, line)| is_setting(line, key))
+            .map(|(offset, line)| (offset, offset + line.len()));
+        if let Some((line_start, line_end)) = existing {
+            self.source.replace_range(line_start..line_end, "");
+        }
+        Ok(())
+    }
+
+    /// Adds or replaces the lines flutter_gen manages under `name` in the
+    /// block at `path`, an empty `content` removes them
+    pub fn set_block(&mut self, path: &[&str], name: &str, content: &str) -> Result<()> {
+        let begin_marker = format!("// flutter_gen:{}", name);
+        let end_marker = format!("// /flutter_gen:{}", name);
+
+        let mut existing = None;
+        if let Some(begin) = self.source.find(&begin_marker) {
+            if let Some(offset) = self.source[begin..].find(&end_marker) {
+                let start = line_start(&self.source, begin);
+                let end = begin + offset + end_marker.len();
+                let end = self.source[end..]
+                    .find('\n')
+                    .map_or(self.source.len(), |i| end + i + 1);
+                self.source.replace_range(start..end, "");
+                existing = Some(start);
+            }
+        }
+        if content.trim().is_empty() {
+            return Ok(());
+        }
+
+        let (_, end) = self.ensure_block(path)?;
+        let indent = self.content_indent(end);
+        let at = existing.unwrap_or_else(|| line_start(&self.source, end));
+        let mut block = format!("{}{}\n", indent, begin_marker);
+        for line in content.lines() {
+            if line.trim().is_empty() {
+                block.push('\n');
+            } else {
+                block.push_str(&format!("{}{}\n", indent, line));
+            }
+        }
+        block.push_str(&format!("{}{}\n", indent, end_marker));
+        self.source.insert_str(at, &block);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRADLE: &str = r#"plugins {
+    id "com.android.application"
+}
+
+android {
+    namespace = "com.example.todo"
+
+    defaultConfig {
+        applicationId = "com.example.todo"
+        minSdkVersion flutter.minSdkVersion
+        targetSdk = flutter.targetSdkVersion
+    }
+
+    buildTypes {
+        release {
+            minSdk = 99
+        }
+    }
+}
+"#;
+
+    fn gradle(name: &str) -> Gradle {
+        Gradle::parse(Path::new(name), GRADLE.to_string()).unwrap()
+    }
+
+    #[test]
+    fn sets_settings_of_the_block_only() {
+        let mut gradle = gradle("build.gradle");
+        assert!(!gradle.is_kts());
+        gradle
+            .remove(&["android", "defaultConfig"], "minSdkVersion")
+            .unwrap();
+        gradle
+            .set(&["android", "defaultConfig"], "minSdk", "23")
+            .unwrap();
+        let once = gradle.source().to_string();
+        gradle
+            .set(&["android", "defaultConfig"], "minSdk", "23")
+            .unwrap();
+        assert_eq!(gradle.source(), once);
+        assert!(once
+            .contains("        targetSdk = flutter.targetSdkVersion\n        minSdk = 23\n    }"));
+        // The release build type keeps its own setting
+        assert!(once.contains("minSdk = 99"));
+        assert!(!once.contains("minSdkVersion"));
+    }
+
+    #[test]
+    fn creates_missing_blocks() {
+        let mut gradle = gradle("build.gradle.kts");
+        assert!(gradle.is_kts());
+        gradle
+            .set(&["android", "flavorDimensions"], "x", "1")
+            .unwrap();
+        assert!(gradle
+            .source()
+            .contains("    flavorDimensions {\n        x = 1\n    }\n}\n"));
+        // Removing what is not there changes nothing
+        let source = gradle.source().to_string();
+        gradle.remove(&["missing"], "x").unwrap();
+        gradle.remove(&["android"], "missing").unwrap();
+        assert_eq!(gradle.source(), source);
+    }
+
+    #[test]
+    fn blocks_are_replaced_and_removed() {
+        let mut gradle = gradle("build.gradle");
+        let flavors = "dev {\n    dimension \"env\"\n}";
+        gradle
+            .set_block(&["android", "productFlavors"], "flavors", flavors)
+            .unwrap();
+        let once = gradle.source().to_string();
+        assert!(once.contains(
+            "    productFlavors {\n        // flutter_gen:flavors\n        dev {\n            dimension \"env\"\n        }\n        // /flutter_gen:flavors\n    }"
+        ));
+        gradle
+            .set_block(&["android", "productFlavors"], "flavors", flavors)
+            .unwrap();
+        assert_eq!(gradle.source(), once);
+
+        gradle
+            .set_block(&["android", "productFlavors"], "flavors", "")
+            .unwrap();
+        assert_eq!(
+            gradle.source(),
+            GRADLE.replace("}\n}\n", "}\n    productFlavors {\n    }\n}\n")
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+use super::{xml_escape, NativeFile};
+
+// Element of AndroidManifest.xml that generated entries are added to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestParent {
+    Manifest,
+    Application,
+    MainActivity,
+}
+
+pub struct AndroidManifest {
+    path: PathBuf,
+    source: String,
+}
+
+impl NativeFile for AndroidManifest {
+    fn parse(path: &Path, source: String) -> Result<Self> {
+        Ok(AndroidManifest {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+fn line_start(source: &str, pos: usize) -> usize {
+    source[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn indent_at(source: &str, pos: usize) -> &str {
+    let start = line_start(source, pos);
+    let line = &source[start..];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+impl AndroidManifest {
+    // Start and end of the element's opening tag, `end` is the index of `>`
+    fn opening_tag(&self, element: ManifestParent) -> Result<(usize, usize)> {
+        let start = match element {
+            ManifestParent::Manifest => self.source.find("<manifest"),
+            ManifestParent::Application => self.source.find("<application"),
+            ManifestParent::MainActivity => {
+                let mut found = None;
+                let mut from = 0;
+                while let Some(offset) = self.source[from..].find("<activity") {
+                    let start = from + offset;
+                    let end = start + self.source[start..].find('>').unwrap_or(0);
+                    if self.source[start..end].contains("android:name=\".MainActivity\"") {
+                        found = Some(start);
+                        break;
+                    }
+                    from = start + 1;
+                }
+                found
+            }
+        };
+        let Some(start) = start else {
+            bail!(
+                "Could not find the {:?} element in {}",
+                element,
+                self.path.display()
+            );
+        };
+        match self.source[start..].find('>') {
+            Some(end) => Ok((start, start + end)),
+            None => bail!("Unterminated tag in {}", self.path.display()),
+        }
+    }
+
+    fn closing_tag(&self, element: ManifestParent) -> Result<usize> {
+        let (_, open_end) = self.opening_tag(element)?;
+        let tag = match element {
+            ManifestParent::Manifest => "</manifest>",
+            ManifestParent::Application => "</application>",
+            ManifestParent::MainActivity => "</activity>",
+        };
+        match self.source[open_end..].find(tag) {
+            Some(offset) => Ok(open_end + offset),
+            None => bail!("Missing {} in {}", tag, self.path.display()),
+        }
+    }
+
+    fn markers(name: &str) -> (String, String) {
+        (
+            format!("<!-- flutter_gen:{} -->", name),
+            format!("<!-- /flutter_gen:{} -->", name),
+        )
+    }
+
+    // Removes the block and returns where it was
+    fn take_block(&mut self, name: &str) -> Option<usize> {
+        let (begin_marker, end_marker) = Self::markers(name);
+        let begin = self.source.find(&begin_marker)?;
+        let end = begin + self.source[begin..].find(&end_marker)? + end_marker.len();
+        let begin = line_start(&self.source, begin);
+        let end = self.source[end..]
+            .find('\n')
+            .map_or(self.source.len(), |i| end + i + 1);
+        self.source.replace_range(begin..end, "");
+        Some(begin)
+    }
+
+    /// Adds or replaces the entries flutter_gen manages under `name`, an
+    /// empty `content` removes them
+    pub fn set_block(&mut self, name: &str, parent: ManifestParent, content: &str) -> Result<()> {
+        let existing = self.take_block(name);
+        if content.trim().is_empty() {
+            return Ok(());
+        }
+
+        let (open_start, _) = self.opening_tag(parent)?;
+        let indent = format!("{}    ", indent_at(&self.source, open_start));
+        let at = match existing {
+            Some(at) => at,
+            // Permissions and features are listed before the application
+            None if parent == ManifestParent::Manifest => {
+                let (application, _) = self.opening_tag(ManifestParent::Application)?;
+                line_start(&self.source, application)
+            }
+            None => line_start(&self.source, self.closing_tag(parent)?),
+        };
+
+        let (begin_marker, end_marker) = Self::markers(name);
+        let mut block = format!("{}{}\n", indent, begin_marker);
+        for line in content.lines() {
+            if line.trim().is_empty() {
+                block.push('\n');
+            } else {
+                block.push_str(&format!("{}{}\n", indent, line));
+            }
+        }
+        block.push_str(&format!("{}{}\n", indent, end_marker));
+        self.source.insert_str(at, &block);
+        Ok(())
+    }
+
+    /// Adds or updates an attribute of the element's opening tag
+    pub fn set_attribute(
+        &mut self,
+        element: ManifestParent,
+        name: &str,
+        value: &str,
+    ) -> Result<()> {
+        let (start, end) = self.opening_tag(element)?;
+        let value = xml_escape(value);
+        let attribute = format!("{}=\"", name);
+        if let Some(offset) = self.source[start..end].find(&format!(" {}", attribute)) {
+            let value_start = start + offset + 1 + attribute.len();
+            let value_end = value_start + self.source[value_start..].find('"').unwrap_or(0);
+            self.source.replace_range(value_start..value_end, &value);
+            return Ok(());
+        }
+
+        // Attributes of multi-line tags go on a line of their own
+        let tag = &self.source[start..end];
+        let separator = match tag.find('\n') {
+            Some(newline) => format!("\n{}", indent_at(&self.source, start + newline + 1)),
+            None => " ".to_string(),
+        };
+        let at = if self.source[..end].ends_with('/') {
+            end - 1
+        } else {
+            end
+        };
+        self.source
+            .insert_str(at, &format!("{}{}{}\"", separator, attribute, value));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android">
+    <application
+        android:label="todo"
+        android:icon="@mipmap/ic_launcher">
+        <activity
+            android:name=".MainActivity"
+            android:exported="true">
+            <intent-filter>
+                <action android:name="android.intent.action.MAIN"/>
+            </intent-filter>
+        </activity>
+    </application>
+</manifest>
+"#;
+
+    fn manifest() -> AndroidManifest {
+        AndroidManifest::parse(Path::new("AndroidManifest.xml"), MANIFEST.to_string()).unwrap()
+    }
+
+    #[test]
+    fn blocks_are_replaced_and_removed() {
+        let permission = r#"<uses-permission android:name="android.permission.CAMERA"/>"#;
+        let mut manifest = manifest();
+        manifest
+            .set_block("permissions", ManifestParent::Manifest, permission)
+            .unwrap();
+        let once = manifest.source().to_string();
+        assert!(once.contains(&format!(
+            "    <!-- flutter_gen:permissions -->\n    {}\n    <!-- /flutter_gen:permissions -->\n    <application",
+            permission
+        )));
+        manifest
+            .set_block("permissions", ManifestParent::Manifest, permission)
+            .unwrap();
+        assert_eq!(manifest.source(), once);
+
+        manifest
+            .set_block(
+                "deep_links",
+                ManifestParent::MainActivity,
+                "<intent-filter>\n\n</intent-filter>",
+            )
+            .unwrap();
+        assert!(manifest
+            .source()
+            .contains("        <!-- /flutter_gen:deep_links -->\n        </activity>"));
+
+        manifest
+            .set_block("permissions", ManifestParent::Manifest, "")
+            .unwrap();
+        manifest
+            .set_block("deep_links", ManifestParent::MainActivity, " ")
+            .unwrap();
+        assert_eq!(manifest.source(), MANIFEST);
+    }
+
+    #[test]
+    fn sets_attributes() {
+        let mut manifest = manifest();
+        manifest
+            .set_attribute(
+                ManifestParent::Application,
+                "android:label",
+                "Tom & \"Jerry\"",
+            )
+            .unwrap();
+        assert!(manifest
+            .source()
+            .contains(r#"android:label="Tom &amp; &quot;Jerry&quot;""#));
+        manifest
+            .set_attribute(ManifestParent::Application, "android:label", "todo")
+            .unwrap();
+        assert_eq!(manifest.source(), MANIFEST);
+
+        // New attributes of multi-line tags go on their own line
+        manifest
+            .set_attribute(
+                ManifestParent::MainActivity,
+                "android:launchMode",
+                "singleTop",
+            )
+            .unwrap();
+        assert!(manifest
+            .source()
+            .contains("android:exported=\"true\"\n            android:launchMode=\"singleTop\">"));
+    }
+
+    #[test]
+    fn sets_attributes_of_self_closing_tags() {
+        let mut manifest = AndroidManifest::parse(
+            Path::new("AndroidManifest.xml"),
+            "<manifest>\n    <application/>\n</manifest>\n".to_string(),
+        )
+        .unwrap();
+        manifest
+            .set_attribute(ManifestParent::Application, "android:label", "todo")
+            .unwrap();
+        assert!(manifest
+            .source()
+            .contains(r#"<application android:label="todo"/>"#));
+        assert!(manifest
+            .set_block("x", ManifestParent::MainActivity, "<meta-data/>")
+            .is_err());
+    }
+}
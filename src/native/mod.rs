@@ -0,0 +1,123 @@
+mod gradle;
+mod manifest;
+mod plist;
+mod podfile;
+mod xcode;
+
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub use gradle::Gradle;
+pub use manifest::{AndroidManifest, ManifestParent};
+pub use plist::{Plist, PlistValue, EMPTY_PLIST};
+pub use podfile::{Podfile, FLUTTER_PODFILE};
+pub use xcode::{XcodeProject, BUILD_CONFIGURATIONS};
+
+// A file of the native projects that flutter_gen edits in place, keeping
+// everything it did not generate as it is
+pub trait NativeFile: Sized {
+    fn parse(path: &Path, source: String) -> Result<Self>;
+
+    fn source(&self) -> &str;
+}
+
+// Applies `change` to a native file, platforms that were not created are
+// skipped. The file is only written when it changed.
+pub fn edit<F: NativeFile>(path: &Path, change: impl FnOnce(&mut F) -> Result<()>) -> Result<()> {
+    let Ok(source) = fs::read_to_string(path) else {
+        return Ok(());
+    };
+    let mut file = F::parse(path, source.clone())?;
+    change(&mut file)?;
+    if file.source() != source {
+        fs::write(path, file.source())?;
+    }
+    Ok(())
+}
+
+pub fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn android_manifest(project_dir: &Path) -> PathBuf {
+    project_dir
+        .join("android")
+        .join("app")
+        .join("src")
+        .join("main")
+        .join("AndroidManifest.xml")
+}
+
+// Newer Flutter templates use the Kotlin DSL
+pub fn app_gradle(project_dir: &Path) -> PathBuf {
+    let app = project_dir.join("android").join("app");
+    let kts = app.join("build.gradle.kts");
+    if kts.exists() {
+        kts
+    } else {
+        app.join("build.gradle")
+    }
+}
+
+pub fn info_plist(project_dir: &Path) -> PathBuf {
+    project_dir.join("ios").join("Runner").join("Info.plist")
+}
+
+pub fn podfile(project_dir: &Path) -> PathBuf {
+    project_dir.join("ios").join("Podfile")
+}
+
+pub fn entitlements(project_dir: &Path) -> PathBuf {
+    project_dir
+        .join("ios")
+        .join("Runner")
+        .join("Runner.entitlements")
+}
+
+pub fn xcode_project(project_dir: &Path) -> PathBuf {
+    project_dir
+        .join("ios")
+        .join("Runner.xcodeproj")
+        .join("project.pbxproj")
+}
+
+pub fn has_ios(project_dir: &Path) -> bool {
+    project_dir.join("ios").join("Runner").exists()
+}
+
+// Name shown below the app icon and the oldest Android version supported
+pub fn add_app_info(
+    project_dir: &Path,
+    display_name: Option<&str>,
+    min_sdk: Option<u32>,
+) -> Result<()> {
+    if let Some(name) = display_name {
+        edit(
+            &android_manifest(project_dir),
+            |manifest: &mut AndroidManifest| {
+                manifest.set_attribute(ManifestParent::Application, "android:label", name)
+            },
+        )?;
+        edit(&info_plist(project_dir), |plist: &mut Plist| {
+            plist.set("CFBundleDisplayName", PlistValue::String(name.to_string()))
+        })?;
+    }
+
+    if let Some(min_sdk) = min_sdk {
+        edit(&app_gradle(project_dir), |gradle: &mut Gradle| {
+            // Older templates use the deprecated `minSdkVersion`
+            gradle.remove(&["android", "defaultConfig"], "minSdkVersion")?;
+            gradle.set(
+                &["android", "defaultConfig"],
+                "minSdk",
+                &min_sdk.to_string(),
+            )
+        })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,407 @@
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+use super::{xml_escape, NativeFile};
+
+pub const EMPTY_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+</dict>
+</plist>
+"#;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlistValue {
+    String(String),
+    Bool(bool),
+    Array(Vec<PlistValue>),
+    Dict(Vec<(String, PlistValue)>),
+}
+
+impl PlistValue {
+    // Xcode indents with tabs, the first line is placed by the caller
+    fn render(&self, depth: usize) -> String {
+        let pad = "\t".repeat(depth);
+        match self {
+            PlistValue::String(s) => format!("<string>{}</string>", xml_escape(s)),
+            PlistValue::Bool(true) => "<true/>".to_string(),
+            PlistValue::Bool(false) => "<false/>".to_string(),
+            PlistValue::Array(items) => {
+                let mut out = "<array>\n".to_string();
+                for item in items {
+                    out.push_str(&format!("{}\t{}\n", pad, item.render(depth + 1)));
+                }
+                out.push_str(&format!("{}</array>", pad));
+                out
+            }
+            PlistValue::Dict(entries) => {
+                let mut out = "<dict>\n".to_string();
+                for (key, value) in entries {
+                    out.push_str(&format!(
+                        "{}\t<key>{}</key>\n{}\t{}\n",
+                        pad,
+                        xml_escape(key),
+                        pad,
+                        value.render(depth + 1)
+                    ));
+                }
+                out.push_str(&format!("{}</dict>", pad));
+                out
+            }
+        }
+    }
+}
+
+// A top-level entry of the root dict
+struct Entry {
+    key: String,
+    // Start of the line holding `<key>`
+    start: usize,
+    value_start: usize,
+    value_end: usize,
+}
+
+pub struct Plist {
+    path: PathBuf,
+    source: String,
+}
+
+impl NativeFile for Plist {
+    fn parse(path: &Path, source: String) -> Result<Self> {
+        let plist = Plist {
+            path: path.to_path_buf(),
+            source,
+        };
+        plist.root()?;
+        Ok(plist)
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+// Index after the element starting at `start`, nested elements included
+fn element_end(source: &str, start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut pos = start;
+    loop {
+        let open = pos + source[pos..].find('<')?;
+        let close = open + source[open..].find('>')?;
+        let tag = &source[open..=close];
+        if tag.starts_with("</") {
+            depth -= 1;
+        } else if !(tag.ends_with("/>") || tag.starts_with("<?") || tag.starts_with("<!")) {
+            depth += 1;
+        }
+        pos = close + 1;
+        if depth == 0 {
+            return Some(pos);
+        }
+    }
+}
+
+fn skip_whitespace(source: &str, pos: usize) -> usize {
+    pos + (source[pos..].len() - source[pos..].trim_start().len())
+}
+
+impl Plist {
+    // Start of the root dict's content and of its closing tag
+    fn root(&self) -> Result<(usize, usize)> {
+        let Some(start) = self.source.find("<dict>") else {
+            bail!("No root dict in {}", self.path.display());
+        };
+        match element_end(&self.source, start) {
+            Some(end) => Ok((start + "<dict>".len(), end - "</dict>".len())),
+            None => bail!("Unterminated root dict in {}", self.path.display()),
+        }
+    }
+
+    fn entries(&self) -> Result<Vec<Entry>> {
+        let (mut pos, end) = self.root()?;
+        let mut entries = Vec::new();
+        loop {
+            pos = skip_whitespace(&self.source, pos);
+            if pos >= end {
+                return Ok(entries);
+            }
+            let rest = &self.source[pos..];
+            if rest.starts_with("<!--") {
+                pos += rest.find("-->").map_or(rest.len(), |i| i + 3);
+                continue;
+            }
+            let (Some(key), Some(key_end)) = (rest.strip_prefix("<key>"), rest.find("</key>"))
+            else {
+                bail!("Unexpected content in {}", self.path.display());
+            };
+            let key = key[..key_end - "<key>".len()].to_string();
+            let value_start = skip_whitespace(&self.source, pos + key_end + "</key>".len());
+            let Some(value_end) = element_end(&self.source, value_start) else {
+                bail!("Unterminated value of `{}` in {}", key, self.path.display());
+            };
+            entries.push(Entry {
+                key,
+                start: self.source[..pos].rfind('\n').map_or(pos, |i| i + 1),
+                value_start,
+                value_end,
+            });
+            pos = value_end;
+        }
+    }
+
+    fn entry(&self, key: &str) -> Result<Option<Entry>> {
+        Ok(self.entries()?.into_iter().find(|e| e.key == key))
+    }
+
+    /// Adds or replaces a top-level entry
+    pub fn set(&mut self, key: &str, value: PlistValue) -> Result<()> {
+        match self.entry(key)? {
+            Some(entry) => {
+                self.source
+                    .replace_range(entry.value_start..entry.value_end, &value.render(1));
+            }
+            None => {
+                let (_, end) = self.root()?;
+                let at = self.source[..end].rfind('\n').map_or(end, |i| i + 1);
+                self.source.insert_str(
+                    at,
+                    &format!("\t<key>{}</key>\n\t{}\n", xml_escape(key), value.render(1)),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, key: &str) -> Result<()> {
+        if let Some(entry) = self.entry(key)? {
+            let end = match self.source[entry.value_end..].find('\n') {
+                Some(i)
+                    if self.source[entry.value_end..entry.value_end + i]
+                        .trim()
+                        .is_empty() =>
+                {
+                    entry.value_end + i + 1
+                }
+                _ => entry.value_end,
+            };
+            self.source.replace_range(entry.start..end, "");
+        }
+        Ok(())
+    }
+
+    // Items of the array value of `entry`, as start and end of each element
+    fn array_items(&self, entry: &Entry) -> Result<Vec<(usize, usize)>> {
+        let value = &self.source[entry.value_start..entry.value_end];
+        if value == "<array/>" {
+            return Ok(Vec::new());
+        }
+        if !value.starts_with("<array>") {
+            bail!("`{}` is not an array in {}", entry.key, self.path.display());
+        }
+
+        let end = entry.value_end - "</array>".len();
+        let mut pos = entry.value_start + "<array>".len();
+        let mut items = Vec::new();
+        loop {
+            pos = skip_whitespace(&self.source, pos);
+            if pos >= end {
+                return Ok(items);
+            }
+            let rest = &self.source[pos..];
+            if rest.starts_with("<!--") {
+                pos += rest.find("-->").map_or(rest.len(), |i| i + 3);
+                continue;
+            }
+            let Some(item_end) = element_end(&self.source, pos) else {
+                bail!(
+                    "Unterminated item of `{}` in {}",
+                    entry.key,
+                    self.path.display()
+                );
+            };
+            items.push((pos, item_end));
+            pos = item_end;
+        }
+    }
+
+    // Position of `item` in the array of `entry`, whatever its indentation
+    fn find_item(&self, entry: &Entry, item: &PlistValue) -> Result<Option<(usize, usize)>> {
+        let rendered = item.render(2);
+        Ok(self.array_items(entry)?.into_iter().find(|&(start, end)| {
+            self.source[start..end]
+                .lines()
+                .map(str::trim)
+                .eq(rendered.lines().map(str::trim))
+        }))
+    }
+
+    /// Adds `item` to the array at `key` unless it is already listed
+    pub fn add_to_array(&mut self, key: &str, item: PlistValue) -> Result<()> {
+        let Some(entry) = self.entry(key)? else {
+            return self.set(key, PlistValue::Array(vec![item]));
+        };
+        if self.source[entry.value_start..entry.value_end] == *"<array/>" {
+            return self.set(key, PlistValue::Array(vec![item]));
+        }
+
+        if self.find_item(&entry, &item)?.is_none() {
+            let close = entry.value_end - "</array>".len();
+            let at = self.source[..close].rfind('\n').map_or(close, |i| i + 1);
+            self.source
+                .insert_str(at, &format!("\t\t{}\n", item.render(2)));
+        }
+        Ok(())
+    }
+
+    /// Removes `item` from the array at `key`, and the key with the last item
+    pub fn remove_from_array(&mut self, key: &str, item: &PlistValue) -> Result<()> {
+        let Some(entry) = self.entry(key)? else {
+            return Ok(());
+        };
+        let Some((start, end)) = self.find_item(&entry, item)? else {
+            return Ok(());
+        };
+        if self.array_items(&entry)?.len() == 1 {
+            return self.remove(key);
+        }
+
+        let line_start = self.source[..start].rfind('\n').map_or(start, |i| i + 1);
+        let line_end = match self.source[end..].find('\n') {
+            Some(i) if self.source[end..end + i].trim().is_empty() => end + i + 1,
+            _ => end,
+        };
+        let (start, end) = if self.source[line_start..start].trim().is_empty() {
+            (line_start, line_end)
+        } else {
+            (start, end)
+        };
+        self.source.replace_range(start..end, "");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plist(source: &str) -> Plist {
+        Plist::parse(Path::new("Info.plist"), source.to_string()).unwrap()
+    }
+
+    fn scheme(name: &str) -> PlistValue {
+        PlistValue::Dict(vec![(
+            "CFBundleURLSchemes".to_string(),
+            PlistValue::Array(vec![PlistValue::String(name.to_string())]),
+        )])
+    }
+
+    #[test]
+    fn sets_and_removes_entries() {
+        let mut plist = plist(EMPTY_PLIST);
+        plist
+            .set(
+                "CFBundleDisplayName",
+                PlistValue::String("A & B".to_string()),
+            )
+            .unwrap();
+        plist
+            .set(
+                "CFBundleDisplayName",
+                PlistValue::String("Todo".to_string()),
+            )
+            .unwrap();
+        assert_eq!(
+            plist
+                .source()
+                .matches("<key>CFBundleDisplayName</key>")
+                .count(),
+            1
+        );
+        assert!(plist.source().contains("\t<string>Todo</string>\n"));
+
+        plist
+            .set("ITSAppUsesNonExemptEncryption", PlistValue::Bool(false))
+            .unwrap();
+        plist.remove("CFBundleDisplayName").unwrap();
+        plist.remove("ITSAppUsesNonExemptEncryption").unwrap();
+        assert_eq!(plist.source(), EMPTY_PLIST);
+    }
+
+    #[test]
+    fn escapes_values() {
+        let mut plist = plist(EMPTY_PLIST);
+        plist
+            .set(
+                "CFBundleDisplayName",
+                PlistValue::String("A & <B>".to_string()),
+            )
+            .unwrap();
+        assert!(plist
+            .source()
+            .contains("<string>A &amp; &lt;B&gt;</string>"));
+        assert!(Plist::parse(Path::new("Info.plist"), plist.source().to_string()).is_ok());
+    }
+
+    #[test]
+    fn adds_array_items_once() {
+        let mut plist = plist(EMPTY_PLIST);
+        plist
+            .add_to_array("CFBundleURLTypes", scheme("todo"))
+            .unwrap();
+        let once = plist.source().to_string();
+        plist
+            .add_to_array("CFBundleURLTypes", scheme("todo"))
+            .unwrap();
+        assert_eq!(plist.source(), once);
+
+        plist
+            .add_to_array("CFBundleURLTypes", scheme("other"))
+            .unwrap();
+        plist
+            .remove_from_array("CFBundleURLTypes", &scheme("other"))
+            .unwrap();
+        assert_eq!(plist.source(), once);
+        plist
+            .remove_from_array("CFBundleURLTypes", &scheme("todo"))
+            .unwrap();
+        assert_eq!(plist.source(), EMPTY_PLIST);
+    }
+
+    #[test]
+    fn matches_items_whatever_their_indentation() {
+        let source = EMPTY_PLIST.replace(
+            "<dict>\n</dict>",
+            "<dict>\n  <key>CFBundleURLTypes</key>\n  <array>\n    <!-- schemes -->\n    <dict>\n      <key>CFBundleURLSchemes</key>\n      <array>\n        <string>todo</string>\n      </array>\n    </dict>\n  </array>\n</dict>",
+        );
+        let mut plist = plist(&source);
+        plist
+            .add_to_array("CFBundleURLTypes", scheme("todo"))
+            .unwrap();
+        assert_eq!(plist.source(), source);
+        plist
+            .add_to_array(
+                "LSApplicationQueriesSchemes",
+                PlistValue::String("https".to_string()),
+            )
+            .unwrap();
+        assert!(plist.source().contains("<!-- schemes -->"));
+    }
+
+    #[test]
+    fn rejects_malformed_plists() {
+        assert!(Plist::parse(Path::new("Info.plist"), "<plist></plist>".to_string()).is_err());
+        assert!(Plist::parse(
+            Path::new("Info.plist"),
+            "<plist><dict><key>A</key>".to_string()
+        )
+        .is_err());
+        let mut plist = plist(&EMPTY_PLIST.replace(
+            "<dict>\n</dict>",
+            "<dict>\n\t<key>CFBundleName</key>\n\t<string>Todo</string>\n</dict>",
+        ));
+        assert!(plist
+            .add_to_array("CFBundleName", PlistValue::String("x".to_string()))
+            .is_err());
+    }
+}
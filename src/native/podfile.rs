@@ -0,0 +1,159 @@
+use anyhow::{bail, Result};
+use std::path::Path;
+
+use super::NativeFile;
+
+// Podfile Flutter writes on the first iOS build, for settings needed before it
+pub const FLUTTER_PODFILE: &str = r##"# Uncomment this line to define a global platform for your project
+# platform :ios, '12.0'
+
+# CocoaPods analytics sends network stats synchronously affecting flutter build latency.
+ENV['COCOAPODS_DISABLE_STATS'] = 'true'
+
+project 'Runner', {
+  'Debug' => :debug,
+  'Profile' => :release,
+  'Release' => :release,
+}
+
+def flutter_root
+  generated_xcode_build_settings_path = File.expand_path(File.join('..', 'Flutter', 'Generated.xcconfig'), __FILE__)
+  unless File.exist?(generated_xcode_build_settings_path)
+    raise "#{generated_xcode_build_settings_path} must exist. If you're running pod install manually, make sure flutter pub get is executed first"
+  end
+
+  File.foreach(generated_xcode_build_settings_path) do |line|
+    matches = line.match(/FLUTTER_ROOT\=(.*)/)
+    return matches[1].strip if matches
+  end
+  raise "FLUTTER_ROOT not found in #{generated_xcode_build_settings_path}. Try deleting Generated.xcconfig, then run flutter pub get"
+end
+
+require File.expand_path(File.join('packages', 'flutter_tools', 'bin', 'podhelper'), flutter_root)
+
+flutter_ios_podfile_setup
+
+target 'Runner' do
+  use_frameworks!
+
+  flutter_install_all_ios_pods File.dirname(File.realpath(__FILE__))
+  target 'RunnerTests' do
+    inherit! :search_paths
+  end
+end
+
+post_install do |installer|
+  installer.pods_project.targets.each do |target|
+    flutter_additional_ios_build_settings(target)
+  end
+end
+"##;
+
+// Line of Flutter's `post_install` hook that runs for every pod target
+const TARGET_SETTINGS: &str = "flutter_additional_ios_build_settings(target)";
+
+pub struct Podfile {
+    source: String,
+}
+
+impl NativeFile for Podfile {
+    fn parse(_path: &Path, source: String) -> Result<Self> {
+        Ok(Podfile { source })
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+fn line_start(source: &str, pos: usize) -> usize {
+    source[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn line_end(source: &str, pos: usize) -> usize {
+    source[pos..]
+        .find('\n')
+        .map_or(source.len(), |i| pos + i + 1)
+}
+
+impl Podfile {
+    /// Adds or replaces the lines flutter_gen manages under `name` in the
+    /// `post_install` loop over the pod targets, an empty `content` removes them
+    pub fn set_block(&mut self, name: &str, content: &str) -> Result<()> {
+        let begin_marker = format!("# flutter_gen:{}", name);
+        let end_marker = format!("# /flutter_gen:{}", name);
+
+        let mut existing = None;
+        if let Some(begin) = self.source.find(&begin_marker) {
+            if let Some(offset) = self.source[begin..].find(&end_marker) {
+                let start = line_start(&self.source, begin);
+                let end = line_end(&self.source, begin + offset);
+                self.source.replace_range(start..end, "");
+                existing = Some(start);
+            }
+        }
+        if content.trim().is_empty() {
+            return Ok(());
+        }
+
+        let Some(settings) = self.source.find(TARGET_SETTINGS) else {
+            bail!(
+                "The Podfile has no `{}` in its post_install hook to add settings after",
+                TARGET_SETTINGS
+            );
+        };
+        let start = line_start(&self.source, settings);
+        let indent = &self.source[start..settings];
+        let mut block = format!("{}{}\n", indent, begin_marker);
+        for line in content.lines() {
+            block.push_str(&format!("{}{}\n", indent, line));
+        }
+        block.push_str(&format!("{}{}\n", indent, end_marker));
+        let at = existing.unwrap_or_else(|| line_end(&self.source, settings));
+        self.source.insert_str(at, &block);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn podfile() -> Podfile {
+        Podfile::parse(Path::new("Podfile"), FLUTTER_PODFILE.to_string()).unwrap()
+    }
+
+    #[test]
+    fn blocks_are_replaced_and_removed() {
+        let mut podfile = podfile();
+        podfile
+            .set_block(
+                "permissions",
+                "target.build_configurations.each do |config|\nend",
+            )
+            .unwrap();
+        let once = podfile.source().to_string();
+        assert!(once.contains(
+            "    flutter_additional_ios_build_settings(target)\n    # flutter_gen:permissions\n    target.build_configurations.each do |config|\n    end\n    # /flutter_gen:permissions\n  end"
+        ));
+        podfile
+            .set_block(
+                "permissions",
+                "target.build_configurations.each do |config|\nend",
+            )
+            .unwrap();
+        assert_eq!(podfile.source(), once);
+
+        podfile.set_block("permissions", "").unwrap();
+        assert_eq!(podfile.source(), FLUTTER_PODFILE);
+    }
+
+    #[test]
+    fn needs_the_flutter_hook() {
+        let mut podfile =
+            Podfile::parse(Path::new("Podfile"), "platform :ios, '12.0'\n".to_string()).unwrap();
+        assert!(podfile.set_block("permissions", "x").is_err());
+        // Nothing to remove is not an error
+        assert!(podfile.set_block("permissions", "").is_ok());
+    }
+}
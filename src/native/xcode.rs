@@ -0,0 +1,481 @@
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+use super::NativeFile;
+
+// Build configurations of the Flutter iOS template
+pub const BUILD_CONFIGURATIONS: [&str; 3] = ["Debug", "Release", "Profile"];
+
+// `ios/Runner.xcodeproj/project.pbxproj`
+pub struct XcodeProject {
+    path: PathBuf,
+    source: String,
+}
+
+impl NativeFile for XcodeProject {
+    fn parse(path: &Path, source: String) -> Result<Self> {
+        Ok(XcodeProject {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+// An object of a section, `\t\t<id> /* <comment> */ = {...};`
+struct Object {
+    id: String,
+    comment: String,
+    start: usize,
+    end: usize,
+}
+
+// Object ids are 24 hex digits, derived from what they are created for so
+// regenerating gives the same ids
+fn derived_id(seed: &str) -> String {
+    let fnv = |salt: &str| {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in salt.bytes().chain(seed.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash
+    };
+    format!("{:016X}{:08X}", fnv(""), fnv("#") as u32)
+}
+
+impl XcodeProject {
+    fn section(&self, isa: &str) -> Result<(usize, usize)> {
+        let begin = format!("/* Begin {} section */", isa);
+        let end = format!("/* End {} section */", isa);
+        match (self.source.find(&begin), self.source.find(&end)) {
+            (Some(start), Some(end)) => Ok((start + begin.len(), end)),
+            _ => bail!("No {} section in {}", isa, self.path.display()),
+        }
+    }
+
+    fn objects(&self, isa: &str) -> Result<Vec<Object>> {
+        let (start, end) = self.section(isa)?;
+        let mut objects = Vec::new();
+        let mut pos = start;
+        for line in self.source[start..end].split_inclusive('\n') {
+            let line_start = pos;
+            pos += line.len();
+            let Some(header) = line.strip_prefix("\t\t") else {
+                continue;
+            };
+            let Some((id, rest)) = header.split_once(" /* ") else {
+                continue;
+            };
+            let Some((comment, _)) = rest.split_once(" */ = {") else {
+                continue;
+            };
+            // Single-line objects end on their own line
+            let object_end = if line.trim_end().ends_with("};") {
+                pos
+            } else {
+                match self.source[line_start..end].find("\n\t\t};\n") {
+                    Some(offset) => line_start + offset + "\n\t\t};\n".len(),
+                    None => bail!("Unterminated object {} in {}", id, self.path.display()),
+                }
+            };
+            objects.push(Object {
+                id: id.to_string(),
+                comment: comment.to_string(),
+                start: line_start,
+                end: object_end,
+            });
+        }
+        Ok(objects)
+    }
+
+    // File reference of an xcconfig in the `Flutter` group
+    fn add_xcconfig(&mut self, file_name: &str) -> Result<String> {
+        let id = derived_id(&format!("Flutter/{}", file_name));
+        if self.source.contains(&id) {
+            return Ok(id);
+        }
+
+        let (_, end) = self.section("PBXFileReference")?;
+        self.source.insert_str(
+            end,
+            &format!(
+                "\t\t{id} /* {file_name} */ = {{isa = PBXFileReference; lastKnownFileType = text.xcconfig; name = \"{file_name}\"; path = \"Flutter/{file_name}\"; sourceTree = \"<group>\"; }};\n"
+            ),
+        );
+
+        let flutter_group = self
+            .objects("PBXGroup")?
+            .into_iter()
+            .find(|group| group.comment == "Flutter");
+        if let Some(group) = flutter_group {
+            if let Some(offset) = self.source[group.start..group.end].find("\t\t\t);") {
+                self.source.insert_str(
+                    group.start + offset,
+                    &format!("\t\t\t\t{} /* {} */,\n", id, file_name),
+                );
+            }
+        }
+        Ok(id)
+    }
+
+    // Ids of the build configurations of a native target, flavors included
+    fn target_configurations(&self, target: &str) -> Result<Vec<String>> {
+        let comment = format!(
+            "Build configuration list for PBXNativeTarget \"{}\"",
+            target
+        );
+        let Some(list) = self
+            .objects("XCConfigurationList")?
+            .into_iter()
+            .find(|list| list.comment == comment)
+        else {
+            bail!("No {} target in {}", target, self.path.display());
+        };
+        Ok(self.source[list.start..list.end]
+            .lines()
+            .filter_map(|line| line.trim().strip_suffix(" */,"))
+            .filter_map(|line| line.split_once(" /* ").map(|(id, _)| id.to_string()))
+            .collect())
+    }
+
+    /// Sets a single-line build setting in every configuration of a target,
+    /// keeping the settings sorted the way Xcode writes them
+    pub fn set_build_setting(&mut self, target: &str, key: &str, value: &str) -> Result<()> {
+        let ids = self.target_configurations(target)?;
+        let setting = format!("\t\t\t\t{} = {};\n", key, value);
+
+        // Edit from the back so the offsets stay valid
+        let objects = self.objects("XCBuildConfiguration")?;
+        for object in objects.iter().rev().filter(|o| ids.contains(&o.id)) {
+            let Some(offset) = self.source[object.start..object.end].find("buildSettings = {\n")
+            else {
+                continue;
+            };
+            let mut at = object.start + offset + "buildSettings = {\n".len();
+            let mut existing = None;
+            for line in self.source[at..object.end].split_inclusive('\n') {
+                if line.trim() == "};" {
+                    break;
+                }
+                // Skip the items of list values
+                if line.starts_with("\t\t\t\t\t") || line.trim() == ");" {
+                    at += line.len();
+                    continue;
+                }
+                let name = line.trim().split(" = ").next().unwrap_or_default();
+                if name == key {
+                    if line.trim_end().ends_with('(') {
+                        bail!("{} of {} is a list", key, target);
+                    }
+                    existing = Some(line.len());
+                    break;
+                }
+                if name > key {
+                    break;
+                }
+                at += line.len();
+            }
+            match existing {
+                Some(len) => self.source.replace_range(at..at + len, &setting),
+                None => self.source.insert_str(at, &setting),
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies the build configurations as `<config>-<flavor>`, the Runner
+    /// target is based on `Flutter/<config>-<flavor>.xcconfig`, which sets its
+    /// bundle identifier
+    pub fn add_flavor(
+        &mut self,
+        flavor: &str,
+        bundle_id: &str,
+        flavor_bundle_id: &str,
+    ) -> Result<()> {
+        for config in BUILD_CONFIGURATIONS {
+            let name = format!("{}-{}", config, flavor);
+            let objects = self.objects("XCBuildConfiguration")?;
+            if objects.iter().any(|o| o.comment == name) {
+                continue;
+            }
+            let xcconfig = self.add_xcconfig(&format!("{}.xcconfig", name))?;
+
+            // Insert from the back so the offsets stay valid
+            let objects = self.objects("XCBuildConfiguration")?;
+            let mut copies = Vec::new();
+            let prefix = format!("{}-", config);
+            for (i, object) in objects
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(_, o)| o.comment == config)
+            {
+                // After the copies of earlier flavors
+                let at = objects[i + 1..]
+                    .iter()
+                    .take_while(|o| o.comment.starts_with(&prefix))
+                    .last()
+                    .map_or(object.end, |o| o.end);
+                let id = derived_id(&format!("{}/{}", object.id, flavor));
+                let mut copy = self.source[object.start..object.end]
+                    .replacen(
+                        &format!("{} /* {} */", object.id, config),
+                        &format!("{} /* {} */", id, name),
+                        1,
+                    )
+                    .replace(
+                        &format!("name = {};", config),
+                        &format!("name = \"{}\";", name),
+                    );
+
+                let runner_bundle_id = format!("PRODUCT_BUNDLE_IDENTIFIER = {};", bundle_id);
+                if copy.contains(&runner_bundle_id) {
+                    copy = copy
+                        .lines()
+                        .filter(|line| line.trim() != runner_bundle_id)
+                        .map(
+                            |line| match line.split_once("baseConfigurationReference = ") {
+                                Some((indent, _)) => format!(
+                                    "{}baseConfigurationReference = {} /* {}.xcconfig */;",
+                                    indent, xcconfig, name
+                                ),
+                                None => line.to_string(),
+                            },
+                        )
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                        + "\n";
+                } else {
+                    copy = copy.replace(
+                        &format!("{}.RunnerTests", bundle_id),
+                        &format!("{}.RunnerTests", flavor_bundle_id),
+                    );
+                }
+                self.source.insert_str(at, &copy);
+                copies.push((object.id.clone(), id));
+            }
+
+            let (start, end) = self.section("XCConfigurationList")?;
+            let mut lists = Vec::new();
+            let mut pending = None;
+            for line in self.source[start..end].lines() {
+                if !line.contains(&format!("/* {}", prefix)) {
+                    lists.extend(pending.take());
+                }
+                lists.push(line.to_string());
+                for (original, copy) in &copies {
+                    if line.trim() == format!("{} /* {} */,", original, config) {
+                        pending = Some(format!("\t\t\t\t{} /* {} */,", copy, name));
+                    }
+                }
+            }
+            let lists = lists.join("\n") + "\n";
+            self.source.replace_range(start..end, &lists);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The Debug configurations of the Flutter template's project
+    const PROJECT: &str = r#"// !$*UTF8*$!
+{
+	objects = {
+
+/* Begin PBXFileReference section */
+		9740EEB21CF90195004384FC /* Debug.xcconfig */ = {isa = PBXFileReference; lastKnownFileType = text.xcconfig; name = Debug.xcconfig; path = Flutter/Debug.xcconfig; sourceTree = "<group>"; };
+/* End PBXFileReference section */
+
+/* Begin PBXGroup section */
+		9740EEB11CF90186004384FC /* Flutter */ = {
+			isa = PBXGroup;
+			children = (
+				9740EEB21CF90195004384FC /* Debug.xcconfig */,
+			);
+			name = Flutter;
+			sourceTree = "<group>";
+		};
+/* End PBXGroup section */
+
+/* Begin XCBuildConfiguration section */
+		97C147031CF9000F007C117D /* Debug */ = {
+			isa = XCBuildConfiguration;
+			buildSettings = {
+				SDKROOT = iphoneos;
+			};
+			name = Debug;
+		};
+		97C147061CF9000F007C117D /* Debug */ = {
+			isa = XCBuildConfiguration;
+			baseConfigurationReference = 9740EEB21CF90195004384FC /* Debug.xcconfig */;
+			buildSettings = {
+				PRODUCT_BUNDLE_IDENTIFIER = com.example.todo;
+			};
+			name = Debug;
+		};
+		331C80881294CF71000E1FA1 /* Debug */ = {
+			isa = XCBuildConfiguration;
+			buildSettings = {
+				PRODUCT_BUNDLE_IDENTIFIER = com.example.todo.RunnerTests;
+			};
+			name = Debug;
+		};
+/* End XCBuildConfiguration section */
+
+/* Begin XCConfigurationList section */
+		97C146E91CF9000F007C117D /* Build configuration list for PBXProject "Runner" */ = {
+			isa = XCConfigurationList;
+			buildConfigurations = (
+				97C147031CF9000F007C117D /* Debug */,
+			);
+			defaultConfigurationIsVisible = 0;
+		};
+		97C147051CF9000F007C117D /* Build configuration list for PBXNativeTarget "Runner" */ = {
+			isa = XCConfigurationList;
+			buildConfigurations = (
+				97C147061CF9000F007C117D /* Debug */,
+			);
+			defaultConfigurationIsVisible = 0;
+		};
+		331C80871294CF71000E1FA1 /* Build configuration list for PBXNativeTarget "RunnerTests" */ = {
+			isa = XCConfigurationList;
+			buildConfigurations = (
+				331C80881294CF71000E1FA1 /* Debug */,
+			);
+			defaultConfigurationIsVisible = 0;
+		};
+/* End XCConfigurationList section */
+	};
+}
+"#;
+
+    fn project() -> XcodeProject {
+        XcodeProject::parse(Path::new("project.pbxproj"), PROJECT.to_string()).unwrap()
+    }
+
+    #[test]
+    fn derived_ids_are_stable() {
+        let id = derived_id("Flutter/Debug-dev.xcconfig");
+        assert_eq!(id, derived_id("Flutter/Debug-dev.xcconfig"));
+        assert_ne!(id, derived_id("Flutter/Debug-prod.xcconfig"));
+        assert_eq!(id.len(), 24);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn copies_the_configurations_once() {
+        let mut project = project();
+        project
+            .add_flavor("dev", "com.example.todo", "com.example.todo.dev")
+            .unwrap();
+        let once = project.source().to_string();
+        project
+            .add_flavor("dev", "com.example.todo", "com.example.todo.dev")
+            .unwrap();
+        assert_eq!(project.source(), once);
+
+        let objects = project.objects("XCBuildConfiguration").unwrap();
+        let copies = objects
+            .iter()
+            .filter(|o| o.comment == "Debug-dev")
+            .collect::<Vec<_>>();
+        assert_eq!(copies.len(), 3);
+        for copy in &copies {
+            assert!(once.contains(&format!("{} /* Debug-dev */,", copy.id)));
+        }
+
+        // The Runner copy takes its bundle identifier from the flavor's xcconfig
+        let xcconfig = derived_id("Flutter/Debug-dev.xcconfig");
+        assert!(once.contains(&format!(
+            "baseConfigurationReference = {} /* Debug-dev.xcconfig */;",
+            xcconfig
+        )));
+        assert!(once.contains(&format!("{} /* Debug-dev.xcconfig */,", xcconfig)));
+        assert_eq!(
+            once.matches("PRODUCT_BUNDLE_IDENTIFIER = com.example.todo;")
+                .count(),
+            1
+        );
+        assert!(once.contains("PRODUCT_BUNDLE_IDENTIFIER = com.example.todo.dev.RunnerTests;"));
+        assert!(once.contains("name = \"Debug-dev\";"));
+    }
+
+    #[test]
+    fn keeps_flavors_in_order() {
+        let mut project = project();
+        project
+            .add_flavor("dev", "com.example.todo", "com.example.todo.dev")
+            .unwrap();
+        project
+            .add_flavor("prod", "com.example.todo", "com.example.todo")
+            .unwrap();
+        let source = project.source();
+        let dev = source.find("/* Debug-dev */ = {").unwrap();
+        let prod = source.find("/* Debug-prod */ = {").unwrap();
+        assert!(dev < prod);
+        let list = &source[source.find("PBXNativeTarget \"Runner\"").unwrap()..];
+        let list = &list[..list.find(");").unwrap()];
+        let names = list
+            .lines()
+            .filter_map(|line| line.trim().strip_suffix(" */,"))
+            .filter_map(|line| line.split_once(" /* ").map(|(_, name)| name))
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Debug", "Debug-dev", "Debug-prod"]);
+    }
+
+    #[test]
+    fn sets_build_settings_of_the_target() {
+        let mut project = project();
+        project
+            .add_flavor("dev", "com.example.todo", "com.example.todo.dev")
+            .unwrap();
+        for _ in 0..2 {
+            project
+                .set_build_setting(
+                    "Runner",
+                    "CODE_SIGN_ENTITLEMENTS",
+                    "Runner/Runner.entitlements",
+                )
+                .unwrap();
+        }
+        let source = project.source();
+        // Runner's Debug and Debug-dev, not the project's or RunnerTests'
+        assert_eq!(
+            source
+                .matches("\t\t\t\tCODE_SIGN_ENTITLEMENTS = Runner/Runner.entitlements;\n\t\t\t\tPRODUCT_BUNDLE_IDENTIFIER = com.example.todo;")
+                .count(),
+            1
+        );
+        assert_eq!(source.matches("CODE_SIGN_ENTITLEMENTS").count(), 2);
+        let tests = &source[source
+            .find("331C80881294CF71000E1FA1 /* Debug */ = {")
+            .unwrap()..];
+        assert!(!tests[..tests.find("};\n").unwrap()].contains("CODE_SIGN_ENTITLEMENTS"));
+
+        project
+            .set_build_setting(
+                "Runner",
+                "CODE_SIGN_ENTITLEMENTS",
+                "Runner/Other.entitlements",
+            )
+            .unwrap();
+        let source = project.source();
+        assert_eq!(source.matches("CODE_SIGN_ENTITLEMENTS").count(), 2);
+        assert_eq!(source.matches("Runner/Other.entitlements;").count(), 2);
+        assert!(project.set_build_setting("Missing", "A", "b").is_err());
+    }
+
+    #[test]
+    fn needs_the_sections() {
+        let mut project =
+            XcodeProject::parse(Path::new("project.pbxproj"), "{}".to_string()).unwrap();
+        assert!(project.add_flavor("dev", "a", "b").is_err());
+    }
+}
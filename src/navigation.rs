@@ -0,0 +1,451 @@
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::form::label;
+use crate::pascal_case;
+use crate::project::ProjectConfig;
+use crate::router::{write_router, RouterKind, AUTH_FEATURES};
+use crate::ui_kit::UiKit;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum NavStyle {
+    #[default]
+    BottomBar,
+    Rail,
+}
+
+impl fmt::Display for NavStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NavStyle::BottomBar => write!(f, "Bottom navigation bar"),
+            NavStyle::Rail => write!(f, "Navigation rail"),
+        }
+    }
+}
+
+// Top-level features shown as tabs of a `StatefulShellRoute`, one branch each
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Navigation {
+    #[serde(default)]
+    pub style: NavStyle,
+    pub tabs: Vec<String>,
+}
+
+// Icon and selected icon of a tab in the kit's icon set, guessed from the
+// feature name
+fn tab_icons(kit: UiKit, feature_name: &str) -> (String, String) {
+    let (outlined, filled, cupertino, cupertino_filled, fluent) = match feature_name {
+        "home" => ("home_outlined", "home", "house", "house_fill", "home"),
+        "search" => ("search_outlined", "search", "search", "search", "search"),
+        "explore" | "discover" => (
+            "explore_outlined",
+            "explore",
+            "compass",
+            "compass_fill",
+            "globe",
+        ),
+        "profile" | "account" => (
+            "person_outlined",
+            "person",
+            "person",
+            "person_fill",
+            "contact",
+        ),
+        "settings" => (
+            "settings_outlined",
+            "settings",
+            "settings",
+            "settings_solid",
+            "settings",
+        ),
+        "notifications" => (
+            "notifications_outlined",
+            "notifications",
+            "bell",
+            "bell_fill",
+            "ringer",
+        ),
+        "chat" | "messages" | "inbox" => (
+            "chat_outlined",
+            "chat",
+            "chat_bubble",
+            "chat_bubble_fill",
+            "chat",
+        ),
+        "cart" => (
+            "shopping_cart_outlined",
+            "shopping_cart",
+            "cart",
+            "cart_fill",
+            "shopping_cart",
+        ),
+        "orders" => (
+            "receipt_long_outlined",
+            "receipt_long",
+            "doc_text",
+            "doc_text_fill",
+            "list",
+        ),
+        "favorites" => (
+            "favorite_outline",
+            "favorite",
+            "heart",
+            "heart_fill",
+            "heart",
+        ),
+        "dashboard" => (
+            "dashboard_outlined",
+            "dashboard",
+            "square_grid_2x2",
+            "square_grid_2x2_fill",
+            "view_dashboard",
+        ),
+        _ => (
+            "folder_outlined",
+            "folder",
+            "folder",
+            "folder_fill",
+            "folder",
+        ),
+    };
+    match kit {
+        UiKit::Shadcn | UiKit::Material => {
+            (format!("Icons.{}", outlined), format!("Icons.{}", filled))
+        }
+        UiKit::Cupertino => (
+            format!("CupertinoIcons.{}", cupertino),
+            format!("CupertinoIcons.{}", cupertino_filled),
+        ),
+        // Fluent icons have no filled variants, the pane highlights the tab
+        UiKit::Fluent => (
+            format!("FluentIcons.{}", fluent),
+            format!("FluentIcons.{}", fluent),
+        ),
+    }
+}
+
+pub fn generate_app_shell_template(config: &ProjectConfig, navigation: &Navigation) -> String {
+    let kit = config.ui_kit;
+    let auto_route = config.router == RouterKind::AutoRoute;
+    let (body, current_index, on_selected) = if auto_route {
+        (
+            "child",
+            "tabsRouter.activeIndex",
+            "tabsRouter.setActiveIndex",
+        )
+    } else {
+        (
+            "navigationShell",
+            "navigationShell.currentIndex",
+            "_onDestinationSelected",
+        )
+    };
+
+    let destinations = navigation
+        .tabs
+        .iter()
+        .enumerate()
+        .map(|(index, tab)| {
+            let (icon, selected) = tab_icons(kit, tab);
+            let label = label(tab);
+            match (kit, navigation.style) {
+                (UiKit::Shadcn | UiKit::Material, NavStyle::BottomBar) => format!(
+                    r#"          NavigationDestination(
+            icon: Icon({icon}),
+            selectedIcon: Icon({selected}),
+            label: '{label}',
+          ),"#
+                ),
+                (UiKit::Shadcn | UiKit::Material, NavStyle::Rail) => format!(
+                    r#"              NavigationRailDestination(
+                icon: Icon({icon}),
+                selectedIcon: Icon({selected}),
+                label: Text('{label}'),
+              ),"#
+                ),
+                (UiKit::Cupertino, NavStyle::BottomBar) => format!(
+                    r#"              BottomNavigationBarItem(
+                icon: Icon({icon}),
+                activeIcon: Icon({selected}),
+                label: '{label}',
+              ),"#
+                ),
+                (UiKit::Cupertino, NavStyle::Rail) => format!(
+                    r#"                CupertinoButton(
+                  onPressed: () => {on_selected}({index}),
+                  child: Column(
+                    mainAxisSize: MainAxisSize.min,
+                    children: [
+                      Icon(
+                        {current_index} == {index}
+                            ? {selected}
+                            : {icon},
+                      ),
+                      const Text('{label}', style: TextStyle(fontSize: 11)),
+                    ],
+                  ),
+                ),"#
+                ),
+                (UiKit::Fluent, _) => format!(
+                    r#"          PaneItem(
+            icon: const Icon({icon}),
+            title: const Text('{label}'),
+            body: const SizedBox.shrink(),
+          ),"#
+                ),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let scaffold = match (kit, navigation.style) {
+        (UiKit::Shadcn | UiKit::Material, NavStyle::BottomBar) => format!(
+            r#"Scaffold(
+      body: {body},
+      bottomNavigationBar: NavigationBar(
+        selectedIndex: {current_index},
+        onDestinationSelected: {on_selected},
+        destinations: const [
+{destinations}
+        ],
+      ),
+    )"#
+        ),
+        (UiKit::Shadcn | UiKit::Material, NavStyle::Rail) => format!(
+            r#"Scaffold(
+      body: Row(
+        children: [
+          NavigationRail(
+            selectedIndex: {current_index},
+            onDestinationSelected: {on_selected},
+            labelType: NavigationRailLabelType.all,
+            destinations: const [
+{destinations}
+            ],
+          ),
+          const VerticalDivider(thickness: 1, width: 1),
+          Expanded(child: {body}),
+        ],
+      ),
+    )"#
+        ),
+        (UiKit::Cupertino, NavStyle::BottomBar) => format!(
+            r#"CupertinoPageScaffold(
+      child: Column(
+        children: [
+          Expanded(child: {body}),
+          CupertinoTabBar(
+            currentIndex: {current_index},
+            onTap: {on_selected},
+            items: const [
+{destinations}
+            ],
+          ),
+        ],
+      ),
+    )"#
+        ),
+        // Cupertino has no rail, so the tabs are buttons in a sidebar
+        (UiKit::Cupertino, NavStyle::Rail) => format!(
+            r#"CupertinoPageScaffold(
+      child: Row(
+        children: [
+          SafeArea(
+            right: false,
+            child: Column(
+              children: [
+{destinations}
+              ],
+            ),
+          ),
+          Container(
+            width: 1,
+            color: CupertinoColors.separator.resolveFrom(context),
+          ),
+          Expanded(child: {body}),
+        ],
+      ),
+    )"#
+        ),
+        // Fluent has no bottom bar, its pane shows the tabs at the top instead
+        (UiKit::Fluent, style) => format!(
+            r#"NavigationView(
+      pane: NavigationPane(
+        selected: {current_index},
+        onChanged: {on_selected},
+        displayMode: PaneDisplayMode.{mode},
+        items: [
+{destinations}
+        ],
+      ),
+      paneBodyBuilder: (item, _) => {body},
+    )"#,
+            mode = match style {
+                NavStyle::BottomBar => "top",
+                NavStyle::Rail => "compact",
+            }
+        ),
+    };
+    let widgets_import = kit.widgets_import();
+
+    if auto_route {
+        let routes = navigation
+            .tabs
+            .iter()
+            .map(|tab| {
+                let root = config
+                    .features
+                    .iter()
+                    .find(|f| &f.name == tab)
+                    .map(|f| f.root_screen())
+                    .unwrap_or(tab);
+                format!("        {}Route(),", pascal_case(root))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let scaffold = scaffold.replace('\n', "\n    ");
+
+        return format!(
+            r#"import 'package:auto_route/auto_route.dart';
+{widgets_import}
+import 'package:{project_name}/app/router.gr.dart';
+
+@RoutePage()
+class AppShell extends StatelessWidget {{
+  const AppShell({{super.key}});
+
+  @override
+  Widget build(BuildContext context) {{
+    return AutoTabsRouter(
+      routes: const [
+{routes}
+      ],
+      builder: (context, child) {{
+        final tabsRouter = AutoTabsRouter.of(context);
+
+        return {scaffold};
+      }},
+    );
+  }}
+}}
+"#,
+            project_name = config.name
+        );
+    }
+
+    format!(
+        r#"{widgets_import}
+import 'package:go_router/go_router.dart';
+
+class AppShell extends StatelessWidget {{
+  const AppShell({{super.key, required this.navigationShell}});
+
+  final StatefulNavigationShell navigationShell;
+
+  void _onDestinationSelected(int index) {{
+    navigationShell.goBranch(
+      index,
+      // Tapping the active tab goes back to the first screen of its branch
+      initialLocation: index == navigationShell.currentIndex,
+    );
+  }}
+
+  @override
+  Widget build(BuildContext context) {{
+    return {scaffold};
+  }}
+}}
+"#
+    )
+}
+
+pub fn validate_tabs(config: &ProjectConfig, tabs: &[String]) -> Result<()> {
+    if tabs.len() < 2 {
+        bail!("Declare at least two tabs");
+    }
+    for (i, tab) in tabs.iter().enumerate() {
+        if tabs[..i].contains(tab) {
+            bail!("`{}` is listed twice, every tab needs its own feature", tab);
+        }
+        if !config.features.iter().any(|f| &f.name == tab) {
+            bail!("Unknown feature `{}`", tab);
+        }
+        if AUTH_FEATURES.contains(&tab.as_str()) {
+            bail!("`{}` is routed under /auth and cannot be a tab", tab);
+        }
+    }
+    Ok(())
+}
+
+pub fn write_app_shell(
+    project_dir: &Path,
+    config: &ProjectConfig,
+    navigation: &Navigation,
+) -> Result<()> {
+    fs::write(
+        project_dir.join("lib").join("app").join("app_shell.dart"),
+        generate_app_shell_template(config, navigation),
+    )?;
+    Ok(())
+}
+
+pub fn add_tabs(project_dir: &Path, tabs: Vec<String>, style: NavStyle) -> Result<()> {
+    let mut config = ProjectConfig::load(project_dir)?;
+    validate_tabs(&config, &tabs)?;
+
+    let navigation = Navigation { style, tabs };
+    write_app_shell(project_dir, &config, &navigation)?;
+    config.navigation = Some(navigation);
+
+    write_router(project_dir, &config)?;
+    config.save(project_dir)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn app_shell_uses_the_ui_kit() {
+        for kit in UiKit::ALL {
+            for router in ["go_router", "auto_route"] {
+                for style in [NavStyle::BottomBar, NavStyle::Rail] {
+                    let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+                        "name": "app",
+                        "package": "com.example",
+                        "use_riverpod": true,
+                        "use_supabase": false,
+                        "ui_kit": kit,
+                        "router": router,
+                        "features": [
+                            { "name": "home", "layers": [] },
+                            { "name": "settings", "layers": [] },
+                        ],
+                    }))
+                    .unwrap();
+                    let navigation = Navigation {
+                        style,
+                        tabs: vec!["home".to_string(), "settings".to_string()],
+                    };
+                    let shell = generate_app_shell_template(&config, &navigation);
+                    assert!(shell.contains(kit.widgets_import()));
+                    assert!(shell.contains(&tab_icons(kit, "settings").0));
+                    if matches!(kit, UiKit::Cupertino | UiKit::Fluent) {
+                        for material in
+                            [" Scaffold(", "NavigationBar(", "NavigationRail", "(Icons."]
+                        {
+                            assert!(!shell.contains(material), "{} uses {}", kit, material);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use crate::di;
+use crate::project::ProjectConfig;
+use crate::{camel_case, pascal_case};
+
+const API_EXCEPTION_TEMPLATE: &str = r#"import 'package:dio/dio.dart';
+
+/// Failure of a request made through `ApiClient`.
+sealed class ApiException implements Exception {
+  const ApiException(this.message, {this.statusCode});
+
+  final String message;
+  final int? statusCode;
+
+  factory ApiException.fromDioException(DioException error) {
+    final status = error.response?.statusCode;
+    switch (error.type) {
+      case DioExceptionType.connectionTimeout:
+      case DioExceptionType.sendTimeout:
+      case DioExceptionType.receiveTimeout:
+        return const TimeoutApiException();
+      case DioExceptionType.connectionError:
+        return const NetworkException();
+      case DioExceptionType.cancel:
+        return const CancelledException();
+      case DioExceptionType.badResponse:
+        final message = _messageOf(error.response?.data);
+        return switch (status) {
+          401 => UnauthorizedException(message),
+          403 => ForbiddenException(message),
+          404 => NotFoundException(message),
+          final code? when code >= 500 => ServerException(code, message),
+          _ => BadRequestException(status, message),
+        };
+      case DioExceptionType.badCertificate:
+      case DioExceptionType.unknown:
+        return UnknownApiException(error.message ?? '${error.error}');
+    }
+  }
+
+  // APIs usually explain errors in a `message` field
+  static String? _messageOf(Object? data) {
+    if (data is Map && data['message'] is String) {
+      return data['message'] as String;
+    }
+    return null;
+  }
+
+  @override
+  String toString() => '$runtimeType($statusCode): $message';
+}
+
+class NetworkException extends ApiException {
+  const NetworkException() : super('No internet connection');
+}
+
+class TimeoutApiException extends ApiException {
+  const TimeoutApiException() : super('The server took too long to respond');
+}
+
+class CancelledException extends ApiException {
+  const CancelledException() : super('The request was cancelled');
+}
+
+class UnauthorizedException extends ApiException {
+  const UnauthorizedException([String? message])
+      : super(message ?? 'Please sign in again', statusCode: 401);
+}
+
+class ForbiddenException extends ApiException {
+  const ForbiddenException([String? message])
+      : super(message ?? 'You are not allowed to do this', statusCode: 403);
+}
+
+class NotFoundException extends ApiException {
+  const NotFoundException([String? message])
+      : super(message ?? 'Not found', statusCode: 404);
+}
+
+class ServerException extends ApiException {
+  const ServerException(int statusCode, [String? message])
+      : super(message ?? 'Something went wrong on the server',
+            statusCode: statusCode);
+}
+
+class BadRequestException extends ApiException {
+  const BadRequestException(int? statusCode, [String? message])
+      : super(message ?? 'The request was rejected', statusCode: statusCode);
+}
+
+class UnknownApiException extends ApiException {
+  const UnknownApiException(super.message);
+}
+"#;
+
+const INTERCEPTORS_TEMPLATE: &str = r#"import 'package:dio/dio.dart';
+import 'package:logging/logging.dart';
+
+typedef TokenProvider = Future<String?> Function();
+
+/// Sends the signed in user's token as a bearer token.
+class AuthInterceptor extends Interceptor {
+  AuthInterceptor(this._token);
+
+  final TokenProvider _token;
+
+  @override
+  Future<void> onRequest(
+    RequestOptions options,
+    RequestInterceptorHandler handler,
+  ) async {
+    final token = await _token();
+    if (token != null) {
+      options.headers['Authorization'] = 'Bearer $token';
+    }
+    handler.next(options);
+  }
+}
+
+/// Retries idempotent requests after connection errors, timeouts and server
+/// errors, waiting twice as long before each attempt.
+class RetryInterceptor extends Interceptor {
+  RetryInterceptor(
+    this._dio, {
+    this.retries = 3,
+    this.delay = const Duration(milliseconds: 500),
+  });
+
+  final Dio _dio;
+  final int retries;
+  final Duration delay;
+
+  static const _idempotent = {'GET', 'HEAD', 'PUT', 'DELETE', 'OPTIONS'};
+  static const _attemptKey = 'retry_attempt';
+
+  @override
+  Future<void> onError(
+    DioException err,
+    ErrorInterceptorHandler handler,
+  ) async {
+    final options = err.requestOptions;
+    final attempt = options.extra[_attemptKey] as int? ?? 0;
+    if (attempt >= retries || !_shouldRetry(err)) {
+      return handler.next(err);
+    }
+
+    await Future<void>.delayed(delay * (1 << attempt));
+    options.extra[_attemptKey] = attempt + 1;
+    try {
+      handler.resolve(await _dio.fetch<dynamic>(options));
+    } on DioException catch (e) {
+      handler.next(e);
+    }
+  }
+
+  bool _shouldRetry(DioException err) {
+    if (!_idempotent.contains(err.requestOptions.method.toUpperCase())) {
+      return false;
+    }
+    return switch (err.type) {
+      DioExceptionType.connectionError ||
+      DioExceptionType.connectionTimeout ||
+      DioExceptionType.sendTimeout ||
+      DioExceptionType.receiveTimeout =>
+        true,
+      DioExceptionType.badResponse => (err.response?.statusCode ?? 0) >= 500,
+      _ => false,
+    };
+  }
+}
+
+/// Logs requests and their outcome through the `logging` package.
+class LoggingInterceptor extends Interceptor {
+  final _logger = Logger('ApiClient');
+
+  @override
+  void onRequest(RequestOptions options, RequestInterceptorHandler handler) {
+    _logger.fine('--> ${options.method} ${options.uri}');
+    handler.next(options);
+  }
+
+  @override
+  void onResponse(
+    Response<dynamic> response,
+    ResponseInterceptorHandler handler,
+  ) {
+    _logger.fine(
+      '<-- ${response.statusCode} ${response.requestOptions.method} '
+      '${response.requestOptions.uri}',
+    );
+    handler.next(response);
+  }
+
+  @override
+  void onError(DioException err, ErrorInterceptorHandler handler) {
+    _logger.warning(
+      '<-- ${err.response?.statusCode ?? err.type.name} '
+      '${err.requestOptions.method} ${err.requestOptions.uri}',
+      err.error,
+    );
+    handler.next(err);
+  }
+}
+"#;
+
+fn generate_api_client_template(config: &ProjectConfig) -> String {
+    let project_name = &config.name;
+    let provider = if !config.use_riverpod {
+        String::new()
+    } else if config.get_it {
+        // get_it builds the client, the provider shares that instance
+        r#"
+
+final apiClientProvider = Provider<ApiClient>((ref) => getIt<ApiClient>());
+"#
+        .to_string()
+    } else if config.use_supabase {
+        r#"
+
+final apiClientProvider = Provider<ApiClient>(
+  (ref) => ApiClient(
+    token: () async => ref.read(authServiceProvider).accessToken,
+  ),
+);
+"#
+        .to_string()
+    } else {
+        r#"
+
+final apiClientProvider = Provider<ApiClient>(
+  // TODO: Read the token from your auth layer
+  (ref) => ApiClient(token: () async => null),
+);
+"#
+        .to_string()
+    };
+    let mut imports = vec![
+        "import 'package:dio/dio.dart';".to_string(),
+        format!("import 'package:{project_name}/core/constants/env.dart';"),
+        format!("import 'package:{project_name}/core/network/api_exception.dart';"),
+        format!("import 'package:{project_name}/core/network/interceptors.dart';"),
+    ];
+    if config.use_riverpod {
+        imports.insert(
+            1,
+            "import 'package:hooks_riverpod/hooks_riverpod.dart';".to_string(),
+        );
+    }
+    if config.use_riverpod && config.get_it {
+        imports.push(format!(
+            "import 'package:{project_name}/core/di/injection.dart';"
+        ));
+    } else if config.use_riverpod && config.use_supabase {
+        imports.push(format!(
+            "import 'package:{project_name}/core/services/auth_service_provider.dart';"
+        ));
+    }
+
+    format!(
+        r#"{imports}
+
+/// HTTP client of the app's API, failures are thrown as [ApiException].
+class ApiClient {{
+  ApiClient({{TokenProvider? token, Dio? dio}})
+      : _dio = dio ??
+            Dio(
+              BaseOptions(
+                baseUrl: Env.apiBaseUrl,
+                connectTimeout: const Duration(seconds: 10),
+                receiveTimeout: const Duration(seconds: 20),
+                headers: {{'Accept': 'application/json'}},
+              ),
+            ) {{
+    _dio.interceptors.addAll([
+      AuthInterceptor(token ?? () async => null),
+      RetryInterceptor(_dio),
+      LoggingInterceptor(),
+    ]);
+  }}
+
+  final Dio _dio;
+
+  Future<T> get<T>(String path, {{Map<String, dynamic>? query}}) =>
+      _send(() => _dio.get<T>(path, queryParameters: query));
+
+  Future<T> post<T>(String path, {{Object? data}}) =>
+      _send(() => _dio.post<T>(path, data: data));
+
+  Future<T> put<T>(String path, {{Object? data}}) =>
+      _send(() => _dio.put<T>(path, data: data));
+
+  Future<T> patch<T>(String path, {{Object? data}}) =>
+      _send(() => _dio.patch<T>(path, data: data));
+
+  Future<T> delete<T>(String path, {{Object? data}}) =>
+      _send(() => _dio.delete<T>(path, data: data));
+
+  Future<T> _send<T>(Future<Response<T>> Function() request) async {{
+    try {{
+      final response = await request();
+      return response.data as T;
+    }} on DioException catch (e) {{
+      throw ApiException.fromDioException(e);
+    }}
+  }}
+}}{provider}"#,
+        imports = imports.join("\n"),
+    )
+}
+
+// Files below `lib/core/network`
+pub fn network_files(config: &ProjectConfig) -> Vec<(&'static str, String)> {
+    vec![
+        (
+            "network/api_client.dart",
+            generate_api_client_template(config),
+        ),
+        (
+            "network/api_exception.dart",
+            API_EXCEPTION_TEMPLATE.to_string(),
+        ),
+        (
+            "network/interceptors.dart",
+            INTERCEPTORS_TEMPLATE.to_string(),
+        ),
+    ]
+}
+
+// Repository of a feature without a table, calling `/<feature>` of the API
+pub fn generate_api_repository_template(config: &ProjectConfig, feature_name: &str) -> String {
+    let project_name = &config.name;
+    let pascal = pascal_case(feature_name);
+    let provider = if config.use_riverpod {
+        format!(
+            r#"
+
+final {camel}RepositoryProvider = Provider<{pascal}Repository>(
+  (ref) => {repository},
+);
+"#,
+            camel = camel_case(feature_name),
+            repository = di::resolve(
+                config,
+                &format!("{pascal}Repository"),
+                &format!("{pascal}Repository(ref.watch(apiClientProvider))"),
+            ),
+        )
+    } else {
+        "\n".to_string()
+    };
+    let riverpod_import = if config.use_riverpod {
+        format!(
+            "import 'package:hooks_riverpod/hooks_riverpod.dart';\n{}",
+            di::import(config)
+        )
+    } else {
+        String::new()
+    };
+
+    // Failures of the client come back as `Result`s with typed errors
+    let (result_import, methods) = if config.typed_errors {
+        (
+            format!("\nimport 'package:{project_name}/core/errors/result.dart';"),
+            r#"  Future<Result<List<Map<String, dynamic>>>> fetchAll() {
+    return guard(() async {
+      final data = await _client.get<List<dynamic>>(_path);
+      return data.cast<Map<String, dynamic>>();
+    });
+  }
+
+  Future<Result<Map<String, dynamic>>> fetchById(Object id) =>
+      guard(() => _client.get<Map<String, dynamic>>('$_path/$id'));
+
+  Future<Result<Map<String, dynamic>>> create(Map<String, dynamic> data) =>
+      guard(() => _client.post<Map<String, dynamic>>(_path, data: data));
+
+  Future<Result<Map<String, dynamic>>> update(
+    Object id,
+    Map<String, dynamic> data,
+  ) =>
+      guard(() => _client.put<Map<String, dynamic>>('$_path/$id', data: data));
+
+  Future<Result<void>> delete(Object id) =>
+      guard(() => _client.delete<void>('$_path/$id'));"#,
+        )
+    } else {
+        (
+            String::new(),
+            r#"  Future<List<Map<String, dynamic>>> fetchAll() async {
+    final data = await _client.get<List<dynamic>>(_path);
+    return data.cast<Map<String, dynamic>>();
+  }
+
+  Future<Map<String, dynamic>> fetchById(Object id) =>
+      _client.get<Map<String, dynamic>>('$_path/$id');
+
+  Future<Map<String, dynamic>> create(Map<String, dynamic> data) =>
+      _client.post<Map<String, dynamic>>(_path, data: data);
+
+  Future<Map<String, dynamic>> update(Object id, Map<String, dynamic> data) =>
+      _client.put<Map<String, dynamic>>('$_path/$id', data: data);
+
+  Future<void> delete(Object id) => _client.delete<void>('$_path/$id');"#,
+        )
+    };
+
+    format!(
+        r#"{riverpod_import}import 'package:{project_name}/core/network/api_client.dart';{result_import}
+
+class {pascal}Repository {{
+  {pascal}Repository(this._client);
+
+  final ApiClient _client;
+
+  static const _path = '/{feature_name}';
+
+{methods}
+}}{provider}"#
+    )
+}
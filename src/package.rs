@@ -0,0 +1,613 @@
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::env;
+use crate::l10n;
+use crate::network;
+use crate::platform::TargetPlatform;
+use crate::project::ProjectConfig;
+use crate::router;
+use crate::storage::{self, LocalDatabase};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Networking,
+    Storage,
+    Ui,
+    Analytics,
+    Platform,
+    Monitoring,
+    Tooling,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Category::Networking => write!(f, "networking"),
+            Category::Storage => write!(f, "storage"),
+            Category::Ui => write!(f, "ui"),
+            Category::Analytics => write!(f, "analytics"),
+            Category::Platform => write!(f, "platform"),
+            Category::Monitoring => write!(f, "monitoring"),
+            Category::Tooling => write!(f, "tooling"),
+        }
+    }
+}
+
+// Packages the app can do without, each with the glue code generated for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum OptionalPackage {
+    Dio,
+    ConnectivityPlus,
+    Sqflite,
+    SimpleCircularProgressBar,
+    FirebaseAnalytics,
+    DeviceInfoPlus,
+    SharePlus,
+    FlutterBackgroundService,
+    Logging,
+    FlutterLauncherIcons,
+    FlutterNativeSplash,
+}
+
+impl fmt::Display for OptionalPackage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.package())
+    }
+}
+
+impl OptionalPackage {
+    pub const ALL: [OptionalPackage; 11] = [
+        OptionalPackage::Dio,
+        OptionalPackage::ConnectivityPlus,
+        OptionalPackage::Sqflite,
+        OptionalPackage::SimpleCircularProgressBar,
+        OptionalPackage::FirebaseAnalytics,
+        OptionalPackage::DeviceInfoPlus,
+        OptionalPackage::SharePlus,
+        OptionalPackage::FlutterBackgroundService,
+        OptionalPackage::Logging,
+        OptionalPackage::FlutterLauncherIcons,
+        OptionalPackage::FlutterNativeSplash,
+    ];
+
+    pub fn package(self) -> &'static str {
+        match self {
+            OptionalPackage::Dio => "dio",
+            OptionalPackage::ConnectivityPlus => "connectivity_plus",
+            OptionalPackage::Sqflite => "sqflite",
+            OptionalPackage::SimpleCircularProgressBar => "simple_circular_progress_bar",
+            OptionalPackage::FirebaseAnalytics => "firebase_analytics",
+            OptionalPackage::DeviceInfoPlus => "device_info_plus",
+            OptionalPackage::SharePlus => "share_plus",
+            OptionalPackage::FlutterBackgroundService => "flutter_background_service",
+            OptionalPackage::Logging => "logging",
+            OptionalPackage::FlutterLauncherIcons => "flutter_launcher_icons",
+            OptionalPackage::FlutterNativeSplash => "flutter_native_splash",
+        }
+    }
+
+    pub fn category(self) -> Category {
+        match self {
+            OptionalPackage::Dio | OptionalPackage::ConnectivityPlus => Category::Networking,
+            OptionalPackage::Sqflite => Category::Storage,
+            OptionalPackage::SimpleCircularProgressBar => Category::Ui,
+            OptionalPackage::FirebaseAnalytics => Category::Analytics,
+            OptionalPackage::DeviceInfoPlus
+            | OptionalPackage::SharePlus
+            | OptionalPackage::FlutterBackgroundService => Category::Platform,
+            OptionalPackage::Logging => Category::Monitoring,
+            OptionalPackage::FlutterLauncherIcons | OptionalPackage::FlutterNativeSplash => {
+                Category::Tooling
+            }
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            OptionalPackage::Dio => "API client with auth, retry and logging",
+            OptionalPackage::ConnectivityPlus => "online/offline state",
+            OptionalPackage::Sqflite => "SQLite database",
+            OptionalPackage::SimpleCircularProgressBar => "circular progress widget",
+            OptionalPackage::FirebaseAnalytics => "event and screen tracking",
+            OptionalPackage::DeviceInfoPlus => "device and OS details",
+            OptionalPackage::SharePlus => "platform share sheet",
+            OptionalPackage::FlutterBackgroundService => "work while the app is closed",
+            OptionalPackage::Logging => "leveled log output",
+            OptionalPackage::FlutterLauncherIcons => "app icon generation",
+            OptionalPackage::FlutterNativeSplash => "native splash screen",
+        }
+    }
+
+    // Plugins without web and desktop implementations
+    pub fn mobile_only(self) -> bool {
+        matches!(
+            self,
+            OptionalPackage::Sqflite | OptionalPackage::FlutterBackgroundService
+        )
+    }
+
+    // Command line tools, never imported by the app
+    fn is_dev(self) -> bool {
+        matches!(
+            self,
+            OptionalPackage::FlutterLauncherIcons | OptionalPackage::FlutterNativeSplash
+        )
+    }
+
+    // Packages the glue code imports besides this one
+    fn requires(self) -> &'static [&'static str] {
+        match self {
+            OptionalPackage::Sqflite => &["path"],
+            OptionalPackage::FirebaseAnalytics => &["firebase_core"],
+            _ => &[],
+        }
+    }
+
+    // Selected unless the user opts out
+    fn is_default(self) -> bool {
+        matches!(
+            self,
+            OptionalPackage::Logging
+                | OptionalPackage::FlutterLauncherIcons
+                | OptionalPackage::FlutterNativeSplash
+        )
+    }
+
+    // Label of the multi-select prompt
+    pub fn label(self) -> String {
+        format!(
+            "{:<11} {} - {}",
+            self.category().to_string(),
+            self.package(),
+            self.description()
+        )
+    }
+}
+
+// Packages a project for `platforms` gets without choosing any
+pub fn default_packages(platforms: &[TargetPlatform]) -> Vec<OptionalPackage> {
+    available_packages(platforms)
+        .into_iter()
+        .filter(|p| p.is_default())
+        .collect()
+}
+
+pub fn available_packages(platforms: &[TargetPlatform]) -> Vec<OptionalPackage> {
+    let mobile = platforms.iter().any(|p| p.is_mobile());
+    OptionalPackage::ALL
+        .into_iter()
+        .filter(|p| mobile || !p.mobile_only())
+        .collect()
+}
+
+// The defaults adjusted by `--with` and `--without`
+pub fn selected_packages(
+    platforms: &[TargetPlatform],
+    with: &[OptionalPackage],
+    without: &[OptionalPackage],
+) -> Result<Vec<OptionalPackage>> {
+    let available = available_packages(platforms);
+    let mut packages = default_packages(platforms);
+    for &package in with {
+        if without.contains(&package) {
+            bail!("{} is listed in both --with and --without", package);
+        }
+        if !available.contains(&package) {
+            bail!("{} needs Android or iOS among the platforms", package);
+        }
+        if !packages.contains(&package) {
+            packages.push(package);
+        }
+    }
+    packages.retain(|p| !without.contains(p));
+    Ok(packages)
+}
+
+// Dependencies and dev dependencies of everything the project uses
+pub fn dependencies(config: &ProjectConfig) -> (Vec<&'static str>, Vec<&'static str>) {
+    let mut dependencies: Vec<&'static str> = config.ui_kit.dependency().into_iter().collect();
+    let mut dev_dependencies = vec!["flutter_lints", "very_good_analysis"];
+
+    for package in OptionalPackage::ALL {
+        if !config.uses_package(package) {
+            continue;
+        }
+        if package.is_dev() {
+            dev_dependencies.push(package.package());
+        } else {
+            dependencies.push(package.package());
+        }
+        dependencies.extend(package.requires());
+    }
+
+    if env::uses_env(config) {
+        dependencies.push("flutter_dotenv");
+    }
+    if config.requests_permissions() {
+        dependencies.push("permission_handler");
+    }
+
+    let router = router::backend(config);
+    dependencies.extend(router.dependencies());
+    dev_dependencies.extend(router.dev_dependencies(config));
+
+    // The app shell is a `ConsumerWidget` in a `ProviderScope` either way
+    dependencies.push("flutter_riverpod");
+    if config.use_riverpod {
+        dependencies.extend(["hooks_riverpod", "flutter_hooks", "riverpod_annotation"]);
+        dev_dependencies.push("riverpod_generator");
+    }
+    if config.get_it {
+        dependencies.push("get_it");
+    }
+    if l10n::is_localized(config) {
+        dependencies.extend(["flutter_localizations", "intl"]);
+    }
+    if let Some(db) = config.local_database {
+        dependencies.extend(db.dependencies());
+        dev_dependencies.extend(db.dev_dependencies());
+    }
+    if config.use_riverpod || router.uses_codegen(config) || storage::uses_codegen(config) {
+        dev_dependencies.push("build_runner");
+    }
+
+    if config.use_supabase {
+        dependencies.push("supabase_flutter");
+    }
+
+    (dependencies, dev_dependencies)
+}
+
+fn generate_connectivity_template(config: &ProjectConfig) -> String {
+    let provider = if config.use_riverpod {
+        r#"
+
+/// Whether the device is online, updated as connectivity changes.
+final isOnlineProvider = StreamProvider<bool>((ref) async* {
+  final connectivity = ConnectivityService();
+  yield await connectivity.isOnline();
+  yield* connectivity.onlineChanges;
+});
+"#
+    } else {
+        "\n"
+    };
+    let riverpod_import = if config.use_riverpod {
+        "import 'package:flutter_riverpod/flutter_riverpod.dart';\n"
+    } else {
+        ""
+    };
+
+    format!(
+        r#"import 'package:connectivity_plus/connectivity_plus.dart';
+{riverpod_import}
+class ConnectivityService {{
+  ConnectivityService([Connectivity? connectivity])
+      : _connectivity = connectivity ?? Connectivity();
+
+  final Connectivity _connectivity;
+
+  Future<bool> isOnline() async =>
+      _isOnline(await _connectivity.checkConnectivity());
+
+  Stream<bool> get onlineChanges =>
+      _connectivity.onConnectivityChanged.map(_isOnline);
+
+  static bool _isOnline(List<ConnectivityResult> results) =>
+      results.any((result) => result != ConnectivityResult.none);
+}}{provider}"#
+    )
+}
+
+fn generate_database_template(config: &ProjectConfig) -> String {
+    // The local database caches the feature models in its tables
+    let tables = if config.local_database == Some(LocalDatabase::Sqflite) {
+        storage::sqflite_tables(config)
+    } else {
+        "        // TODO: Create tables".to_string()
+    };
+    format!(
+        r#"import 'package:path/path.dart';
+import 'package:sqflite/sqflite.dart';
+
+class DatabaseService {{
+  DatabaseService._();
+  static final instance = DatabaseService._();
+
+  static const _name = '{}.db';
+  static const _version = 1;
+
+  Database? _database;
+
+  Future<Database> get database async => _database ??= await _open();
+
+  Future<Database> _open() async {{
+    final path = join(await getDatabasesPath(), _name);
+    return openDatabase(
+      path,
+      version: _version,
+      onCreate: (db, version) async {{
+{}
+      }},
+    );
+  }}
+}}
+"#,
+        config.name, tables
+    )
+}
+
+const PROGRESS_RING_TEMPLATE: &str = r#"import 'package:flutter/material.dart';
+import 'package:simple_circular_progress_bar/simple_circular_progress_bar.dart';
+
+/// Circular progress from 0 to 100 with the percentage in the middle.
+class ProgressRing extends StatefulWidget {
+  const ProgressRing({super.key, required this.progress, this.size = 64});
+
+  final double progress;
+  final double size;
+
+  @override
+  State<ProgressRing> createState() => _ProgressRingState();
+}
+
+class _ProgressRingState extends State<ProgressRing> {
+  late final _progress = ValueNotifier<double>(widget.progress);
+
+  @override
+  void didUpdateWidget(ProgressRing oldWidget) {
+    super.didUpdateWidget(oldWidget);
+    _progress.value = widget.progress;
+  }
+
+  @override
+  void dispose() {
+    _progress.dispose();
+    super.dispose();
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    return SimpleCircularProgressBar(
+      size: widget.size,
+      valueNotifier: _progress,
+      progressColors: [Theme.of(context).colorScheme.primary],
+      mergeMode: true,
+      onGetText: (value) => Text('${value.toInt()}%'),
+    );
+  }
+}
+"#;
+
+const ANALYTICS_TEMPLATE: &str = r#"import 'package:firebase_analytics/firebase_analytics.dart';
+
+class AnalyticsService {
+  AnalyticsService._();
+  static final instance = AnalyticsService._();
+
+  final _analytics = FirebaseAnalytics.instance;
+
+  Future<void> logEvent(String name, [Map<String, Object>? parameters]) =>
+      _analytics.logEvent(name: name, parameters: parameters);
+
+  Future<void> logScreen(String name) =>
+      _analytics.logScreenView(screenName: name);
+}
+"#;
+
+const SHARE_TEMPLATE: &str = r#"import 'package:share_plus/share_plus.dart';
+
+/// Opens the platform share sheet with [text].
+Future<ShareResult> shareText(String text, {String? subject}) =>
+    Share.share(text, subject: subject);
+"#;
+
+const BACKGROUND_SERVICE_TEMPLATE: &str = r#"import 'dart:ui';
+
+import 'package:flutter_background_service/flutter_background_service.dart';
+
+Future<void> initializeBackgroundService() async {
+  await FlutterBackgroundService().configure(
+    androidConfiguration: AndroidConfiguration(
+      onStart: onBackgroundStart,
+      autoStart: false,
+      isForegroundMode: false,
+    ),
+    iosConfiguration: IosConfiguration(
+      autoStart: false,
+      onForeground: onBackgroundStart,
+    ),
+  );
+}
+
+@pragma('vm:entry-point')
+void onBackgroundStart(ServiceInstance service) {
+  DartPluginRegistrant.ensureInitialized();
+  service.on('stop').listen((_) => service.stopSelf());
+  // TODO: Run background work
+}
+"#;
+
+const LOGGING_TEMPLATE: &str = r#"import 'package:flutter/foundation.dart';
+import 'package:logging/logging.dart';
+
+/// Prints log records, only warnings and errors in release builds.
+void setupLogging() {
+  Logger.root.level = kReleaseMode ? Level.WARNING : Level.ALL;
+  Logger.root.onRecord.listen((record) {
+    debugPrint('${record.level.name} ${record.loggerName}: ${record.message}');
+    if (record.error != null) {
+      debugPrint('${record.error}');
+    }
+    if (record.stackTrace != null) {
+      debugPrint('${record.stackTrace}');
+    }
+  });
+}
+"#;
+
+// Files below `lib/core` generated for the selected packages
+pub fn glue_files(config: &ProjectConfig) -> Vec<(&'static str, String)> {
+    let mut files = Vec::new();
+    for package in OptionalPackage::ALL {
+        if !config.uses_package(package) {
+            continue;
+        }
+        let file = match package {
+            OptionalPackage::Dio => {
+                files.extend(network::network_files(config));
+                continue;
+            }
+            OptionalPackage::ConnectivityPlus => (
+                "services/connectivity_service.dart",
+                generate_connectivity_template(config),
+            ),
+            OptionalPackage::Sqflite => (
+                "services/database_service.dart",
+                generate_database_template(config),
+            ),
+            OptionalPackage::SimpleCircularProgressBar => (
+                "widgets/progress_ring.dart",
+                PROGRESS_RING_TEMPLATE.to_string(),
+            ),
+            OptionalPackage::FirebaseAnalytics => (
+                "services/analytics_service.dart",
+                ANALYTICS_TEMPLATE.to_string(),
+            ),
+            OptionalPackage::SharePlus => ("utilities/share.dart", SHARE_TEMPLATE.to_string()),
+            OptionalPackage::FlutterBackgroundService => (
+                "services/background_service.dart",
+                BACKGROUND_SERVICE_TEMPLATE.to_string(),
+            ),
+            OptionalPackage::Logging => ("utilities/logging.dart", LOGGING_TEMPLATE.to_string()),
+            OptionalPackage::DeviceInfoPlus
+            | OptionalPackage::FlutterLauncherIcons
+            | OptionalPackage::FlutterNativeSplash => continue,
+        };
+        files.push(file);
+    }
+    files
+}
+
+// Configuration files the tooling packages read from the project root
+pub fn write_tool_configs(project_dir: &Path, config: &ProjectConfig) -> Result<()> {
+    let has = |platform| config.platforms.contains(&platform);
+
+    if config.uses_package(OptionalPackage::FlutterLauncherIcons) {
+        let mut icons =
+            "flutter_launcher_icons:\n  image_path: \"assets/icon/icon.png\"\n".to_string();
+        icons.push_str(&format!("  android: {}\n", has(TargetPlatform::Android)));
+        icons.push_str(&format!("  ios: {}\n", has(TargetPlatform::Ios)));
+        if has(TargetPlatform::Ios) {
+            icons.push_str("  remove_alpha_ios: true\n");
+        }
+        if has(TargetPlatform::Web) {
+            icons.push_str("  web:\n    generate: true\n");
+        }
+        if has(TargetPlatform::Windows) {
+            icons.push_str("  windows:\n    generate: true\n");
+        }
+        if has(TargetPlatform::Macos) {
+            icons.push_str("  macos:\n    generate: true\n");
+        }
+        fs::write(project_dir.join("flutter_launcher_icons.yaml"), icons)?;
+    }
+
+    if config.uses_package(OptionalPackage::FlutterNativeSplash) {
+        let mut splash =
+            "flutter_native_splash:\n  color: \"#ffffff\"\n  color_dark: \"#000000\"\n".to_string();
+        for platform in [
+            TargetPlatform::Android,
+            TargetPlatform::Ios,
+            TargetPlatform::Web,
+        ] {
+            if !has(platform) {
+                splash.push_str(&format!("  {}: false\n", platform));
+            }
+        }
+        fs::write(project_dir.join("flutter_native_splash.yaml"), splash)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    use OptionalPackage::*;
+
+    const MOBILE: [TargetPlatform; 2] = [TargetPlatform::Android, TargetPlatform::Ios];
+
+    #[test]
+    fn parses_with_and_without() {
+        let cli = crate::Cli::try_parse_from([
+            "flutter_gen",
+            "--with",
+            "dio,share_plus",
+            "--without",
+            "logging",
+        ])
+        .unwrap();
+        assert_eq!(cli.with_packages, [Dio, SharePlus]);
+        assert_eq!(cli.without_packages, [Logging]);
+
+        assert!(crate::Cli::try_parse_from(["flutter_gen", "--with", "dio,riverpod"]).is_err());
+        assert!(crate::Cli::try_parse_from(["flutter_gen", "--without", "Dio"]).is_err());
+    }
+
+    #[test]
+    fn flags_adjust_the_defaults() {
+        assert_eq!(
+            selected_packages(&MOBILE, &[], &[]).unwrap(),
+            default_packages(&MOBILE)
+        );
+        assert_eq!(
+            selected_packages(&MOBILE, &[Dio, Logging], &[FlutterNativeSplash]).unwrap(),
+            [Logging, FlutterLauncherIcons, Dio]
+        );
+        assert!(selected_packages(&MOBILE, &[Dio], &[Dio]).is_err());
+
+        // Mobile-only plugins need a mobile platform
+        assert!(selected_packages(&[TargetPlatform::Web], &[Sqflite], &[]).is_err());
+        assert!(!selected_packages(&[TargetPlatform::Web], &[], &[])
+            .unwrap()
+            .contains(&FlutterBackgroundService));
+    }
+
+    #[test]
+    fn implied_packages_are_dependencies() {
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "name": "app",
+            "package": "com.example",
+            "use_riverpod": true,
+            "use_supabase": true,
+            "features": [{ "name": "todo", "layers": [] }],
+            "packages": ["firebase_analytics"],
+            "local_database": "sqflite",
+        }))
+        .unwrap();
+        assert!(config.uses_package(Logging));
+        assert!(config.uses_package(Sqflite));
+        assert!(!config.uses_package(Dio));
+
+        let (dependencies, _) = dependencies(&config);
+        for package in [
+            "logging",
+            "sqflite",
+            "path",
+            "firebase_analytics",
+            "firebase_core",
+        ] {
+            assert!(dependencies.contains(&package), "{}", package);
+        }
+    }
+}
@@ -0,0 +1,576 @@
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::dart;
+use crate::native::{
+    self, AndroidManifest, ManifestParent, Plist, PlistValue, Podfile, FLUTTER_PODFILE,
+};
+use crate::package::OptionalPackage;
+use crate::project::ProjectConfig;
+use crate::pubspec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum AppPermission {
+    Camera,
+    Photos,
+    Location,
+    LocationAlways,
+    Microphone,
+    Notifications,
+    Contacts,
+    Calendar,
+    Bluetooth,
+    ActivityRecognition,
+}
+
+impl fmt::Display for AppPermission {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppPermission::Camera => write!(f, "camera"),
+            AppPermission::Photos => write!(f, "photos"),
+            AppPermission::Location => write!(f, "location"),
+            AppPermission::LocationAlways => write!(f, "location_always"),
+            AppPermission::Microphone => write!(f, "microphone"),
+            AppPermission::Notifications => write!(f, "notifications"),
+            AppPermission::Contacts => write!(f, "contacts"),
+            AppPermission::Calendar => write!(f, "calendar"),
+            AppPermission::Bluetooth => write!(f, "bluetooth"),
+            AppPermission::ActivityRecognition => write!(f, "activity_recognition"),
+        }
+    }
+}
+
+impl AppPermission {
+    pub const ALL: [AppPermission; 10] = [
+        AppPermission::Camera,
+        AppPermission::Photos,
+        AppPermission::Location,
+        AppPermission::LocationAlways,
+        AppPermission::Microphone,
+        AppPermission::Notifications,
+        AppPermission::Contacts,
+        AppPermission::Calendar,
+        AppPermission::Bluetooth,
+        AppPermission::ActivityRecognition,
+    ];
+
+    // Shown in the settings dialog and as the iOS usage description
+    fn default_rationale(self) -> &'static str {
+        match self {
+            AppPermission::Camera => "The camera is used to take photos and scan codes.",
+            AppPermission::Photos => "Your photo library is used to pick and save photos.",
+            AppPermission::Location => "Your location is used to show what is nearby.",
+            AppPermission::LocationAlways => {
+                "Your location is used in the background to keep tracking your route."
+            }
+            AppPermission::Microphone => "The microphone is used to record audio.",
+            AppPermission::Notifications => "Notifications keep you up to date.",
+            AppPermission::Contacts => "Your contacts are used to find people you know.",
+            AppPermission::Calendar => "Your calendar is used to add and show events.",
+            AppPermission::Bluetooth => "Bluetooth is used to connect to nearby devices.",
+            AppPermission::ActivityRecognition => {
+                "Motion and fitness data is used to count your steps."
+            }
+        }
+    }
+
+    // `Permission` values of permission_handler requested on Android
+    fn android_requests(self) -> &'static [&'static str] {
+        match self {
+            AppPermission::Camera => &["Permission.camera"],
+            // Photos need the legacy storage permission below Android 13
+            AppPermission::Photos => &[
+                "if (await _getAndroidSdkVersion() >= 33) Permission.photos else Permission.storage",
+            ],
+            AppPermission::Location => &["Permission.locationWhenInUse"],
+            AppPermission::LocationAlways => {
+                &["Permission.locationWhenInUse", "Permission.locationAlways"]
+            }
+            AppPermission::Microphone => &["Permission.microphone"],
+            AppPermission::Notifications => &["Permission.notification"],
+            AppPermission::Contacts => &["Permission.contacts"],
+            AppPermission::Calendar => &["Permission.calendarFullAccess"],
+            AppPermission::Bluetooth => &["Permission.bluetoothScan", "Permission.bluetoothConnect"],
+            AppPermission::ActivityRecognition => &["Permission.activityRecognition"],
+        }
+    }
+
+    fn ios_requests(self) -> &'static [&'static str] {
+        match self {
+            AppPermission::Camera => &["Permission.camera"],
+            AppPermission::Photos => &["Permission.photos"],
+            AppPermission::Location => &["Permission.locationWhenInUse"],
+            AppPermission::LocationAlways => {
+                &["Permission.locationWhenInUse", "Permission.locationAlways"]
+            }
+            AppPermission::Microphone => &["Permission.microphone"],
+            AppPermission::Notifications => &["Permission.notification"],
+            AppPermission::Contacts => &["Permission.contacts"],
+            AppPermission::Calendar => &["Permission.calendarFullAccess"],
+            AppPermission::Bluetooth => &["Permission.bluetooth"],
+            AppPermission::ActivityRecognition => &["Permission.sensors"],
+        }
+    }
+
+    // `<uses-permission>` names, with the last SDK version they are needed on
+    fn android_manifest(self) -> &'static [(&'static str, Option<u32>)] {
+        match self {
+            AppPermission::Camera => &[("android.permission.CAMERA", None)],
+            AppPermission::Photos => &[
+                ("android.permission.READ_MEDIA_IMAGES", None),
+                ("android.permission.READ_EXTERNAL_STORAGE", Some(32)),
+            ],
+            AppPermission::Location => &[
+                ("android.permission.ACCESS_FINE_LOCATION", None),
+                ("android.permission.ACCESS_COARSE_LOCATION", None),
+            ],
+            AppPermission::LocationAlways => &[
+                ("android.permission.ACCESS_FINE_LOCATION", None),
+                ("android.permission.ACCESS_COARSE_LOCATION", None),
+                ("android.permission.ACCESS_BACKGROUND_LOCATION", None),
+            ],
+            AppPermission::Microphone => &[("android.permission.RECORD_AUDIO", None)],
+            AppPermission::Notifications => &[("android.permission.POST_NOTIFICATIONS", None)],
+            AppPermission::Contacts => &[("android.permission.READ_CONTACTS", None)],
+            AppPermission::Calendar => &[
+                ("android.permission.READ_CALENDAR", None),
+                ("android.permission.WRITE_CALENDAR", None),
+            ],
+            AppPermission::Bluetooth => &[
+                ("android.permission.BLUETOOTH_SCAN", None),
+                ("android.permission.BLUETOOTH_CONNECT", None),
+            ],
+            AppPermission::ActivityRecognition => {
+                &[("android.permission.ACTIVITY_RECOGNITION", None)]
+            }
+        }
+    }
+
+    // Info.plist keys holding the usage description
+    fn ios_usage_keys(self) -> &'static [&'static str] {
+        match self {
+            AppPermission::Camera => &["NSCameraUsageDescription"],
+            AppPermission::Photos => &["NSPhotoLibraryUsageDescription"],
+            AppPermission::Location => &["NSLocationWhenInUseUsageDescription"],
+            AppPermission::LocationAlways => &[
+                "NSLocationWhenInUseUsageDescription",
+                "NSLocationAlwaysAndWhenInUseUsageDescription",
+            ],
+            AppPermission::Microphone => &["NSMicrophoneUsageDescription"],
+            AppPermission::Notifications => &[],
+            AppPermission::Contacts => &["NSContactsUsageDescription"],
+            AppPermission::Calendar => &[
+                "NSCalendarsUsageDescription",
+                "NSCalendarsFullAccessUsageDescription",
+            ],
+            AppPermission::Bluetooth => &["NSBluetoothAlwaysUsageDescription"],
+            AppPermission::ActivityRecognition => &["NSMotionUsageDescription"],
+        }
+    }
+
+    // permission_handler only compiles the iOS handlers the Podfile enables,
+    // others report `permanentlyDenied`
+    fn ios_macro(self) -> &'static str {
+        match self {
+            AppPermission::Camera => "PERMISSION_CAMERA",
+            AppPermission::Photos => "PERMISSION_PHOTOS",
+            AppPermission::Location => "PERMISSION_LOCATION_WHENINUSE",
+            AppPermission::LocationAlways => "PERMISSION_LOCATION",
+            AppPermission::Microphone => "PERMISSION_MICROPHONE",
+            AppPermission::Notifications => "PERMISSION_NOTIFICATIONS",
+            AppPermission::Contacts => "PERMISSION_CONTACTS",
+            AppPermission::Calendar => "PERMISSION_EVENTS_FULL_ACCESS",
+            AppPermission::Bluetooth => "PERMISSION_BLUETOOTH",
+            AppPermission::ActivityRecognition => "PERMISSION_SENSORS",
+        }
+    }
+}
+
+// A permission the app asks for, with the reason shown to the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclaredPermission {
+    pub permission: AppPermission,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
+}
+
+impl DeclaredPermission {
+    pub fn new(permission: AppPermission) -> Self {
+        DeclaredPermission {
+            permission,
+            rationale: None,
+        }
+    }
+
+    // Parses `name[:rationale]`, e.g. `camera:Scan receipts.`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (name, rationale) = match spec.split_once(':') {
+            Some((name, rationale)) => (name, Some(rationale.trim().to_string())),
+            None => (spec, None),
+        };
+        let Ok(permission) = AppPermission::from_str(name.trim(), true) else {
+            bail!(
+                "Unknown permission `{}`. Use one of: {}",
+                name,
+                AppPermission::ALL
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        };
+        Ok(DeclaredPermission {
+            permission,
+            rationale: rationale.filter(|r| !r.is_empty()),
+        })
+    }
+
+    pub fn rationale(&self) -> &str {
+        self.rationale
+            .as_deref()
+            .unwrap_or(self.permission.default_rationale())
+    }
+}
+
+fn request_list(
+    permissions: &[DeclaredPermission],
+    requests: fn(AppPermission) -> &'static [&'static str],
+) -> String {
+    let mut entries: Vec<&str> = Vec::new();
+    for declared in permissions {
+        for request in requests(declared.permission) {
+            if !entries.contains(request) {
+                entries.push(request);
+            }
+        }
+    }
+    entries
+        .iter()
+        .map(|entry| format!("        {},\n", entry))
+        .collect()
+}
+
+pub fn generate_permissions_template(config: &ProjectConfig) -> String {
+    let permissions = &config.permissions;
+    let uses_sdk_version = permissions
+        .iter()
+        .any(|p| p.permission == AppPermission::Photos);
+
+    let device_info_import = if uses_sdk_version {
+        "import 'package:device_info_plus/device_info_plus.dart';\n"
+    } else {
+        ""
+    };
+    let sdk_version = if uses_sdk_version {
+        r#"
+  // Helper method to get Android SDK version
+  Future<int> _getAndroidSdkVersion() async {
+    try {
+      final deviceInfo = DeviceInfoPlugin();
+      final androidInfo = await deviceInfo.androidInfo;
+      return androidInfo.version.sdkInt;
+    } catch (e) {
+      print('Error getting Android SDK version: $e');
+    }
+    return 29; // Default to Android 10 for safety
+  }
+"#
+    } else {
+        ""
+    };
+    let rationales = permissions
+        .iter()
+        .map(|p| format!("    {},\n", dart::string_literal(p.rationale())))
+        .collect::<String>();
+
+    format!(
+        r#"{device_info_import}import 'package:flutter/foundation.dart';
+import 'package:flutter/material.dart';
+import 'package:flutter/services.dart';
+import 'package:hooks_riverpod/hooks_riverpod.dart';
+import 'package:permission_handler/permission_handler.dart';
+
+final permissionUtilProvider =
+    StateNotifierProvider<PermissionUtil, bool>((ref) => PermissionUtil());
+
+class PermissionUtil extends StateNotifier<bool> {{
+  PermissionUtil() : super(false);
+
+  // Why the app asks, shown when a permission was denied
+  static const rationales = [
+{rationales}  ];
+
+  // `dart:io` is not available on the web, so the platform is read from
+  // `defaultTargetPlatform` and the web never asks for permissions
+  Future<List<Permission>> get _requiredPermissions async {{
+    if (kIsWeb) return [];
+    if (defaultTargetPlatform == TargetPlatform.android) {{
+      return [
+{android}      ];
+    }} else if (defaultTargetPlatform == TargetPlatform.iOS) {{
+      return [
+{ios}      ];
+    }}
+    return [];
+  }}
+{sdk_version}
+  Future<bool> requestPermissions() async {{
+    try {{
+      final permissions = await _requiredPermissions;
+      if (permissions.isEmpty) return true;
+
+      final statuses = await permissions.request();
+      return statuses.values.every((status) => status.isGranted);
+    }} catch (e) {{
+      print('Error requesting permissions: $e');
+      return false;
+    }}
+  }}
+
+  Future<bool> checkPermissions() async {{
+    try {{
+      final permissions = await _requiredPermissions;
+      if (permissions.isEmpty) return true;
+
+      final statuses = await Future.wait(
+        permissions.map((permission) => permission.status),
+      );
+      return statuses.every((status) => status.isGranted);
+    }} catch (e) {{
+      print('Error checking permissions: $e');
+      return false;
+    }}
+  }}
+
+  Future<void> openSettings() async {{
+    try {{
+      await openAppSettings();
+    }} catch (e) {{
+      print('Error opening settings: $e');
+    }}
+  }}
+
+  Future<void> checkAndRequestPermissions(
+    BuildContext context,
+    WidgetRef ref,
+  ) async {{
+    final hasPermissions = await checkPermissions();
+    if (!hasPermissions) {{
+      final granted = await requestPermissions();
+      if (!granted) {{
+        // Show dialog if permissions are not granted
+        if (context.mounted) {{
+          await showPermissionDialog(context, ref);
+        }}
+      }} else {{
+        state = true;
+      }}
+    }} else {{
+      state = true;
+    }}
+  }}
+
+  Future<void> showPermissionDialog(BuildContext context, WidgetRef ref) async {{
+    return showDialog(
+      context: context,
+      barrierDismissible: false,
+      builder: (BuildContext context) {{
+        return AlertDialog(
+          content: Text(
+            '${{rationales.join('\n')}}\n\n'
+            'Please grant the required permissions in settings.',
+          ),
+          actions: <Widget>[
+            TextButton(
+              child: const Text('Open Settings'),
+              onPressed: () async {{
+                Navigator.of(context).pop();
+                await openSettings();
+                if (context.mounted) {{
+                  await checkAndRequestPermissions(context, ref);
+                }}
+              }},
+            ),
+            TextButton(
+              child: const Text('Exit App'),
+              onPressed: () => SystemNavigator.pop(),
+            ),
+          ],
+        );
+      }},
+    );
+  }}
+}}
+"#,
+        android = request_list(permissions, AppPermission::android_requests),
+        ios = request_list(permissions, AppPermission::ios_requests),
+    )
+}
+
+fn uses_permission_block(permissions: &[DeclaredPermission]) -> String {
+    let mut entries = Vec::new();
+    for declared in permissions {
+        for entry in declared.permission.android_manifest() {
+            if !entries.contains(entry) {
+                entries.push(*entry);
+            }
+        }
+    }
+
+    entries
+        .into_iter()
+        .map(|(name, max_sdk)| {
+            let max_sdk = max_sdk
+                .map(|sdk| format!(" android:maxSdkVersion=\"{}\"", sdk))
+                .unwrap_or_default();
+            format!("<uses-permission android:name=\"{}\"{} />\n", name, max_sdk)
+        })
+        .collect()
+}
+
+fn preprocessor_block(permissions: &[DeclaredPermission]) -> String {
+    if permissions.is_empty() {
+        return String::new();
+    }
+    let mut lines = vec![
+        "target.build_configurations.each do |config|".to_string(),
+        "  config.build_settings['GCC_PREPROCESSOR_DEFINITIONS'] ||= [".to_string(),
+        "    '$(inherited)',".to_string(),
+    ];
+    for declared in permissions {
+        lines.push(format!("    '{}=1',", declared.permission.ios_macro()));
+    }
+    lines.push("  ]".to_string());
+    lines.push("end".to_string());
+    lines.join("\n")
+}
+
+// Declares the permissions in the native projects and drops the entries of
+// `removed` ones, platforms that were not created are skipped
+pub fn write_native_permissions(
+    project_dir: &Path,
+    config: &ProjectConfig,
+    removed: &[AppPermission],
+) -> Result<()> {
+    native::edit(
+        &native::android_manifest(project_dir),
+        |manifest: &mut AndroidManifest| {
+            manifest.set_block(
+                "permissions",
+                ManifestParent::Manifest,
+                &uses_permission_block(&config.permissions),
+            )
+        },
+    )?;
+
+    native::edit(&native::info_plist(project_dir), |plist: &mut Plist| {
+        for permission in removed {
+            for key in permission.ios_usage_keys() {
+                // Calendar and location share keys with other permissions
+                let still_used = config
+                    .permissions
+                    .iter()
+                    .any(|p| p.permission.ios_usage_keys().contains(key));
+                if !still_used {
+                    plist.remove(key)?;
+                }
+            }
+        }
+        for declared in &config.permissions {
+            for key in declared.permission.ios_usage_keys() {
+                plist.set(key, PlistValue::String(declared.rationale().to_string()))?;
+            }
+        }
+        Ok(())
+    })?;
+
+    let podfile = native::podfile(project_dir);
+    if native::has_ios(project_dir) && !podfile.exists() && !config.permissions.is_empty() {
+        fs::write(&podfile, FLUTTER_PODFILE)?;
+    }
+    native::edit(&podfile, |podfile: &mut Podfile| {
+        podfile.set_block("permissions", &preprocessor_block(&config.permissions))
+    })
+}
+
+pub fn add_permissions(project_dir: &Path, specs: &[String], remove: bool) -> Result<()> {
+    let mut config = ProjectConfig::load(project_dir)?;
+
+    if !config.targets_mobile() {
+        bail!("Runtime permissions are only requested on Android and iOS, but this project targets neither");
+    }
+    if !config.use_riverpod {
+        bail!(
+            "The permission utility is a Riverpod notifier, but this project does not use Riverpod"
+        );
+    }
+
+    let mut removed = Vec::new();
+    for spec in specs {
+        let declared = DeclaredPermission::parse(spec)?;
+        if remove {
+            config
+                .permissions
+                .retain(|p| p.permission != declared.permission);
+            removed.push(declared.permission);
+            continue;
+        }
+        match config
+            .permissions
+            .iter_mut()
+            .find(|p| p.permission == declared.permission)
+        {
+            Some(existing) => *existing = declared,
+            None => config.permissions.push(declared),
+        }
+    }
+
+    let utilities = project_dir.join("lib").join("core").join("utilities");
+    let permissions_file = utilities.join("permissions.dart");
+    if config.permissions.is_empty() {
+        if permissions_file.exists() {
+            fs::remove_file(&permissions_file)?;
+        }
+    } else {
+        fs::create_dir_all(&utilities)?;
+        fs::write(&permissions_file, generate_permissions_template(&config))?;
+    }
+    write_native_permissions(project_dir, &config, &removed)?;
+    config.save(project_dir)?;
+
+    if !config.permissions.is_empty() {
+        let mut packages = vec!["permission_handler"];
+        if config.uses_package(OptionalPackage::DeviceInfoPlus) {
+            packages.push(OptionalPackage::DeviceInfoPlus.package());
+        }
+        pubspec::add_dependencies(project_dir, &packages, &[])?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_permission_with_rationale() {
+        let declared = DeclaredPermission::parse("camera: Costs $5 to scan").unwrap();
+        assert_eq!(declared.permission, AppPermission::Camera);
+        assert_eq!(declared.rationale(), "Costs $5 to scan");
+        assert_eq!(
+            dart::string_literal(declared.rationale()),
+            r"'Costs \$5 to scan'"
+        );
+
+        let declared = DeclaredPermission::parse("camera:").unwrap();
+        assert!(declared.rationale.is_none());
+        assert!(DeclaredPermission::parse("teleport").is_err());
+    }
+}
@@ -0,0 +1,47 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetPlatform {
+    Android,
+    Ios,
+    Web,
+    Macos,
+    Windows,
+    Linux,
+}
+
+impl TargetPlatform {
+    pub const ALL: [TargetPlatform; 6] = [
+        TargetPlatform::Android,
+        TargetPlatform::Ios,
+        TargetPlatform::Web,
+        TargetPlatform::Macos,
+        TargetPlatform::Windows,
+        TargetPlatform::Linux,
+    ];
+
+    pub fn is_mobile(self) -> bool {
+        matches!(self, TargetPlatform::Android | TargetPlatform::Ios)
+    }
+}
+
+impl fmt::Display for TargetPlatform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TargetPlatform::Android => write!(f, "android"),
+            TargetPlatform::Ios => write!(f, "ios"),
+            TargetPlatform::Web => write!(f, "web"),
+            TargetPlatform::Macos => write!(f, "macos"),
+            TargetPlatform::Windows => write!(f, "windows"),
+            TargetPlatform::Linux => write!(f, "linux"),
+        }
+    }
+}
+
+// Projects created before platforms were selectable
+pub fn default_platforms() -> Vec<TargetPlatform> {
+    vec![TargetPlatform::Android, TargetPlatform::Ios]
+}
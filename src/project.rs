@@ -0,0 +1,257 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::camel_case;
+use crate::deep_link::DeepLinks;
+use crate::form::FormFieldSpec;
+use crate::model::ModelSpec;
+use crate::navigation::Navigation;
+use crate::package::OptionalPackage;
+use crate::permission::{AppPermission, DeclaredPermission};
+use crate::platform::{default_platforms, TargetPlatform};
+use crate::router::guard::Guard;
+use crate::router::RouterKind;
+use crate::storage::LocalDatabase;
+use crate::theme::DesignTokens;
+use crate::ui_kit::UiKit;
+
+pub const CONFIG_FILE: &str = "flutter_gen.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Feature {
+    pub name: String,
+    pub layers: Vec<String>,
+    /// Screen shown at the feature's own route, `<name>_screen.dart` when unset
+    #[serde(default)]
+    pub root_screen: Option<String>,
+    /// Screens routed below the feature's own route
+    #[serde(default)]
+    pub screens: Vec<Screen>,
+    /// Guards of the feature's route, `authenticated` when unset and the app
+    /// has a login. Auth features run below the `guest` guarded `/auth` route.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guards: Option<Vec<Guard>>,
+}
+
+impl Feature {
+    pub fn new(name: &str) -> Self {
+        Feature {
+            name: name.to_string(),
+            layers: vec![
+                "data".to_string(),
+                "presentation".to_string(),
+                "domain".to_string(),
+                "logic".to_string(),
+            ],
+            root_screen: None,
+            screens: Vec::new(),
+            guards: None,
+        }
+    }
+
+    pub fn root_screen(&self) -> &str {
+        self.root_screen.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteParam {
+    /// Name of the path or query parameter, passed to the screen in camelCase
+    pub name: String,
+    pub dart_type: String,
+    /// Optional query parameter instead of a path segment
+    #[serde(default)]
+    pub query: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Screen {
+    /// Route name, unique across the app
+    pub name: String,
+    /// Widget file / class the route builds, `<widget>_screen.dart`
+    pub widget: String,
+    /// Path relative to the parent route
+    pub path: String,
+    #[serde(default)]
+    pub params: Vec<RouteParam>,
+    #[serde(default)]
+    pub children: Vec<Screen>,
+    #[serde(default)]
+    pub kind: ScreenKind,
+    /// Inputs of `form` screens
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FormFieldSpec>,
+    /// Checked after the guards of the parent routes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub guards: Vec<Guard>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenKind {
+    #[default]
+    Stateless,
+    Stateful,
+    Consumer,
+    HookConsumer,
+    Form,
+}
+
+// Everything flutter_gen decided while creating a project, stored next to
+// pubspec.yaml so later commands can generate consistent code.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    pub name: String,
+    pub package: String,
+    pub use_riverpod: bool,
+    pub use_supabase: bool,
+    /// Platforms passed to `flutter create`
+    #[serde(default = "default_platforms")]
+    pub platforms: Vec<TargetPlatform>,
+    #[serde(default)]
+    pub features: Vec<Feature>,
+    #[serde(default)]
+    pub models: Vec<ModelSpec>,
+    #[serde(default)]
+    pub navigation: Option<Navigation>,
+    #[serde(default)]
+    pub router: RouterKind,
+    /// Generate `@TypedGoRoute` classes with go_router_builder
+    #[serde(default)]
+    pub typed_routes: bool,
+    /// App links and custom scheme the native projects are configured for
+    #[serde(default)]
+    pub deep_links: Option<DeepLinks>,
+    /// Runtime permissions requested on Android and iOS
+    #[serde(default)]
+    pub permissions: Vec<DeclaredPermission>,
+    /// Build flavors such as `dev`, `staging` and `prod`, the first one is
+    /// what `lib/main.dart` runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flavors: Vec<String>,
+    /// Optional packages from the catalog, with their generated glue code
+    #[serde(default)]
+    pub packages: Vec<OptionalPackage>,
+    /// Data sources return a `Result` with a typed `Failure` instead of throwing
+    #[serde(default)]
+    pub typed_errors: bool,
+    /// Database caching the models on the device
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_database: Option<LocalDatabase>,
+    /// Services, data sources and repositories are registered with get_it
+    #[serde(default)]
+    pub get_it: bool,
+    /// Design system of the app, shadcn_ui for configs from before the choice
+    #[serde(default)]
+    pub ui_kit: UiKit,
+    /// Locales with an ARB file, the template `en` first. Empty when the app
+    /// is not localized.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locales: Vec<String>,
+    /// Seed color and design tokens of the generated theme
+    #[serde(default, skip_serializing_if = "DesignTokens::is_empty")]
+    pub theme: DesignTokens,
+}
+
+impl ProjectConfig {
+    pub fn load(project_dir: &Path) -> Result<Self> {
+        let path = project_dir.join(CONFIG_FILE);
+        let content = fs::read_to_string(&path).with_context(|| {
+            format!(
+                "Failed to read {}. Run this command from a project created by flutter_gen.",
+                path.display()
+            )
+        })?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self, project_dir: &Path) -> Result<()> {
+        fs::write(
+            project_dir.join(CONFIG_FILE),
+            serde_json::to_string_pretty(self)? + "\n",
+        )?;
+        Ok(())
+    }
+
+    // Runtime permissions only exist on Android and iOS
+    pub fn targets_mobile(&self) -> bool {
+        self.platforms.iter().any(|p| p.is_mobile())
+    }
+
+    pub fn requests_permissions(&self) -> bool {
+        self.targets_mobile() && !self.permissions.is_empty()
+    }
+
+    // Selected packages and the ones the code of other choices relies on
+    pub fn uses_package(&self, package: OptionalPackage) -> bool {
+        self.packages.contains(&package)
+            || match package {
+                // The auth service and the API client log their failures
+                OptionalPackage::Logging => {
+                    self.use_supabase || self.packages.contains(&OptionalPackage::Dio)
+                }
+                // The sqflite cache lives in its database service
+                OptionalPackage::Sqflite => self.local_database == Some(LocalDatabase::Sqflite),
+                // Photo access depends on the Android version
+                OptionalPackage::DeviceInfoPlus => {
+                    self.requests_permissions()
+                        && self
+                            .permissions
+                            .iter()
+                            .any(|p| p.permission == AppPermission::Photos)
+                }
+                _ => false,
+            }
+    }
+
+    // Application id of the Android app, as `flutter create` derives it
+    pub fn android_package(&self) -> String {
+        format!("{}.{}", self.package, self.name)
+    }
+
+    // Bundle identifier of the iOS app, iOS does not allow underscores
+    pub fn ios_bundle_id(&self) -> String {
+        format!("{}.{}", self.package, camel_case(&self.name))
+    }
+
+    pub fn model(&self, feature_name: &str) -> Option<&ModelSpec> {
+        self.models.iter().find(|m| m.name == feature_name)
+    }
+
+    // Route names are global in the router, so they must not repeat
+    pub fn has_route(&self, name: &str) -> bool {
+        fn walk(screens: &[Screen], name: &str) -> bool {
+            screens
+                .iter()
+                .any(|s| s.name == name || walk(&s.children, name))
+        }
+
+        self.features
+            .iter()
+            .any(|f| f.name == name || walk(&f.screens, name))
+    }
+
+    pub fn feature_mut(&mut self, feature_name: &str) -> Option<&mut Feature> {
+        self.features.iter_mut().find(|f| f.name == feature_name)
+    }
+
+    pub fn screen_mut(&mut self, name: &str) -> Option<&mut Screen> {
+        fn walk<'a>(screens: &'a mut [Screen], name: &str) -> Option<&'a mut Screen> {
+            for screen in screens {
+                if screen.name == name {
+                    return Some(screen);
+                }
+                if let Some(found) = walk(&mut screen.children, name) {
+                    return Some(found);
+                }
+            }
+            None
+        }
+
+        self.features
+            .iter_mut()
+            .find_map(|f| walk(&mut f.screens, name))
+    }
+}
@@ -0,0 +1,464 @@
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::native::{self, NativeFile};
+
+// Known-good constraints of every package flutter_gen adds, bumped together
+// so generated projects resolve the same way without network access
+pub const CATALOG_VERSION: &str = "2025.1";
+
+const CATALOG: &[(&str, &str)] = &[
+    ("auto_route", "^9.2.2"),
+    ("auto_route_generator", "^9.0.0"),
+    ("build_runner", "^2.4.13"),
+    ("connectivity_plus", "^6.1.1"),
+    ("device_info_plus", "^11.2.0"),
+    ("dio", "^5.7.0"),
+    ("drift", "^2.22.1"),
+    ("drift_dev", "^2.22.1"),
+    ("drift_flutter", "^0.2.4"),
+    ("firebase_analytics", "^11.3.6"),
+    ("firebase_core", "^3.8.1"),
+    ("fluent_ui", "^4.10.0"),
+    ("flutter_background_service", "^5.1.0"),
+    ("flutter_dotenv", "^5.2.1"),
+    ("flutter_hooks", "^0.20.5"),
+    ("flutter_launcher_icons", "^0.14.2"),
+    ("flutter_lints", "^5.0.0"),
+    ("flutter_localizations", "sdk: flutter"),
+    ("flutter_native_splash", "^2.4.4"),
+    ("flutter_riverpod", "^2.6.1"),
+    ("get_it", "^8.0.3"),
+    ("go_router", "^14.6.2"),
+    ("go_router_builder", "^2.7.3"),
+    ("hive", "^2.2.3"),
+    ("hive_flutter", "^1.1.0"),
+    ("hooks_riverpod", "^2.6.1"),
+    ("intl", "^0.20.2"),
+    ("isar", "^3.1.0+1"),
+    ("isar_flutter_libs", "^3.1.0+1"),
+    ("isar_generator", "^3.1.0+1"),
+    ("logging", "^1.3.0"),
+    ("path", "^1.9.0"),
+    ("path_provider", "^2.1.5"),
+    ("permission_handler", "^11.3.1"),
+    ("riverpod_annotation", "^2.6.1"),
+    ("riverpod_generator", "^2.6.3"),
+    ("shadcn_ui", "^0.18.0"),
+    ("share_plus", "^10.1.3"),
+    ("simple_circular_progress_bar", "^1.0.2"),
+    ("sqflite", "^2.4.1"),
+    ("supabase_flutter", "^2.8.3"),
+    ("very_good_analysis", "^7.0.0"),
+];
+
+pub fn constraint(package: &str) -> Result<&'static str> {
+    match CATALOG.iter().find(|(name, _)| *name == package) {
+        Some((_, constraint)) => Ok(constraint),
+        None => bail!(
+            "{} is not in the dependency catalog {}",
+            package,
+            CATALOG_VERSION
+        ),
+    }
+}
+
+pub fn pubspec(project_dir: &Path) -> PathBuf {
+    project_dir.join("pubspec.yaml")
+}
+
+// A font file of a family, `weight` and `italic` as Flutter reads them
+pub struct FontAsset {
+    pub asset: String,
+    pub weight: Option<u32>,
+    pub italic: bool,
+}
+
+impl FontAsset {
+    // `path[:weight][:italic]`, e.g. `assets/fonts/Inter-BoldItalic.ttf:700:italic`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut parts = spec.split(':');
+        let asset = parts.next().unwrap_or_default().trim().to_string();
+        if asset.is_empty() {
+            bail!("Missing font file in `{}`", spec);
+        }
+        let mut font = FontAsset {
+            asset,
+            weight: None,
+            italic: false,
+        };
+        for part in parts {
+            match part.trim() {
+                "italic" => font.italic = true,
+                weight => match weight.parse::<u32>() {
+                    Ok(weight) if (100..=900).contains(&weight) && weight % 100 == 0 => {
+                        font.weight = Some(weight)
+                    }
+                    _ => bail!(
+                        "Invalid font option `{}` in `{}`, use a weight from 100 to 900 or `italic`",
+                        weight,
+                        spec
+                    ),
+                },
+            }
+        }
+        Ok(font)
+    }
+}
+
+// pubspec.yaml, edited line by line so comments and formatting survive
+pub struct Pubspec {
+    lines: Vec<String>,
+    source: String,
+}
+
+impl NativeFile for Pubspec {
+    fn parse(_path: &Path, source: String) -> Result<Self> {
+        Ok(Pubspec {
+            lines: source.lines().map(str::to_string).collect(),
+            source,
+        })
+    }
+
+    fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+// Blank and comment lines do not end or extend a block
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+fn key_of(line: &str) -> Option<&str> {
+    let (key, _) = line.trim_start().split_once(':')?;
+    Some(key)
+}
+
+impl Pubspec {
+    fn sync(&mut self) {
+        self.source = self.lines.join("\n") + "\n";
+    }
+
+    // Line of `key:` at `indent` within `start..end`
+    fn find_key(&self, start: usize, end: usize, indent: usize, key: &str) -> Option<usize> {
+        (start..end).find(|&i| {
+            let line = &self.lines[i];
+            is_content(line) && indent_of(line) == indent && key_of(line) == Some(key)
+        })
+    }
+
+    // Index after the last content line nested below the line at `at`
+    fn block_end(&self, at: usize) -> usize {
+        let indent = indent_of(&self.lines[at]);
+        let mut end = at + 1;
+        for (i, line) in self.lines.iter().enumerate().skip(at + 1) {
+            if !is_content(line) {
+                continue;
+            }
+            // List items may sit at the indent of their key
+            let nested = indent_of(line) > indent
+                || (indent_of(line) == indent && line.trim_start().starts_with("- "));
+            if !nested {
+                break;
+            }
+            end = i + 1;
+        }
+        end
+    }
+
+    // Line of a top-level key, appended when missing
+    fn ensure_section(&mut self, key: &str) -> usize {
+        if let Some(at) = self.find_key(0, self.lines.len(), 0, key) {
+            return at;
+        }
+        while self.lines.last().is_some_and(|l| l.trim().is_empty()) {
+            self.lines.pop();
+        }
+        self.lines.push(String::new());
+        self.lines.push(format!("{}:", key));
+        self.lines.len() - 1
+    }
+
+    // Line of `key:` directly below the line at `parent`, added when missing
+    fn ensure_child(&mut self, parent: usize, key: &str) -> usize {
+        let indent = indent_of(&self.lines[parent]) + 2;
+        let end = self.block_end(parent);
+        if let Some(at) = self.find_key(parent + 1, end, indent, key) {
+            return at;
+        }
+        self.lines
+            .insert(end, format!("{}{}:", " ".repeat(indent), key));
+        end
+    }
+
+    fn set_dependency(&mut self, section: &str, package: &str) -> Result<()> {
+        let constraint = constraint(package)?;
+        let at = self.ensure_section(section);
+        let end = self.block_end(at);
+        // Constraints that are already there were chosen on purpose
+        if self.find_key(at + 1, end, 2, package).is_none() {
+            // SDK packages such as flutter_localizations ship with Flutter
+            let entry = match constraint.strip_prefix("sdk: ") {
+                Some(sdk) => vec![format!("  {}:", package), format!("    sdk: {}", sdk)],
+                None => vec![format!("  {}: {}", package, constraint)],
+            };
+            self.lines.splice(end..end, entry);
+        }
+        self.sync();
+        Ok(())
+    }
+
+    pub fn add_dependency(&mut self, package: &str) -> Result<()> {
+        self.set_dependency("dependencies", package)
+    }
+
+    pub fn add_dev_dependency(&mut self, package: &str) -> Result<()> {
+        self.set_dependency("dev_dependencies", package)
+    }
+
+    /// Adds or updates a setting of the `flutter:` section, e.g. `generate: true`
+    pub fn set_flutter(&mut self, key: &str, value: &str) {
+        let flutter = self.ensure_section("flutter");
+        let at = self.ensure_child(flutter, key);
+        self.lines[at] = format!("  {}: {}", key, value);
+        self.sync();
+    }
+
+    pub fn add_asset(&mut self, asset: &str) {
+        let flutter = self.ensure_section("flutter");
+        let assets = self.ensure_child(flutter, "assets");
+        let entry = format!("    - {}", asset);
+        let end = self.block_end(assets);
+        if !self.lines[assets + 1..end].contains(&entry) {
+            self.lines.insert(end, entry);
+        }
+        self.sync();
+    }
+
+    /// Adds a font family, replacing the files of an existing one
+    pub fn set_font(&mut self, family: &str, fonts: &[FontAsset]) {
+        let flutter = self.ensure_section("flutter");
+        let list = self.ensure_child(flutter, "fonts");
+        // Family names may contain spaces and characters YAML reads otherwise
+        let quoted = format!("\"{}\"", family.replace('\\', "\\\\").replace('"', "\\\""));
+
+        let mut entry = vec![
+            format!("    - family: {}", quoted),
+            "      fonts:".to_string(),
+        ];
+        for font in fonts {
+            entry.push(format!("        - asset: {}", font.asset));
+            if let Some(weight) = font.weight {
+                entry.push(format!("          weight: {}", weight));
+            }
+            if font.italic {
+                entry.push("          style: italic".to_string());
+            }
+        }
+
+        let end = self.block_end(list);
+        // Earlier versions wrote the family unquoted
+        let existing = (list + 1..end).find(|&i| {
+            let line = self.lines[i].trim();
+            line == format!("- family: {}", quoted) || line == format!("- family: {}", family)
+        });
+        match existing {
+            Some(start) => {
+                let family_end = (start + 1..end)
+                    .find(|&i| self.lines[i].trim_start().starts_with("- family:"))
+                    .unwrap_or(end);
+                self.lines.splice(start..family_end, entry);
+            }
+            None => {
+                self.lines.splice(end..end, entry);
+            }
+        }
+        self.sync();
+    }
+}
+
+// Adds the packages with their catalog constraints
+pub fn add_dependencies(
+    project_dir: &Path,
+    packages: &[&str],
+    dev_packages: &[&str],
+) -> Result<()> {
+    native::edit(&pubspec(project_dir), |pubspec: &mut Pubspec| {
+        for package in packages {
+            pubspec.add_dependency(package)?;
+        }
+        for package in dev_packages {
+            pubspec.add_dev_dependency(package)?;
+        }
+        Ok(())
+    })
+}
+
+pub fn add_font(project_dir: &Path, family: &str, fonts: &[FontAsset]) -> Result<()> {
+    native::edit(&pubspec(project_dir), |pubspec: &mut Pubspec| {
+        pubspec.set_font(family, fonts);
+        Ok(())
+    })
+}
+
+// Resolves the dependencies written to pubspec.yaml
+pub fn pub_get(project_dir: &Path) -> Result<()> {
+    Command::new("flutter")
+        .current_dir(project_dir)
+        .args(["pub", "get"])
+        .status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBSPEC: &str = "name: todo
+description: A new Flutter project.
+
+environment:
+  sdk: ^3.5.0
+
+dependencies:
+  flutter:
+    sdk: flutter
+
+  # The following adds the Cupertino Icons font to your application.
+  cupertino_icons: ^1.0.8
+
+dev_dependencies:
+  flutter_test:
+    sdk: flutter
+
+flutter:
+  uses-material-design: true
+";
+
+    fn pubspec(source: &str) -> Pubspec {
+        Pubspec::parse(Path::new("pubspec.yaml"), source.to_string()).unwrap()
+    }
+
+    #[test]
+    fn adds_dependencies_once() {
+        let mut pubspec = pubspec(PUBSPEC);
+        pubspec.add_dependency("go_router").unwrap();
+        pubspec.add_dependency("flutter_localizations").unwrap();
+        pubspec.add_dev_dependency("build_runner").unwrap();
+        let once = pubspec.source().to_string();
+        pubspec.add_dependency("go_router").unwrap();
+        pubspec.add_dependency("flutter_localizations").unwrap();
+        pubspec.add_dev_dependency("build_runner").unwrap();
+        assert_eq!(pubspec.source(), once);
+
+        assert!(once.contains(
+            "  cupertino_icons: ^1.0.8\n  go_router: ^14.6.2\n  flutter_localizations:\n    sdk: flutter\n\ndev_dependencies:"
+        ));
+        assert!(once.contains("    sdk: flutter\n  build_runner: ^2.4.13\n\nflutter:"));
+        assert!(once.contains("# The following adds the Cupertino Icons font"));
+        assert!(pubspec.add_dependency("left_pad").is_err());
+    }
+
+    #[test]
+    fn keeps_chosen_constraints() {
+        let mut pubspec = pubspec(&PUBSPEC.replace(
+            "  cupertino_icons: ^1.0.8",
+            "  cupertino_icons: ^1.0.8\n  go_router: ^13.0.0",
+        ));
+        pubspec.add_dependency("go_router").unwrap();
+        assert!(pubspec.source().contains("go_router: ^13.0.0"));
+        assert!(!pubspec.source().contains("^14.6.2"));
+    }
+
+    #[test]
+    fn adds_missing_sections() {
+        let mut pubspec = pubspec("name: todo\n\n");
+        pubspec.add_dev_dependency("flutter_lints").unwrap();
+        pubspec.set_flutter("generate", "true");
+        pubspec.set_flutter("generate", "true");
+        pubspec.add_asset(".env");
+        pubspec.add_asset(".env");
+        assert_eq!(
+            pubspec.source(),
+            "name: todo\n\ndev_dependencies:\n  flutter_lints: ^5.0.0\n\nflutter:\n  generate: true\n  assets:\n    - .env\n"
+        );
+    }
+
+    #[test]
+    fn replaces_the_files_of_a_font_family() {
+        let mut pubspec = pubspec(PUBSPEC);
+        let fonts = [
+            FontAsset::parse("assets/fonts/Inter-Regular.ttf").unwrap(),
+            FontAsset::parse("assets/fonts/Inter-BoldItalic.ttf:700:italic").unwrap(),
+        ];
+        pubspec.set_font("Inter", &fonts);
+        pubspec.set_font("Mono", &fonts[..1]);
+        let once = pubspec.source().to_string();
+        pubspec.set_font("Inter", &fonts);
+        assert_eq!(pubspec.source(), once);
+        assert!(once.ends_with(
+            r#"  fonts:
+    - family: "Inter"
+      fonts:
+        - asset: assets/fonts/Inter-Regular.ttf
+        - asset: assets/fonts/Inter-BoldItalic.ttf
+          weight: 700
+          style: italic
+    - family: "Mono"
+      fonts:
+        - asset: assets/fonts/Inter-Regular.ttf
+"#
+        ));
+
+        pubspec.set_font("Inter", &fonts[..1]);
+        assert!(!pubspec.source().contains("weight: 700"));
+        assert!(pubspec.source().contains("- family: \"Mono\""));
+
+        pubspec.set_font("Fira \"Code\" #2", &fonts[..1]);
+        assert!(pubspec
+            .source()
+            .contains(r#"    - family: "Fira \"Code\" #2""#));
+    }
+
+    #[test]
+    fn replaces_unquoted_font_families() {
+        let mut pubspec = pubspec(
+            "name: todo\n\nflutter:\n  fonts:\n    - family: Inter\n      fonts:\n        - asset: a.ttf\n",
+        );
+        pubspec.set_font("Inter", &[FontAsset::parse("b.ttf").unwrap()]);
+        assert_eq!(
+            pubspec.source(),
+            "name: todo\n\nflutter:\n  fonts:\n    - family: \"Inter\"\n      fonts:\n        - asset: b.ttf\n"
+        );
+    }
+
+    #[test]
+    fn parses_font_assets() {
+        let font = FontAsset::parse("a.ttf:italic:300").unwrap();
+        assert_eq!(font.asset, "a.ttf");
+        assert_eq!(font.weight, Some(300));
+        assert!(font.italic);
+        for spec in ["", ":700", "a.ttf:bold", "a.ttf:950", "a.ttf:450"] {
+            assert!(FontAsset::parse(spec).is_err(), "accepted `{}`", spec);
+        }
+    }
+
+    #[test]
+    fn catalog_lists_each_package_once_in_order() {
+        for pair in CATALOG.windows(2) {
+            assert!(
+                pair[0].0 < pair[1].0,
+                "{} is listed before {}",
+                pair[0].0,
+                pair[1].0
+            );
+        }
+    }
+}
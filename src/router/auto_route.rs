@@ -0,0 +1,391 @@
+use super::guard::{dart_list, Guard};
+use super::{join_path, route_tree, Route, RouterBackend};
+use crate::project::{ProjectConfig, RouteParam};
+use crate::{camel_case, pascal_case};
+
+pub struct AutoRoute;
+
+impl RouterBackend for AutoRoute {
+    fn generate_router(&self, config: &ProjectConfig) -> String {
+        generate_auto_router_template(config)
+    }
+
+    fn dependencies(&self) -> &'static [&'static str] {
+        &["auto_route"]
+    }
+
+    fn dev_dependencies(&self, _config: &ProjectConfig) -> Vec<&'static str> {
+        vec!["auto_route_generator"]
+    }
+
+    fn uses_codegen(&self, _config: &ProjectConfig) -> bool {
+        true
+    }
+
+    fn router_config(&self, config: &ProjectConfig) -> &'static str {
+        if config.use_riverpod {
+            "ref.watch(appRouterProvider).routerConfig()"
+        } else {
+            "appRouter.routerConfig()"
+        }
+    }
+
+    fn screen_imports(&self, config: &ProjectConfig, navigates: bool, pops: bool) -> Vec<String> {
+        let mut imports = Vec::new();
+        if navigates || pops {
+            imports.push("import 'package:auto_route/auto_route.dart';".to_string());
+        }
+        if navigates {
+            imports.push(format!(
+                "import 'package:{}/app/router.gr.dart';",
+                config.name
+            ));
+        }
+        imports
+    }
+
+    fn go_to(
+        &self,
+        _config: &ProjectConfig,
+        _route_name: &str,
+        widget: &str,
+        key: Option<(&str, &str)>,
+        _indent: usize,
+    ) -> String {
+        // One route class serves every route of a widget, e.g. the CRUD form
+        // at `new` and `:id/edit`. It is not const once any of them has a
+        // parameter, which this route cannot tell.
+        match key {
+            None => format!("context.router.push({}())", route_class(widget)),
+            Some((name, value)) => format!(
+                "context.router.push({}({}: {}))",
+                route_class(widget),
+                name,
+                value
+            ),
+        }
+    }
+
+    fn pop(&self) -> &'static str {
+        "context.router.maybePop()"
+    }
+
+    fn annotate_screen(&self, source: &str, class_name: &str, params: &[RouteParam]) -> String {
+        let declaration = format!("\nclass {} extends", class_name);
+        if source.contains("@RoutePage()") || !source.contains(&declaration) {
+            return source.to_string();
+        }
+
+        let constructor = format!("  const {}({{", class_name);
+        let lines = source
+            .lines()
+            .map(|line| match line.strip_prefix(&constructor) {
+                Some(rest) => {
+                    let (args, end) = rest.split_once('}').unwrap_or((rest, ""));
+                    let args = args
+                        .split(", ")
+                        .map(|arg| annotate_param(arg, params))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("{}{}}}{}", constructor, args, end)
+                }
+                None => line.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        let import = "import 'package:auto_route/auto_route.dart';";
+        let source = if source.contains(import) {
+            format!("{}\n", lines.join("\n"))
+        } else {
+            format!("{}\n{}\n", import, lines.join("\n"))
+        };
+        source.replace(&declaration, &format!("\n@RoutePage(){}", declaration))
+    }
+}
+
+// auto_route names the route of `FooScreen` `FooRoute`
+fn route_class(widget: &str) -> String {
+    format!("{}Route", pascal_case(widget))
+}
+
+// Links a constructor argument to the path or query parameter it is read from
+fn annotate_param(arg: &str, params: &[RouteParam]) -> String {
+    let param = params.iter().find(|p| {
+        arg.strip_prefix("required ")
+            .unwrap_or(arg)
+            .strip_prefix("this.")
+            == Some(camel_case(&p.name).as_str())
+    });
+    match param {
+        Some(p) if p.query => format!("@QueryParam('{}') {}", p.name, arg),
+        Some(p) => format!("@PathParam('{}') {}", p.name, arg),
+        None => arg.to_string(),
+    }
+}
+
+// Guards of a route entry, auto_route has no parent routes to inherit from
+fn guards_arg(guards: &[Guard]) -> String {
+    if guards.is_empty() {
+        String::new()
+    } else {
+        format!(", guards: [guard({})]", dart_list(guards))
+    }
+}
+
+// auto_route pushes every screen on the root stack, so nested go_router
+// style routes become flat routes with their full path
+fn flatten(route: &Route, path: &str, inherited: &[Guard], entries: &mut Vec<String>) {
+    let guards = [inherited, &route.guards].concat();
+    entries.push(format!(
+        "        AutoRoute(page: {}.page, path: '{}'{}),",
+        route_class(route.widget),
+        path,
+        guards_arg(&guards)
+    ));
+    for child in &route.children {
+        flatten(child, &join_path(path, &child.path), &guards, entries);
+    }
+}
+
+fn generate_auto_router_template(config: &ProjectConfig) -> String {
+    let tree = route_tree(config);
+    let guarded = tree.guarded();
+
+    let mut imports = vec!["import 'package:auto_route/auto_route.dart';".to_string()];
+    if config.use_riverpod {
+        imports.push("import 'package:hooks_riverpod/hooks_riverpod.dart';".to_string());
+    }
+    if guarded {
+        imports.push(format!(
+            "import 'package:{}/app/route_guards.dart';",
+            config.name
+        ));
+    }
+    imports.push(format!(
+        "import 'package:{}/app/router.gr.dart';",
+        config.name
+    ));
+    if guarded {
+        imports.push(format!(
+            "import 'package:{}/core/services/session_provider.dart';",
+            config.name
+        ));
+    }
+
+    let mut entries = Vec::new();
+
+    // Tabs are children of the shell, their screens are pushed above it
+    if !tree.tabs.is_empty() {
+        let shell_path = if tree.routes.iter().any(|r| r.path == "/") {
+            "/tabs"
+        } else {
+            "/"
+        };
+        let children = tree
+            .tabs
+            .iter()
+            .map(|tab| {
+                format!(
+                    "            AutoRoute(page: {}.page, path: '{}'{}),",
+                    route_class(tab.widget),
+                    tab.path.trim_start_matches('/'),
+                    guards_arg(&tab.guards)
+                )
+            })
+            .collect::<Vec<_>>();
+        entries.push(format!(
+            "        AutoRoute(\n          page: AppShellRoute.page,\n          path: '{}',\n          children: [\n{}\n          ],\n        ),",
+            shell_path,
+            children.join("\n")
+        ));
+        for tab in &tree.tabs {
+            for child in &tab.children {
+                flatten(
+                    child,
+                    &join_path(&tab.path, &child.path),
+                    &tab.guards,
+                    &mut entries,
+                );
+            }
+        }
+    }
+
+    for route in &tree.routes {
+        if route.name == "auth" {
+            entries.push(format!(
+                "        RedirectRoute(path: '/auth', redirectTo: '{}'),",
+                tree.login_path
+            ));
+            for child in &route.children {
+                flatten(
+                    child,
+                    &join_path("/auth", &child.path),
+                    &route.guards,
+                    &mut entries,
+                );
+            }
+        } else {
+            flatten(route, &route.path, &[], &mut entries);
+        }
+    }
+
+    if !entries.iter().any(|e| e.contains("path: '/'")) {
+        entries.push(format!(
+            "        RedirectRoute(path: '/', redirectTo: '{}'),",
+            tree.initial_location
+        ));
+    }
+
+    let (guard_helper, router_config, guard_class) = if guarded {
+        (
+            r#"
+  // Route guard running the guards against the current session
+  AutoRouteGuard guard(List<RouteGuard> guards) => SessionGuard(ref, guards);
+"#,
+            r#"  // Guards run again whenever the session changes
+  RouterConfig<UrlState> routerConfig() =>
+      config(reevaluateListenable: ref.read(sessionProvider));"#,
+            r#"
+
+class SessionGuard extends AutoRouteGuard {
+  SessionGuard(this.ref, this.guards);
+
+  final Ref ref;
+  final List<RouteGuard> guards;
+
+  @override
+  void onNavigation(NavigationResolver resolver, StackRouter router) {
+    final redirect = runGuards(
+      ref.read(sessionProvider).session,
+      resolver.route.stringMatch,
+      guards,
+    );
+    if (redirect == null) {
+      resolver.next();
+    } else {
+      resolver.next(false);
+      router.replacePath(redirect);
+    }
+  }
+}"#,
+        )
+    } else {
+        (
+            "",
+            "  RouterConfig<UrlState> routerConfig() => config();",
+            "",
+        )
+    };
+
+    // Without Riverpod the app reads the router from a top-level variable
+    let (fields, instance) = if config.use_riverpod {
+        (
+            format!(
+                "  AppRouter(this.ref);\n\n  final Ref ref;\n{}\n",
+                guard_helper
+            ),
+            "final appRouterProvider = Provider<AppRouter>((ref) => AppRouter(ref));",
+        )
+    } else {
+        (String::new(), "final appRouter = AppRouter();")
+    };
+
+    format!(
+        r#"{imports}
+
+@AutoRouterConfig()
+class AppRouter extends RootStackRouter {{
+{fields}  @override
+  RouteType get defaultRouteType => const RouteType.material();
+
+  @override
+  List<AutoRoute> get routes => [
+{entries}
+      ];
+
+{router_config}
+}}{guard_class}
+
+{instance}
+"#,
+        imports = imports.join("\n"),
+        entries = entries.join("\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ProjectConfig {
+        serde_json::from_value(serde_json::json!({
+            "name": "app",
+            "package": "com.example",
+            "use_riverpod": true,
+            "use_supabase": false,
+            "router": "auto_route",
+            "features": [
+                { "name": "home", "layers": [] },
+                { "name": "login", "layers": [] },
+            ],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn generates_a_plain_router_without_riverpod() {
+        let mut config = config();
+        config.use_riverpod = false;
+        let router = generate_auto_router_template(&config);
+        assert!(!router.contains("riverpod"));
+        assert!(!router.contains("guard"));
+        assert!(router.contains("class AppRouter extends RootStackRouter {\n  @override"));
+        assert!(router.ends_with("\nfinal appRouter = AppRouter();\n"));
+        assert_eq!(AutoRoute.router_config(&config), "appRouter.routerConfig()");
+
+        config.use_riverpod = true;
+        let router = generate_auto_router_template(&config);
+        assert!(router.contains("  final Ref ref;\n\n  // Route guard"));
+        assert!(router.contains("final appRouterProvider = Provider<AppRouter>"));
+    }
+
+    #[test]
+    fn pushes_routes_that_may_take_parameters() {
+        let config = config();
+        assert_eq!(
+            AutoRoute.go_to(&config, "todo_new", "todo_form", None, 8),
+            "context.router.push(TodoFormRoute())"
+        );
+        assert_eq!(
+            AutoRoute.go_to(&config, "todo_edit", "todo_form", Some(("id", "id")), 8),
+            "context.router.push(TodoFormRoute(id: id))"
+        );
+    }
+
+    #[test]
+    fn annotates_screens_once() {
+        let params = [
+            RouteParam {
+                name: "todo_id".to_string(),
+                dart_type: "String".to_string(),
+                query: false,
+            },
+            RouteParam {
+                name: "tab".to_string(),
+                dart_type: "String".to_string(),
+                query: true,
+            },
+        ];
+        let source = "import 'package:flutter/material.dart';\n\nclass TodoScreen extends StatelessWidget {\n  const TodoScreen({super.key, required this.todoId, this.tab});\n}\n";
+        let annotated = AutoRoute.annotate_screen(source, "TodoScreen", &params);
+        assert!(annotated.starts_with("import 'package:auto_route/auto_route.dart';\n"));
+        assert!(annotated.contains("\n@RoutePage()\nclass TodoScreen extends"));
+        assert!(annotated.contains(
+            "const TodoScreen({super.key, @PathParam('todo_id') required this.todoId, @QueryParam('tab') this.tab});"
+        ));
+        assert_eq!(
+            AutoRoute.annotate_screen(&annotated, "TodoScreen", &params),
+            annotated
+        );
+    }
+}
@@ -0,0 +1,501 @@
+use super::guard::dart_list;
+use super::{route_tree, Route, RouteTree, RouterBackend};
+use crate::project::ProjectConfig;
+use crate::{camel_case, pascal_case};
+
+pub struct GoRouter;
+
+impl RouterBackend for GoRouter {
+    fn generate_router(&self, config: &ProjectConfig) -> String {
+        generate_router_template(config)
+    }
+
+    fn dependencies(&self) -> &'static [&'static str] {
+        &["go_router"]
+    }
+
+    fn dev_dependencies(&self, config: &ProjectConfig) -> Vec<&'static str> {
+        if config.typed_routes {
+            vec!["go_router_builder"]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn uses_codegen(&self, config: &ProjectConfig) -> bool {
+        config.typed_routes
+    }
+
+    fn router_config(&self, config: &ProjectConfig) -> &'static str {
+        if config.use_riverpod {
+            "ref.watch(goRouterProvider)"
+        } else {
+            "goRouter"
+        }
+    }
+
+    fn screen_imports(&self, config: &ProjectConfig, navigates: bool, pops: bool) -> Vec<String> {
+        let mut imports = Vec::new();
+        // Typed routes navigate through the route classes alone
+        if pops || (navigates && !config.typed_routes) {
+            imports.push("import 'package:go_router/go_router.dart';".to_string());
+        }
+        if navigates && config.typed_routes {
+            imports.push(format!("import 'package:{}/app/router.dart';", config.name));
+        }
+        imports
+    }
+
+    fn go_to(
+        &self,
+        config: &ProjectConfig,
+        route_name: &str,
+        _widget: &str,
+        key: Option<(&str, &str)>,
+        indent: usize,
+    ) -> String {
+        let pad = " ".repeat(indent);
+        match (key, config.typed_routes) {
+            (None, true) => format!("const {}().go(context)", route_class(route_name)),
+            (None, false) => format!("context.goNamed('{}')", camel_case(route_name)),
+            (Some((name, value)), true) => {
+                format!(
+                    "{}({}: {}).go(context)",
+                    route_class(route_name),
+                    name,
+                    value
+                )
+            }
+            (Some((name, value)), false) => {
+                let value = if value.contains('.') {
+                    format!("${{{}}}", value)
+                } else {
+                    format!("${}", value)
+                };
+                format!(
+                    "context.goNamed(\n{pad}  '{}',\n{pad}  pathParameters: {{'{}': '{}'}},\n{pad})",
+                    camel_case(route_name),
+                    name,
+                    value
+                )
+            }
+        }
+    }
+
+    fn pop(&self) -> &'static str {
+        "context.pop()"
+    }
+}
+
+fn builder(route: &Route) -> String {
+    if route.params.is_empty() {
+        return format!("(context, state) => const {}()", route.class_name());
+    }
+
+    let args = route
+        .params
+        .iter()
+        .map(|p| {
+            let value = if p.query {
+                format!("state.uri.queryParameters['{}']", p.name)
+            } else {
+                format!("state.pathParameters['{}']!", p.name)
+            };
+            match (p.dart_type.as_str(), p.query) {
+                ("String", _) => format!("{}: {}", camel_case(&p.name), value),
+                (dart_type, true) => format!(
+                    "{}: {}.tryParse({} ?? '')",
+                    camel_case(&p.name),
+                    dart_type,
+                    value
+                ),
+                (dart_type, false) => {
+                    format!("{}: {}.parse({})", camel_case(&p.name), dart_type, value)
+                }
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("(context, state) => {}({})", route.class_name(), args)
+}
+
+fn render_route(route: &Route, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    let mut out = format!(
+        "{pad}GoRoute(\n{pad}  path: '{}',\n{pad}  name: '{}',\n",
+        route.path,
+        camel_case(&route.name),
+    );
+    if !route.guards.is_empty() {
+        out.push_str(&format!(
+            "{pad}  redirect: guard({}),\n",
+            dart_list(&route.guards)
+        ));
+    }
+    out.push_str(&format!("{pad}  builder: {},\n", builder(route)));
+    if !route.children.is_empty() {
+        let children = route
+            .children
+            .iter()
+            .map(|child| render_route(child, indent + 4))
+            .collect::<Vec<_>>();
+        out.push_str(&format!(
+            "{pad}  routes: [\n{}\n{pad}  ],\n",
+            children.join("\n")
+        ));
+    }
+    out.push_str(&format!("{pad}),"));
+    out
+}
+
+fn imports(config: &ProjectConfig, tree: &RouteTree, mut imports: Vec<String>) -> String {
+    if !tree.tabs.is_empty() {
+        imports.push(format!(
+            "import 'package:{}/app/app_shell.dart';",
+            config.name
+        ));
+    }
+    if tree.guarded() {
+        imports.push(format!(
+            "import 'package:{}/app/route_guards.dart';",
+            config.name
+        ));
+        imports.push(format!(
+            "import 'package:{}/core/services/session_provider.dart';",
+            config.name
+        ));
+    }
+    for route in tree.tabs.iter().chain(&tree.routes) {
+        route.imports(&config.name, &mut imports);
+    }
+
+    let mut seen = Vec::new();
+    imports.retain(|import| {
+        let new = !seen.contains(import);
+        seen.push(import.clone());
+        new
+    });
+    imports.join("\n")
+}
+
+// Body of the `goRouterProvider`, shared by both routing modes
+fn router_provider(tree: &RouteTree, routes: &str, typed: bool) -> String {
+    let mut session = String::new();
+    let mut refresh = String::new();
+    if tree.guarded() {
+        session = "  final session = ref.read(sessionProvider);\n\n".to_string();
+        // Typed routes run their guards in `GoRouteData.redirect`
+        if !typed {
+            session.push_str(
+                r#"  // Route-level redirect running the guards against the current session
+  GoRouterRedirect guard(List<RouteGuard> guards) =>
+      (context, state) => runGuards(session.session, state.uri.path, guards);
+
+"#,
+            );
+        }
+        // Guards run again whenever the session changes
+        refresh = "\n    refreshListenable: session,".to_string();
+    }
+
+    format!(
+        r#"final goRouterProvider = Provider<GoRouter>((ref) {{
+{}  return GoRouter(
+    initialLocation: '{}',{}
+    routes: {},
+  );
+}});
+"#,
+        session, tree.initial_location, refresh, routes,
+    )
+}
+
+pub fn generate_router_template(config: &ProjectConfig) -> String {
+    if config.typed_routes {
+        return generate_typed_router_template(config);
+    }
+
+    // Without Riverpod the router is a top-level variable, one level less deep
+    let indent = if config.use_riverpod { 6 } else { 4 };
+    let pad = " ".repeat(indent);
+    let tree = route_tree(config);
+    let mut routes = Vec::new();
+
+    // Tabs become branches of a shell route, in the order they were declared
+    if !tree.tabs.is_empty() {
+        let branches = tree
+            .tabs
+            .iter()
+            .map(|route| {
+                format!(
+                    "{pad}    StatefulShellBranch(\n{pad}      routes: [\n{}\n{pad}      ],\n{pad}    ),",
+                    render_route(route, indent + 8)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        routes.push(format!(
+            r#"{pad}StatefulShellRoute.indexedStack(
+{pad}  builder: (context, state, navigationShell) =>
+{pad}      AppShell(navigationShell: navigationShell),
+{pad}  branches: [
+{}
+{pad}  ],
+{pad}),"#,
+            branches.join("\n")
+        ));
+    }
+    routes.extend(tree.routes.iter().map(|route| render_route(route, indent)));
+
+    let mut packages = vec!["import 'package:go_router/go_router.dart';".to_string()];
+    let router = if config.use_riverpod {
+        packages.push("import 'package:hooks_riverpod/hooks_riverpod.dart';".to_string());
+        router_provider(&tree, &format!("[\n{}\n    ]", routes.join("\n")), false)
+    } else {
+        format!(
+            "final goRouter = GoRouter(\n  initialLocation: '{}',\n  routes: [\n{}\n  ],\n);\n",
+            tree.initial_location,
+            routes.join("\n")
+        )
+    };
+
+    format!("{}\n\n{}", imports(config, &tree, packages), router)
+}
+
+// Name of the generated `GoRouteData` class of a route
+fn route_class(route_name: &str) -> String {
+    format!("{}Route", pascal_case(route_name))
+}
+
+// go_router_builder matches `:param` segments against the camelCase fields
+fn typed_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(param) => format!(":{}", camel_case(param)),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn typed_annotation(route: &Route, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    let path = typed_path(&route.path);
+    let class = route_class(&route.name);
+    if route.children.is_empty() {
+        return format!("{pad}TypedGoRoute<{class}>(path: '{path}')");
+    }
+
+    let children = route
+        .children
+        .iter()
+        .map(|child| format!("{},", typed_annotation(child, indent + 4)))
+        .collect::<Vec<_>>();
+    format!(
+        "{pad}TypedGoRoute<{class}>(\n{pad}  path: '{path}',\n{pad}  routes: [\n{}\n{pad}  ],\n{pad})",
+        children.join("\n")
+    )
+}
+
+fn typed_route_class(route: &Route, classes: &mut Vec<String>) {
+    let class = route_class(&route.name);
+    let screen = route.class_name();
+
+    let (constructor, fields, build) = if route.params.is_empty() {
+        (
+            format!("  const {}();", class),
+            String::new(),
+            format!("const {}()", screen),
+        )
+    } else {
+        let args = route
+            .params
+            .iter()
+            .map(|p| {
+                if p.query {
+                    format!("this.{}", camel_case(&p.name))
+                } else {
+                    format!("required this.{}", camel_case(&p.name))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let fields = route
+            .params
+            .iter()
+            .map(|p| {
+                format!(
+                    "  final {}{} {};\n",
+                    p.dart_type,
+                    if p.query { "?" } else { "" },
+                    camel_case(&p.name)
+                )
+            })
+            .collect::<String>();
+        let build_args = route
+            .params
+            .iter()
+            .map(|p| format!("{0}: {0}", camel_case(&p.name)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        (
+            format!("  const {}({{{}}});", class, args),
+            format!("\n{}", fields),
+            format!("{}({})", screen, build_args),
+        )
+    };
+
+    let redirect = if route.guards.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"
+
+  @override
+  String? redirect(BuildContext context, GoRouterState state) => runGuards(
+        ProviderScope.containerOf(context, listen: false).read(sessionProvider).session,
+        state.uri.path,
+        {},
+      );"#,
+            dart_list(&route.guards)
+        )
+    };
+
+    classes.push(format!(
+        r#"class {class} extends GoRouteData {{
+{constructor}
+{fields}
+  @override
+  Widget build(BuildContext context, GoRouterState state) => {build};{redirect}
+}}"#
+    ));
+    for child in &route.children {
+        typed_route_class(child, classes);
+    }
+}
+
+fn generate_typed_router_template(config: &ProjectConfig) -> String {
+    let tree = route_tree(config);
+    let mut declarations = Vec::new();
+
+    if !tree.tabs.is_empty() {
+        let branches = tree
+            .tabs
+            .iter()
+            .map(|route| {
+                format!(
+                    "    TypedStatefulShellBranch<{}BranchData>(\n      routes: [\n{},\n      ],\n    ),",
+                    pascal_case(&route.name),
+                    typed_annotation(route, 8)
+                )
+            })
+            .collect::<Vec<_>>();
+        declarations.push(format!(
+            r#"@TypedStatefulShellRoute<AppShellRouteData>(
+  branches: [
+{}
+  ],
+)
+class AppShellRouteData extends StatefulShellRouteData {{
+  const AppShellRouteData();
+
+  @override
+  Widget builder(
+    BuildContext context,
+    GoRouterState state,
+    StatefulNavigationShell navigationShell,
+  ) =>
+      AppShell(navigationShell: navigationShell);
+}}"#,
+            branches.join("\n")
+        ));
+        for route in &tree.tabs {
+            declarations.push(format!(
+                "class {0}BranchData extends StatefulShellBranchData {{\n  const {0}BranchData();\n}}",
+                pascal_case(&route.name)
+            ));
+        }
+    }
+
+    for route in tree.tabs.iter().chain(&tree.routes) {
+        let mut classes = Vec::new();
+        typed_route_class(route, &mut classes);
+        if !tree.tabs.iter().any(|tab| tab.name == route.name) {
+            classes[0] = format!("@{}\n{}", typed_annotation(route, 0), classes[0]);
+        }
+        declarations.extend(classes);
+    }
+
+    format!(
+        "{}\n\npart 'router.g.dart';\n\n{}\n\n{}",
+        imports(
+            config,
+            &tree,
+            vec![
+                "import 'package:flutter/material.dart';".to_string(),
+                "import 'package:go_router/go_router.dart';".to_string(),
+                "import 'package:hooks_riverpod/hooks_riverpod.dart';".to_string(),
+            ]
+        ),
+        declarations.join("\n\n"),
+        router_provider(&tree, "$appRoutes", true)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(use_riverpod: bool) -> ProjectConfig {
+        serde_json::from_value(serde_json::json!({
+            "name": "app",
+            "package": "com.example",
+            "use_riverpod": use_riverpod,
+            "use_supabase": false,
+            "features": [
+                { "name": "home", "layers": [] },
+                {
+                    "name": "todo",
+                    "layers": [],
+                    "screens": [{
+                        "name": "todo_detail",
+                        "widget": "todo_detail",
+                        "path": ":id",
+                        "params": [{ "name": "id", "dart_type": "int" }],
+                    }],
+                },
+                { "name": "login", "layers": [] },
+            ],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn generates_a_plain_router_without_riverpod() {
+        let config = config(false);
+        let router = generate_router_template(&config);
+        assert!(!router.contains("riverpod"));
+        assert!(!router.contains("redirect"));
+        assert!(router.contains("final goRouter = GoRouter(\n  initialLocation: '/',\n  routes: [\n    GoRoute(\n      path: '/',"));
+        assert!(router.contains("builder: (context, state) => TodoDetailScreen(id: int.parse(state.pathParameters['id']!)),"));
+        assert_eq!(GoRouter.router_config(&config), "goRouter");
+    }
+
+    #[test]
+    fn guards_the_riverpod_router() {
+        let config = config(true);
+        let router = generate_router_template(&config);
+        assert!(router.contains("final goRouterProvider = Provider<GoRouter>((ref) {"));
+        assert!(router.contains("      redirect: guard([authenticated]),"));
+        assert!(router.contains("      redirect: guard([guest]),"));
+        assert_eq!(
+            GoRouter.router_config(&config),
+            "ref.watch(goRouterProvider)"
+        );
+    }
+
+    #[test]
+    fn typed_paths_use_camel_case_params() {
+        assert_eq!(typed_path(":todo_id/edit"), ":todoId/edit");
+    }
+}
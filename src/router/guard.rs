@@ -0,0 +1,444 @@
+use anyhow::{bail, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{route_tree, write_router, RouteTree};
+use crate::dart;
+use crate::project::ProjectConfig;
+
+// Condition a route checks before it is shown, stored in the config as
+// `authenticated`, `guest`, `onboarded`, `role:admin|editor` or `flag:<name>`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Guard {
+    Authenticated,
+    Guest,
+    Role(Vec<String>),
+    Onboarded,
+    Flag(String),
+}
+
+impl FromStr for Guard {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, arg) = match s.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (s, None),
+        };
+        let guard = match (name, arg) {
+            ("authenticated", None) => Guard::Authenticated,
+            ("guest", None) => Guard::Guest,
+            ("onboarded", None) => Guard::Onboarded,
+            ("role", Some(roles)) => Guard::Role(
+                roles
+                    .split('|')
+                    .map(|role| guard_value("role", role))
+                    .collect::<Result<_>>()?,
+            ),
+            ("flag", Some(flag)) => Guard::Flag(guard_value("flag", flag)?),
+            _ => bail!(
+                "Unknown guard `{}`. Use authenticated, guest, onboarded, role:<a|b> or flag:<name>",
+                s
+            ),
+        };
+        Ok(guard)
+    }
+}
+
+// Roles and flags are compared against the session's strings, e.g. `admin`,
+// `beta_search` or `billing.read`
+fn guard_value(what: &str, value: &str) -> Result<String> {
+    let valid = value.starts_with(|c: char| c.is_ascii_alphabetic())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if !valid {
+        bail!(
+            "Invalid {} `{}`, use letters, digits, `_`, `-` and `.` starting with a letter",
+            what,
+            value
+        );
+    }
+    Ok(value.to_string())
+}
+
+impl TryFrom<String> for Guard {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Guard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Guard::Authenticated => write!(f, "authenticated"),
+            Guard::Guest => write!(f, "guest"),
+            Guard::Role(roles) => write!(f, "role:{}", roles.join("|")),
+            Guard::Onboarded => write!(f, "onboarded"),
+            Guard::Flag(flag) => write!(f, "flag:{}", flag),
+        }
+    }
+}
+
+impl From<Guard> for String {
+    fn from(guard: Guard) -> Self {
+        guard.to_string()
+    }
+}
+
+impl Guard {
+    // The matching `RouteGuard` in `route_guards.dart`
+    pub fn dart_expr(&self) -> String {
+        match self {
+            Guard::Authenticated => "authenticated".to_string(),
+            Guard::Guest => "guest".to_string(),
+            Guard::Role(roles) => format!(
+                "role([{}])",
+                roles
+                    .iter()
+                    .map(|r| dart::string_literal(r))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Guard::Onboarded => "onboarded".to_string(),
+            Guard::Flag(flag) => format!("flag({})", dart::string_literal(flag)),
+        }
+    }
+}
+
+pub fn dart_list(guards: &[Guard]) -> String {
+    format!(
+        "[{}]",
+        guards
+            .iter()
+            .map(Guard::dart_expr)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+// Route the `onboarded` guard sends users to until they finish onboarding
+const ONBOARDING_ROUTE: &str = "onboarding";
+
+pub(super) fn generate_route_guards_template(config: &ProjectConfig, tree: &RouteTree) -> String {
+    let login_location = if tree.login_path.is_empty() {
+        tree.initial_location.as_str()
+    } else {
+        tree.login_path.as_str()
+    };
+    // `add_guards` only lets routes use `onboarded` with an onboarding route
+    let (onboarding_location, onboarded) = match tree.location(ONBOARDING_ROUTE) {
+        Some(location) => (
+            format!("const onboardingLocation = '{}';\n", location),
+            "String? onboarded(AppSession session) =>\n    session.onboardingComplete ? null : onboardingLocation;\n\n",
+        ),
+        None => (String::new(), ""),
+    };
+
+    format!(
+        r#"import 'package:{project_name}/core/services/session_provider.dart';
+
+// A guard returns the location to redirect to, or null to let the
+// navigation through
+typedef RouteGuard = String? Function(AppSession session);
+
+const loginLocation = '{login_location}';
+const homeLocation = '{home_location}';
+{onboarding_location}
+String? authenticated(AppSession session) =>
+    session.isLoggedIn ? null : loginLocation;
+
+String? guest(AppSession session) => session.isLoggedIn ? homeLocation : null;
+
+{onboarded}RouteGuard role(List<String> roles) => (session) {{
+      if (!session.isLoggedIn) return loginLocation;
+      return roles.contains(session.role) ? null : homeLocation;
+    }};
+
+RouteGuard flag(String name) =>
+    (session) => session.flags.contains(name) ? null : homeLocation;
+
+// Runs the guards in order, the first redirect that leaves the current
+// location wins
+String? runGuards(
+  AppSession session,
+  String location,
+  List<RouteGuard> guards,
+) {{
+  for (final guard in guards) {{
+    final redirect = guard(session);
+    if (redirect != null && redirect != location) return redirect;
+  }}
+  return null;
+}}
+"#,
+        project_name = config.name,
+        home_location = tree.initial_location,
+    )
+}
+
+pub(super) fn generate_session_template(config: &ProjectConfig) -> String {
+    if !config.use_supabase {
+        return r#"import 'package:flutter/foundation.dart';
+import 'package:hooks_riverpod/hooks_riverpod.dart';
+
+// What the route guards know about the current user
+class AppSession {
+  const AppSession({
+    this.isLoggedIn = false,
+    this.role,
+    this.onboardingComplete = false,
+    this.flags = const {},
+  });
+
+  final bool isLoggedIn;
+  final String? role;
+  final bool onboardingComplete;
+  final Set<String> flags;
+}
+
+// Notifies the router whenever the session changes, so the guards run again
+class SessionNotifier extends ChangeNotifier {
+  AppSession _session = const AppSession();
+
+  AppSession get session => _session;
+
+  // TODO: Update the session from your auth layer
+  set session(AppSession value) {
+    _session = value;
+    notifyListeners();
+  }
+}
+
+final sessionProvider = ChangeNotifierProvider<SessionNotifier>(
+  (ref) => SessionNotifier(),
+);
+"#
+        .to_string();
+    }
+
+    r#"import 'dart:async';
+
+import 'package:flutter/foundation.dart';
+import 'package:hooks_riverpod/hooks_riverpod.dart';
+import 'package:supabase_flutter/supabase_flutter.dart';
+
+// What the route guards know about the current user
+class AppSession {
+  const AppSession({
+    this.isLoggedIn = false,
+    this.role,
+    this.onboardingComplete = false,
+    this.flags = const {},
+  });
+
+  final bool isLoggedIn;
+  final String? role;
+  final bool onboardingComplete;
+  final Set<String> flags;
+}
+
+// Follows Supabase auth state and notifies the router whenever the session
+// changes, so the guards run again
+class SessionNotifier extends ChangeNotifier {
+  SessionNotifier() {
+    final auth = Supabase.instance.client.auth;
+    _update(auth.currentUser);
+    _subscription = auth.onAuthStateChange.listen(
+      (state) => _update(state.session?.user),
+    );
+  }
+
+  late final StreamSubscription<AuthState> _subscription;
+  AppSession _session = const AppSession();
+
+  AppSession get session => _session;
+
+  set session(AppSession value) {
+    _session = value;
+    notifyListeners();
+  }
+
+  // Role and onboarding come from the user's metadata, flags are kept
+  void _update(User? user) {
+    session = AppSession(
+      isLoggedIn: user != null,
+      role: user?.appMetadata['role'] as String?,
+      onboardingComplete: user?.userMetadata?['onboarding_complete'] == true,
+      flags: _session.flags,
+    );
+  }
+
+  @override
+  void dispose() {
+    _subscription.cancel();
+    super.dispose();
+  }
+}
+
+final sessionProvider = ChangeNotifierProvider<SessionNotifier>(
+  (ref) => SessionNotifier(),
+);
+"#
+    .to_string()
+}
+
+// Every guard needs a redirect target other than the route it guards
+fn check_redirects(tree: &RouteTree) -> Result<()> {
+    let onboarded = tree
+        .all()
+        .iter()
+        .any(|route| route.guards.contains(&Guard::Onboarded));
+    if onboarded && tree.location(ONBOARDING_ROUTE).is_none() {
+        bail!(
+            "The onboarded guard redirects to the `{}` route, add an `{}` feature or screen first",
+            ONBOARDING_ROUTE,
+            ONBOARDING_ROUTE
+        );
+    }
+
+    // `runGuards` ignores redirects to the current location, so these would
+    // let everyone in
+    let home = tree
+        .guards_at(&tree.initial_location)
+        .into_iter()
+        .find(|guard| matches!(guard, Guard::Role(_) | Guard::Flag(_)));
+    if let Some(guard) = home {
+        bail!(
+            "The {} guard redirects to the home location `{}`, so it cannot guard that route or its parents",
+            guard,
+            tree.initial_location
+        );
+    }
+    Ok(())
+}
+
+pub fn add_guards(project_dir: &Path, route: &str, guards: &[String]) -> Result<()> {
+    let mut config = ProjectConfig::load(project_dir)?;
+
+    if !config.use_riverpod {
+        bail!(
+            "Guards read the session from a Riverpod provider, but this project does not use Riverpod"
+        );
+    }
+
+    let guards = if guards == ["public"] {
+        Vec::new()
+    } else {
+        guards
+            .iter()
+            .map(|g| g.parse())
+            .collect::<Result<Vec<Guard>>>()?
+    };
+
+    if let Some(feature) = config.feature_mut(route) {
+        feature.guards = Some(guards);
+    } else if let Some(screen) = config.screen_mut(route) {
+        screen.guards = guards;
+    } else {
+        bail!("Unknown route `{}`", route);
+    }
+
+    check_redirects(&route_tree(&config))?;
+
+    write_router(project_dir, &config)?;
+    config.save(project_dir)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_guards() {
+        assert_eq!(
+            "authenticated".parse::<Guard>().unwrap(),
+            Guard::Authenticated
+        );
+        assert_eq!(
+            "role:admin|super-admin".parse::<Guard>().unwrap(),
+            Guard::Role(vec!["admin".to_string(), "super-admin".to_string()])
+        );
+        assert_eq!(
+            "flag:billing.beta".parse::<Guard>().unwrap(),
+            Guard::Flag("billing.beta".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_empty_and_unsafe_values() {
+        for spec in [
+            "role:",
+            "role:admin|",
+            "role:|admin",
+            "role:o'brien",
+            "role:$x",
+            "flag:",
+            "flag:1st",
+            "flag:a b",
+            "guest:x",
+            "admin",
+        ] {
+            assert!(spec.parse::<Guard>().is_err(), "accepted `{}`", spec);
+        }
+    }
+
+    #[test]
+    fn round_trips_through_the_config_string() {
+        for spec in ["guest", "onboarded", "role:admin|editor", "flag:beta"] {
+            let guard: Guard = spec.parse().unwrap();
+            assert_eq!(guard.to_string(), spec);
+        }
+        let guards = ["authenticated", "role:admin|editor", "flag:beta"]
+            .map(|s| s.parse::<Guard>().unwrap());
+        assert_eq!(
+            dart_list(&guards),
+            "[authenticated, role(['admin', 'editor']), flag('beta')]"
+        );
+    }
+
+    fn config(guards: serde_json::Value) -> ProjectConfig {
+        serde_json::from_value(serde_json::json!({
+            "name": "app",
+            "package": "com.example",
+            "use_riverpod": true,
+            "use_supabase": false,
+            "features": [
+                { "name": "home", "layers": [], "guards": guards },
+                {
+                    "name": "admin",
+                    "layers": [],
+                    "guards": ["role:admin"],
+                    "screens": [{
+                        "name": "audit",
+                        "widget": "audit",
+                        "path": "audit",
+                        "guards": ["flag:audit"],
+                    }],
+                },
+            ],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn role_and_flag_guards_cannot_guard_home() {
+        assert!(check_redirects(&route_tree(&config(serde_json::json!([])))).is_ok());
+        assert!(
+            check_redirects(&route_tree(&config(serde_json::json!(["authenticated"])))).is_ok()
+        );
+        for guard in ["role:admin", "flag:beta"] {
+            let config = config(serde_json::json!([guard]));
+            let tree = route_tree(&config);
+            assert_eq!(tree.guards_at(&tree.initial_location).len(), 1);
+            assert!(check_redirects(&tree).is_err(), "accepted `{}`", guard);
+        }
+    }
+}
@@ -0,0 +1,416 @@
+mod auto_route;
+mod go_router;
+pub mod guard;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use self::guard::{generate_route_guards_template, generate_session_template, Guard};
+use crate::deep_link::write_deep_links;
+use crate::pascal_case;
+use crate::project::{Feature, ProjectConfig, RouteParam, Screen};
+
+use self::auto_route::AutoRoute;
+use self::go_router::GoRouter;
+
+// Features that live below the `/auth` parent route
+pub const AUTH_FEATURES: [&str; 3] = ["login", "register", "forgot_password"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum RouterKind {
+    #[default]
+    GoRouter,
+    AutoRoute,
+}
+
+impl fmt::Display for RouterKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RouterKind::GoRouter => write!(f, "go_router"),
+            RouterKind::AutoRoute => write!(f, "auto_route"),
+        }
+    }
+}
+
+// Everything that differs between the routing packages flutter_gen targets
+pub trait RouterBackend {
+    /// Contents of `lib/app/router.dart`
+    fn generate_router(&self, config: &ProjectConfig) -> String;
+
+    fn dependencies(&self) -> &'static [&'static str];
+
+    fn dev_dependencies(&self, config: &ProjectConfig) -> Vec<&'static str>;
+
+    /// Whether `router.dart` relies on build_runner output
+    fn uses_codegen(&self, config: &ProjectConfig) -> bool;
+
+    /// Expression the app passes as `routerConfig`
+    fn router_config(&self, config: &ProjectConfig) -> &'static str;
+
+    /// Imports of a screen that navigates with `go_to` and/or leaves with `pop`
+    fn screen_imports(&self, config: &ProjectConfig, navigates: bool, pops: bool) -> Vec<String>;
+
+    /// Expression navigating to a route, passing its key path parameter
+    fn go_to(
+        &self,
+        config: &ProjectConfig,
+        route_name: &str,
+        widget: &str,
+        key: Option<(&str, &str)>,
+        indent: usize,
+    ) -> String;
+
+    /// Expression leaving the current screen
+    fn pop(&self) -> &'static str;
+
+    /// Screen source with whatever the router expects on the widget class
+    fn annotate_screen(&self, source: &str, _class_name: &str, _params: &[RouteParam]) -> String {
+        source.to_string()
+    }
+}
+
+pub fn backend(config: &ProjectConfig) -> &'static dyn RouterBackend {
+    match config.router {
+        RouterKind::GoRouter => &GoRouter,
+        RouterKind::AutoRoute => &AutoRoute,
+    }
+}
+
+fn join_path(parent: &str, path: &str) -> String {
+    if path.starts_with('/') {
+        path.to_string()
+    } else if parent == "/" {
+        format!("/{}", path)
+    } else {
+        format!("{}/{}", parent, path)
+    }
+}
+
+fn route_segment(name: &str) -> String {
+    name.replace('_', "-")
+}
+
+pub fn screen_import(project_name: &str, feature: &str, widget: &str) -> String {
+    format!(
+        "import 'package:{}/features/{}/presentation/{}_screen.dart';",
+        project_name, feature, widget
+    )
+}
+
+// A route, resolved from the features and screens of the config
+struct Route<'a> {
+    name: String,
+    path: String,
+    feature: &'a str,
+    widget: &'a str,
+    params: &'a [RouteParam],
+    // Checked in addition to the guards of the parent routes
+    guards: Vec<Guard>,
+    children: Vec<Route<'a>>,
+}
+
+impl<'a> Route<'a> {
+    fn feature(feature: &'a Feature, path: String, default_guards: &[Guard]) -> Self {
+        Route {
+            name: feature.name.clone(),
+            path,
+            feature: &feature.name,
+            widget: feature.root_screen(),
+            params: &[],
+            guards: feature
+                .guards
+                .clone()
+                .unwrap_or_else(|| default_guards.to_vec()),
+            children: feature
+                .screens
+                .iter()
+                .map(|screen| Route::screen(feature, screen))
+                .collect(),
+        }
+    }
+
+    fn screen(feature: &'a Feature, screen: &'a Screen) -> Self {
+        Route {
+            name: screen.name.clone(),
+            path: screen.path.clone(),
+            feature: &feature.name,
+            widget: &screen.widget,
+            params: &screen.params,
+            guards: screen.guards.clone(),
+            children: screen
+                .children
+                .iter()
+                .map(|child| Route::screen(feature, child))
+                .collect(),
+        }
+    }
+
+    fn class_name(&self) -> String {
+        format!("{}Screen", pascal_case(self.widget))
+    }
+
+    fn imports(&self, project_name: &str, imports: &mut Vec<String>) {
+        imports.push(screen_import(project_name, self.feature, self.widget));
+        for child in &self.children {
+            child.imports(project_name, imports);
+        }
+    }
+
+    fn walk<'r>(&'r self, routes: &mut Vec<&'r Route<'a>>) {
+        routes.push(self);
+        for child in &self.children {
+            child.walk(routes);
+        }
+    }
+}
+
+struct RouteTree<'a> {
+    // One branch per navigation tab, in tab order
+    tabs: Vec<Route<'a>>,
+    routes: Vec<Route<'a>>,
+    // Empty without auth features
+    login_path: String,
+    initial_location: String,
+}
+
+impl RouteTree<'_> {
+    fn all(&self) -> Vec<&Route<'_>> {
+        let mut routes = Vec::new();
+        for route in self.tabs.iter().chain(&self.routes) {
+            route.walk(&mut routes);
+        }
+        routes
+    }
+
+    // Full path of every route, as incoming links are matched against them
+    fn paths(&self) -> Vec<String> {
+        fn walk(route: &Route, parent: &str, paths: &mut Vec<String>) {
+            let path = join_path(parent, &route.path);
+            if !paths.contains(&path) {
+                paths.push(path.clone());
+            }
+            for child in &route.children {
+                walk(child, &path, paths);
+            }
+        }
+
+        let mut paths = Vec::new();
+        for route in self.tabs.iter().chain(&self.routes) {
+            walk(route, "/", &mut paths);
+        }
+        paths
+    }
+
+    // Full path of the first route with the given name
+    fn location(&self, name: &str) -> Option<String> {
+        fn find(route: &Route, parent: &str, name: &str) -> Option<String> {
+            let path = join_path(parent, &route.path);
+            if route.name == name {
+                return Some(path);
+            }
+            route
+                .children
+                .iter()
+                .find_map(|child| find(child, &path, name))
+        }
+
+        self.tabs
+            .iter()
+            .chain(&self.routes)
+            .find_map(|route| find(route, "/", name))
+    }
+
+    // Guards checked before the route at `location` is shown, its parents' included
+    fn guards_at(&self, location: &str) -> Vec<&Guard> {
+        fn find<'r>(
+            route: &'r Route,
+            parent: &str,
+            location: &str,
+            guards: &mut Vec<&'r Guard>,
+        ) -> bool {
+            let path = join_path(parent, &route.path);
+            let len = guards.len();
+            guards.extend(&route.guards);
+            if path == location
+                || route
+                    .children
+                    .iter()
+                    .any(|child| find(child, &path, location, guards))
+            {
+                return true;
+            }
+            guards.truncate(len);
+            false
+        }
+
+        let mut guards = Vec::new();
+        for route in self.tabs.iter().chain(&self.routes) {
+            if find(route, "/", location, &mut guards) {
+                break;
+            }
+        }
+        guards
+    }
+
+    // Whether the router needs the session and `route_guards.dart`
+    fn guarded(&self) -> bool {
+        self.all().iter().any(|route| !route.guards.is_empty())
+    }
+}
+
+fn route_tree(config: &ProjectConfig) -> RouteTree<'_> {
+    let auth_features = config
+        .features
+        .iter()
+        .filter(|f| AUTH_FEATURES.contains(&f.name.as_str()))
+        .collect::<Vec<_>>();
+    let app_features = config
+        .features
+        .iter()
+        .filter(|f| !AUTH_FEATURES.contains(&f.name.as_str()))
+        .collect::<Vec<_>>();
+
+    // Without declared guards, the auth routes are for guests and everything
+    // else needs a login. The session the guards read is a Riverpod provider.
+    let (app_guards, auth_guards) = if auth_features.is_empty() || !config.use_riverpod {
+        (Vec::new(), Vec::new())
+    } else {
+        (vec![Guard::Authenticated], vec![Guard::Guest])
+    };
+
+    // `home` owns `/`, every other feature gets a top-level route of its own
+    let feature_path = |feature: &Feature| {
+        if feature.name == "home" {
+            "/".to_string()
+        } else {
+            format!("/{}", route_segment(&feature.name))
+        }
+    };
+
+    let tab_names = config
+        .navigation
+        .as_ref()
+        .map(|n| n.tabs.as_slice())
+        .unwrap_or_default();
+    let tabs = tab_names
+        .iter()
+        .filter_map(|tab| app_features.iter().find(|f| &f.name == tab))
+        .map(|feature| Route::feature(feature, feature_path(feature), &app_guards))
+        .collect::<Vec<_>>();
+    let mut routes = app_features
+        .iter()
+        .filter(|f| !tab_names.contains(&f.name))
+        .map(|feature| Route::feature(feature, feature_path(feature), &app_guards))
+        .collect::<Vec<_>>();
+
+    let initial_location = tabs
+        .iter()
+        .chain(&routes)
+        .map(|route| route.path.as_str())
+        .find(|path| *path == "/")
+        .or_else(|| tabs.iter().chain(&routes).map(|r| r.path.as_str()).next())
+        .unwrap_or("/auth")
+        .to_string();
+
+    let mut login_path = String::new();
+    if let Some(first) = auth_features.first() {
+        login_path = format!("/auth/{}", route_segment(&first.name));
+
+        routes.push(Route {
+            name: "auth".to_string(),
+            path: "/auth".to_string(),
+            feature: &first.name,
+            widget: first.root_screen(),
+            params: &[],
+            guards: auth_guards,
+            children: auth_features
+                .iter()
+                .map(|feature| Route::feature(feature, route_segment(&feature.name), &[]))
+                .collect(),
+        });
+    }
+
+    RouteTree {
+        tabs,
+        routes,
+        login_path,
+        initial_location,
+    }
+}
+
+pub fn write_router(project_dir: &Path, config: &ProjectConfig) -> Result<()> {
+    let backend = backend(config);
+    let app_dir = project_dir.join("lib").join("app");
+    fs::write(app_dir.join("router.dart"), backend.generate_router(config))?;
+
+    let tree = route_tree(config);
+    if config.use_riverpod && tree.guarded() {
+        fs::write(
+            app_dir.join("route_guards.dart"),
+            generate_route_guards_template(config, &tree),
+        )?;
+
+        // The session is wired to the app's auth by hand, so keep it
+        let services = project_dir.join("lib").join("core").join("services");
+        let session = services.join("session_provider.dart");
+        if !session.exists() {
+            fs::create_dir_all(&services)?;
+            fs::write(session, generate_session_template(config))?;
+        }
+    }
+
+    if let Some(links) = &config.deep_links {
+        write_deep_links(project_dir, config, links, &tree.paths())?;
+    }
+
+    // Screens may have been written before the router knew about them
+    let routes = tree.all();
+    for route in &routes {
+        let path = project_dir
+            .join("lib")
+            .join("features")
+            .join(route.feature)
+            .join("presentation")
+            .join(format!("{}_screen.dart", route.widget));
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        // A widget shared by several routes takes the parameters of all of them
+        let mut params: Vec<RouteParam> = Vec::new();
+        for other in routes.iter().filter(|r| r.widget == route.widget) {
+            for param in other.params {
+                if !params.iter().any(|p| p.name == param.name) {
+                    params.push(param.clone());
+                }
+            }
+        }
+
+        let annotated = backend.annotate_screen(&source, &route.class_name(), &params);
+        if annotated != source {
+            fs::write(&path, annotated)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Regenerates the router's build_runner output
+pub fn run_build_runner(project_dir: &Path) -> Result<()> {
+    Command::new("dart")
+        .current_dir(project_dir)
+        .args([
+            "run",
+            "build_runner",
+            "build",
+            "--delete-conflicting-outputs",
+        ])
+        .status()?;
+    Ok(())
+}
@@ -0,0 +1,555 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::model::{FieldSpec, FieldType, ModelSpec};
+
+// Reads either a single schema dump or a directory of migrations and returns
+// one model per table in the `public` schema. Migrations are applied in file
+// name order, which matches the timestamp prefixes the Supabase CLI uses.
+pub fn load_schema(path: &Path) -> Result<Vec<ModelSpec>> {
+    let mut sources = Vec::new();
+
+    if path.is_dir() {
+        let mut files = fs::read_dir(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "sql"))
+            .collect::<Vec<_>>();
+        files.sort();
+
+        for file in files {
+            sources.push(
+                fs::read_to_string(&file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?,
+            );
+        }
+    } else {
+        sources.push(
+            fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?,
+        );
+    }
+
+    let mut tables = Vec::new();
+    for source in &sources {
+        apply_sql(&mut tables, source);
+    }
+
+    tables.retain(|t: &ModelSpec| !t.fields.is_empty());
+    Ok(tables)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Literal,
+    Symbol(char),
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn ident(&self) -> Option<String> {
+        match self {
+            Token::Word(w) => Some(w.to_lowercase()),
+            Token::Quoted(q) => Some(q.clone()),
+            _ => None,
+        }
+    }
+}
+
+fn tokenize(sql: &str) -> Vec<Token> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '\'' {
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '\'' {
+                    if chars.get(i + 1) == Some(&'\'') {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Literal);
+        } else if c == '"' {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && chars[i] != '"' {
+                i += 1;
+            }
+            tokens.push(Token::Quoted(
+                chars[start..i.min(chars.len())].iter().collect(),
+            ));
+            i += 1;
+        } else if c == '$' {
+            // Dollar-quoted bodies ($$ ... $$ or $tag$ ... $tag$) hold function
+            // code that may contain semicolons, so skip them wholesale.
+            let mut end = i + 1;
+            while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+                end += 1;
+            }
+            if chars.get(end) == Some(&'$') {
+                let tag: Vec<char> = chars[i..=end].to_vec();
+                i = end + 1;
+                while i < chars.len() && !chars[i..].starts_with(&tag) {
+                    i += 1;
+                }
+                i += tag.len();
+                tokens.push(Token::Literal);
+            } else {
+                tokens.push(Token::Symbol(c));
+                i += 1;
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Token::Symbol(c));
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+fn apply_sql(tables: &mut Vec<ModelSpec>, sql: &str) {
+    let tokens = tokenize(sql);
+
+    for statement in tokens.split(|t| *t == Token::Symbol(';')) {
+        if statement.first().is_some_and(|t| t.is_keyword("create")) {
+            parse_create_table(tables, statement);
+        } else if statement.first().is_some_and(|t| t.is_keyword("alter")) {
+            parse_alter_table(tables, statement);
+        } else if statement.first().is_some_and(|t| t.is_keyword("drop"))
+            && statement.get(1).is_some_and(|t| t.is_keyword("table"))
+        {
+            let mut pos = 2;
+            skip_keywords(statement, &mut pos, &["if", "exists"]);
+            for name in split_top_level(&statement[pos..]) {
+                if let Some(table) = table_name(name, &mut 0) {
+                    tables.retain(|t| t.table.as_deref() != Some(table.as_str()));
+                }
+            }
+        }
+    }
+}
+
+fn skip_keywords(tokens: &[Token], pos: &mut usize, keywords: &[&str]) {
+    while *pos < tokens.len() && keywords.iter().any(|k| tokens[*pos].is_keyword(k)) {
+        *pos += 1;
+    }
+}
+
+// Reads a possibly schema-qualified table name. Tables outside `public` (auth,
+// storage, ...) are not part of the app's data model and yield `None`.
+fn table_name(tokens: &[Token], pos: &mut usize) -> Option<String> {
+    let first = tokens.get(*pos)?.ident()?;
+    *pos += 1;
+
+    if tokens.get(*pos) == Some(&Token::Symbol('.')) {
+        let second = tokens.get(*pos + 1)?.ident()?;
+        *pos += 2;
+        return (first == "public").then_some(second);
+    }
+
+    Some(first)
+}
+
+// Splits a token list on commas that are not nested inside parentheses.
+fn split_top_level(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Symbol('(') => depth += 1,
+            Token::Symbol(')') => depth -= 1,
+            Token::Symbol(',') if depth == 0 => {
+                parts.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < tokens.len() {
+        parts.push(&tokens[start..]);
+    }
+
+    parts
+}
+
+fn parse_create_table(tables: &mut Vec<ModelSpec>, tokens: &[Token]) {
+    let mut pos = 1;
+    skip_keywords(
+        tokens,
+        &mut pos,
+        &["global", "local", "temp", "temporary", "unlogged"],
+    );
+    if !tokens.get(pos).is_some_and(|t| t.is_keyword("table")) {
+        return;
+    }
+    pos += 1;
+    skip_keywords(tokens, &mut pos, &["if", "not", "exists"]);
+
+    let Some(name) = table_name(tokens, &mut pos) else {
+        return;
+    };
+    if tokens.get(pos) != Some(&Token::Symbol('(')) {
+        // CREATE TABLE ... AS / PARTITION OF carry no column list
+        return;
+    }
+
+    let Some(body) = parenthesized(tokens, pos) else {
+        return;
+    };
+
+    let mut model = ModelSpec {
+        name: feature_name(&name),
+        table: Some(name.clone()),
+        fields: Vec::new(),
+    };
+
+    for definition in split_top_level(body) {
+        if is_table_constraint(definition) {
+            apply_table_constraint(&mut model, definition);
+        } else if let Some(field) = parse_column(definition) {
+            model.fields.push(field);
+        }
+    }
+
+    tables.retain(|t| t.table.as_deref() != Some(name.as_str()));
+    tables.push(model);
+}
+
+// Quoted table names may be mixed case; feature folders are snake_case.
+fn feature_name(table: &str) -> String {
+    let mut name = String::new();
+    for (i, c) in table.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 && !name.ends_with('_') {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+        } else if c.is_alphanumeric() {
+            name.push(c);
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    name
+}
+
+fn parse_alter_table(tables: &mut [ModelSpec], tokens: &[Token]) {
+    if !tokens.get(1).is_some_and(|t| t.is_keyword("table")) {
+        return;
+    }
+    let mut pos = 2;
+    skip_keywords(tokens, &mut pos, &["if", "exists", "only"]);
+
+    let Some(name) = table_name(tokens, &mut pos) else {
+        return;
+    };
+    let Some(model) = tables
+        .iter_mut()
+        .find(|t| t.table.as_deref() == Some(name.as_str()))
+    else {
+        return;
+    };
+
+    for action in split_top_level(&tokens[pos..]) {
+        let Some(first) = action.first() else {
+            continue;
+        };
+
+        if first.is_keyword("add") {
+            let mut pos = 1;
+            if is_table_constraint(&action[pos..]) {
+                apply_table_constraint(model, &action[pos..]);
+                continue;
+            }
+            skip_keywords(action, &mut pos, &["column", "if", "not", "exists"]);
+            if let Some(field) = parse_column(&action[pos..]) {
+                model.fields.retain(|f| f.column != field.column);
+                model.fields.push(field);
+            }
+        } else if first.is_keyword("drop") {
+            let mut pos = 1;
+            if action.get(pos).is_some_and(|t| t.is_keyword("constraint")) {
+                continue;
+            }
+            skip_keywords(action, &mut pos, &["column", "if", "exists"]);
+            if let Some(column) = action.get(pos).and_then(Token::ident) {
+                model.fields.retain(|f| f.column != column);
+            }
+        } else if first.is_keyword("rename")
+            && action.get(1).is_some_and(|t| t.is_keyword("column"))
+        {
+            let from = action.get(2).and_then(Token::ident);
+            let to = action.get(4).and_then(Token::ident);
+            if let (Some(from), Some(to)) = (from, to) {
+                if let Some(field) = model.fields.iter_mut().find(|f| f.column == from) {
+                    field.column = to;
+                }
+            }
+        }
+    }
+}
+
+// Returns the tokens between the parenthesis at `open` and its match.
+fn parenthesized(tokens: &[Token], open: usize) -> Option<&[Token]> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::Symbol('(') => depth += 1,
+            Token::Symbol(')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&tokens[open + 1..i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_table_constraint(tokens: &[Token]) -> bool {
+    tokens.first().is_some_and(|t| {
+        [
+            "constraint",
+            "primary",
+            "foreign",
+            "unique",
+            "check",
+            "exclude",
+            "like",
+        ]
+        .iter()
+        .any(|k| t.is_keyword(k))
+    })
+}
+
+fn apply_table_constraint(model: &mut ModelSpec, tokens: &[Token]) {
+    let Some(primary) = tokens.iter().position(|t| t.is_keyword("primary")) else {
+        return;
+    };
+    let Some(open) = tokens[primary..]
+        .iter()
+        .position(|t| *t == Token::Symbol('('))
+    else {
+        return;
+    };
+
+    if let Some(columns) = parenthesized(tokens, primary + open) {
+        for column in columns.iter().filter_map(Token::ident) {
+            if let Some(field) = model.fields.iter_mut().find(|f| f.column == column) {
+                field.primary_key = true;
+                field.nullable = false;
+            }
+        }
+    }
+}
+
+const COLUMN_CONSTRAINTS: [&str; 10] = [
+    "not",
+    "null",
+    "primary",
+    "default",
+    "references",
+    "unique",
+    "check",
+    "constraint",
+    "generated",
+    "collate",
+];
+
+fn parse_column(tokens: &[Token]) -> Option<FieldSpec> {
+    let column = tokens.first()?.ident()?;
+
+    let type_end = tokens
+        .iter()
+        .skip(1)
+        .position(|t| COLUMN_CONSTRAINTS.iter().any(|k| t.is_keyword(k)))
+        .map(|p| p + 1)
+        .unwrap_or(tokens.len());
+    let type_tokens = &tokens[1..type_end];
+    let constraints = &tokens[type_end..];
+
+    let (field_type, is_list, is_serial) = map_sql_type(type_tokens);
+
+    let mut field = FieldSpec {
+        column,
+        field_type,
+        is_list,
+        nullable: true,
+        primary_key: false,
+        has_default: is_serial,
+    };
+
+    for (i, token) in constraints.iter().enumerate() {
+        if token.is_keyword("not") && constraints.get(i + 1).is_some_and(|t| t.is_keyword("null")) {
+            field.nullable = false;
+        } else if token.is_keyword("primary") {
+            field.primary_key = true;
+            field.nullable = false;
+        } else if token.is_keyword("default") || token.is_keyword("generated") {
+            field.has_default = true;
+        }
+    }
+
+    Some(field)
+}
+
+// Maps a Postgres column type to a Dart type. Returns the field type, whether
+// the column is an array and whether it is a serial (auto-incrementing) type.
+fn map_sql_type(tokens: &[Token]) -> (FieldType, bool, bool) {
+    let mut words = Vec::new();
+    let mut is_list = false;
+    let mut depth = 0;
+
+    for token in tokens {
+        match token {
+            Token::Symbol('(') => depth += 1,
+            Token::Symbol(')') => depth -= 1,
+            Token::Symbol('[') => is_list = true,
+            Token::Word(w) if depth == 0 && w.eq_ignore_ascii_case("array") => is_list = true,
+            Token::Word(w) | Token::Quoted(w) if depth == 0 => words.push(w.to_lowercase()),
+            _ => {}
+        }
+    }
+
+    // Qualified user types (public.status) keep only the type name
+    let name = words.last().cloned().unwrap_or_default();
+    let full = words.join(" ");
+    let name = match name.strip_prefix('_') {
+        // Internal array names such as _text
+        Some(element) => {
+            is_list = true;
+            element.to_string()
+        }
+        None => name,
+    };
+
+    let is_serial = name.contains("serial");
+    let field_type = match name.as_str() {
+        "smallint" | "integer" | "int" | "int2" | "int4" | "int8" | "bigint" | "smallserial"
+        | "serial" | "bigserial" | "serial2" | "serial4" | "serial8" => FieldType::Int,
+        "real" | "float4" | "float8" | "numeric" | "decimal" | "money" => FieldType::Double,
+        "precision" if full.starts_with("double") => FieldType::Double,
+        "boolean" | "bool" => FieldType::Bool,
+        "date" | "timestamp" | "timestamptz" => FieldType::DateTime,
+        "zone" if full.starts_with("timestamp") => FieldType::DateTime,
+        "json" | "jsonb" => FieldType::Json,
+        _ => FieldType::String,
+    };
+
+    (field_type, is_list, is_serial)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn models(sql: &str) -> Vec<ModelSpec> {
+        let mut tables = Vec::new();
+        apply_sql(&mut tables, sql);
+        tables
+    }
+
+    #[test]
+    fn tokenizer_skips_comments_literals_and_dollar_quotes() {
+        let tokens = tokenize(
+            "-- note\ncreate /* x; */ table \"Todo Items\" (a text default 'it''s; ok');\n$$ begin; end $$",
+        );
+        assert_eq!(tokens[0], Token::Word("create".to_string()));
+        assert_eq!(tokens[1], Token::Word("table".to_string()));
+        assert_eq!(tokens[2], Token::Quoted("Todo Items".to_string()));
+        assert_eq!(
+            tokens.iter().filter(|t| **t == Token::Symbol(';')).count(),
+            1
+        );
+        assert_eq!(tokens.last(), Some(&Token::Literal));
+    }
+
+    #[test]
+    fn parses_create_table_columns() {
+        let tables = models(
+            "create table public.todos (
+                id bigint generated by default as identity primary key,
+                title text not null,
+                tags text[],
+                price numeric(10, 2),
+                done boolean default false,
+                due_at timestamp with time zone,
+                meta jsonb
+            );
+            create table auth.users (id uuid primary key);",
+        );
+        assert_eq!(tables.len(), 1);
+        let todos = &tables[0];
+        assert_eq!(todos.name, "todos");
+        assert_eq!(todos.key().column, "id");
+
+        let field = |column: &str| todos.fields.iter().find(|f| f.column == column).unwrap();
+        assert!(field("id").primary_key && field("id").has_default);
+        assert_eq!(field("title").field_type, FieldType::String);
+        assert!(!field("title").nullable);
+        assert!(field("tags").is_list);
+        assert_eq!(field("price").field_type, FieldType::Double);
+        assert!(field("done").has_default);
+        assert_eq!(field("due_at").field_type, FieldType::DateTime);
+        assert_eq!(field("meta").field_type, FieldType::Json);
+    }
+
+    #[test]
+    fn applies_migrations_in_order() {
+        let tables = models(
+            "create table notes (id serial, body text, \"new\" boolean);
+            alter table notes add column if not exists author_id uuid, drop column body;
+            alter table notes rename column author_id to owner_id;
+            alter table notes add primary key (id);
+            create table drafts (id int);
+            drop table if exists drafts;",
+        );
+        assert_eq!(tables.len(), 1);
+        let columns: Vec<&str> = tables[0].fields.iter().map(|f| f.column.as_str()).collect();
+        assert_eq!(columns, ["id", "new", "owner_id"]);
+        assert!(tables[0].fields[0].primary_key);
+        assert_eq!(tables[0].fields[1].dart_name(), "newValue");
+    }
+
+    #[test]
+    fn snake_cases_quoted_table_names() {
+        assert_eq!(feature_name("TodoItems"), "todo_items");
+        assert_eq!(feature_name("todo items"), "todo_items");
+    }
+}
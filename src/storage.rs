@@ -0,0 +1,662 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::dart;
+use crate::model::{FieldType, ModelSpec};
+use crate::platform::TargetPlatform;
+use crate::project::ProjectConfig;
+use crate::{camel_case, pascal_case};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalDatabase {
+    Drift,
+    Isar,
+    Hive,
+    Sqflite,
+}
+
+impl fmt::Display for LocalDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LocalDatabase::Drift => write!(f, "drift"),
+            LocalDatabase::Isar => write!(f, "isar"),
+            LocalDatabase::Hive => write!(f, "hive"),
+            LocalDatabase::Sqflite => write!(f, "sqflite"),
+        }
+    }
+}
+
+impl LocalDatabase {
+    pub const ALL: [LocalDatabase; 4] = [
+        LocalDatabase::Drift,
+        LocalDatabase::Isar,
+        LocalDatabase::Hive,
+        LocalDatabase::Sqflite,
+    ];
+
+    pub fn dependencies(self) -> &'static [&'static str] {
+        match self {
+            LocalDatabase::Drift => &["drift", "drift_flutter"],
+            LocalDatabase::Isar => &["isar", "isar_flutter_libs", "path_provider"],
+            LocalDatabase::Hive => &["hive", "hive_flutter"],
+            LocalDatabase::Sqflite => &["sqflite", "path"],
+        }
+    }
+
+    pub fn dev_dependencies(self) -> &'static [&'static str] {
+        match self {
+            LocalDatabase::Drift => &["drift_dev"],
+            LocalDatabase::Isar => &["isar_generator"],
+            LocalDatabase::Hive | LocalDatabase::Sqflite => &[],
+        }
+    }
+
+    /// Whether `local_database.dart` relies on build_runner output
+    pub fn uses_codegen(self) -> bool {
+        matches!(self, LocalDatabase::Drift | LocalDatabase::Isar)
+    }
+
+    /// Whether `main` opens the database before `runApp`
+    pub fn needs_init(self) -> bool {
+        matches!(self, LocalDatabase::Isar | LocalDatabase::Hive)
+    }
+
+    // sqflite has no web and desktop implementations, and isar_generator 3
+    // needs an analyzer older than riverpod_generator and the route generators
+    pub fn available(platforms: &[TargetPlatform], other_codegen: bool) -> Vec<LocalDatabase> {
+        let mobile = platforms.iter().any(|p| p.is_mobile());
+        LocalDatabase::ALL
+            .into_iter()
+            .filter(|db| mobile || *db != LocalDatabase::Sqflite)
+            .filter(|db| !other_codegen || *db != LocalDatabase::Isar)
+            .collect()
+    }
+}
+
+pub fn uses_codegen(config: &ProjectConfig) -> bool {
+    config.local_database.is_some_and(|db| db.uses_codegen())
+}
+
+// Class of the cached rows of a model, e.g. `TodosEntry`
+fn entry_class(model: &ModelSpec) -> String {
+    format!("{}Entry", pascal_case(&model.name))
+}
+
+fn sql_type(field_type: FieldType) -> &'static str {
+    match field_type {
+        FieldType::Int | FieldType::Bool => "INTEGER",
+        FieldType::Double => "REAL",
+        _ => "TEXT",
+    }
+}
+
+// Key of a model as the database stores it
+fn stored_key(db: LocalDatabase, model: &ModelSpec, value: &str) -> String {
+    match (db, model.key().field_type) {
+        (LocalDatabase::Sqflite, FieldType::DateTime) => format!("{}.toIso8601String()", value),
+        // Hive keys are ints or strings
+        (LocalDatabase::Hive, FieldType::Int | FieldType::String) => value.to_string(),
+        (LocalDatabase::Hive, _) => format!("{}.toString()", value),
+        _ => value.to_string(),
+    }
+}
+
+// Quoted so columns named like SQL keywords or with spaces still work
+fn sql_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+// `CREATE TABLE` statements of the sqflite cache, one per model
+pub fn sqflite_tables(config: &ProjectConfig) -> String {
+    config
+        .models
+        .iter()
+        .map(|model| {
+            let key = model.key();
+            let statement = format!(
+                "CREATE TABLE {} ({} {} PRIMARY KEY, data TEXT NOT NULL)",
+                sql_identifier(&model.name),
+                sql_identifier(&key.column),
+                sql_type(key.field_type)
+            );
+            format!(
+                "        await db.execute(\n          {},\n        );",
+                dart::string_literal(&statement)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn generate_drift_database_template(config: &ProjectConfig) -> String {
+    let tables = config
+        .models
+        .iter()
+        .map(|model| {
+            let key = model.key();
+            let (column, builder) = match key.field_type {
+                FieldType::Int => ("IntColumn", "integer"),
+                FieldType::Double => ("RealColumn", "real"),
+                FieldType::Bool => ("BoolColumn", "boolean"),
+                FieldType::DateTime => ("DateTimeColumn", "dateTime"),
+                FieldType::String | FieldType::Json => ("TextColumn", "text"),
+            };
+            format!(
+                r#"/// Cached rows of `{name}`
+@DataClassName('{entry}')
+class {pascal}Entries extends Table {{
+  {column} get {key} => {builder}()();
+  TextColumn get data => text()();
+
+  @override
+  Set<Column> get primaryKey => {{{key}}};
+}}
+"#,
+                name = model.name,
+                entry = entry_class(model),
+                pascal = pascal_case(&model.name),
+                key = key.dart_name(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let table_list = config
+        .models
+        .iter()
+        .map(|m| format!("{}Entries", pascal_case(&m.name)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        r#"import 'package:drift/drift.dart';
+import 'package:drift_flutter/drift_flutter.dart';
+
+part 'local_database.g.dart';
+
+{tables}
+@DriftDatabase(tables: [{table_list}])
+class LocalDatabase extends _$LocalDatabase {{
+  LocalDatabase._() : super(driftDatabase(name: '{name}'));
+
+  static final instance = LocalDatabase._();
+
+  @override
+  int get schemaVersion => 1;
+}}
+"#,
+        name = config.name,
+    )
+}
+
+fn generate_isar_database_template(config: &ProjectConfig) -> String {
+    let collections = config
+        .models
+        .iter()
+        .map(|model| {
+            format!(
+                r#"/// Cached rows of `{name}`
+@collection
+class {entry} {{
+  Id isarId = Isar.autoIncrement;
+
+  @Index(unique: true, replace: true)
+  late {key_type} key;
+
+  late String data;
+}}
+"#,
+                name = model.name,
+                entry = entry_class(model),
+                key_type = model.key().dart_type(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let schemas = config
+        .models
+        .iter()
+        .map(|m| format!("{}Schema", entry_class(m)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        r#"import 'package:isar/isar.dart';
+import 'package:path_provider/path_provider.dart';
+
+part 'local_database.g.dart';
+
+{collections}
+class LocalDatabase {{
+  LocalDatabase._();
+
+  static late final Isar instance;
+
+  /// Opens the database, call before `runApp`.
+  static Future<void> init() async {{
+    final directory = await getApplicationDocumentsDirectory();
+    instance = await Isar.open(
+      [{schemas}],
+      directory: directory.path,
+      name: '{name}',
+    );
+  }}
+}}
+"#,
+        name = config.name,
+    )
+}
+
+fn generate_hive_database_template(config: &ProjectConfig) -> String {
+    let boxes = config
+        .models
+        .iter()
+        .map(|m| format!("      Hive.openBox<String>('{}'),", m.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"import 'package:hive_flutter/hive_flutter.dart';
+
+/// Boxes of JSON encoded models, one per feature model.
+class LocalDatabase {{
+  LocalDatabase._();
+
+  /// Opens the boxes, call before `runApp`.
+  static Future<void> init() async {{
+    await Hive.initFlutter();
+    await Future.wait([
+{boxes}
+    ]);
+  }}
+}}
+"#
+    )
+}
+
+// Files below `lib/core`, sqflite extends the `DatabaseService` of the catalog
+pub fn local_database_files(config: &ProjectConfig) -> Vec<(&'static str, String)> {
+    let template = match config.local_database {
+        Some(LocalDatabase::Drift) => generate_drift_database_template(config),
+        Some(LocalDatabase::Isar) => generate_isar_database_template(config),
+        Some(LocalDatabase::Hive) => generate_hive_database_template(config),
+        Some(LocalDatabase::Sqflite) | None => return Vec::new(),
+    };
+    vec![("services/local_database.dart", template)]
+}
+
+// Local copy of a feature's models, stored as JSON next to their key
+pub fn generate_local_data_source_template(
+    config: &ProjectConfig,
+    db: LocalDatabase,
+    model: &ModelSpec,
+) -> String {
+    let project_name = &config.name;
+    let feature_name = &model.name;
+    let pascal = pascal_case(feature_name);
+    let class_name = model.class_name();
+    let key = model.key();
+    let key_type = key.dart_type();
+    let key_name = key.dart_name();
+    let key_column = dart::string_literal(&key.column);
+    let order_by = dart::string_literal(&sql_identifier(&key.column));
+    let where_key = dart::string_literal(&format!("{} = ?", sql_identifier(&key.column)));
+    let key_unwrap = if key.is_optional() { "!" } else { "" };
+    let item_key = stored_key(db, model, &format!("item.{}{}", key_name, key_unwrap));
+    let arg_key = stored_key(db, model, &key_name);
+    let entry = entry_class(model);
+
+    let (imports, body) = match db {
+        LocalDatabase::Drift => (
+            format!(
+                "import 'package:drift/drift.dart';\nimport 'package:{}/core/services/local_database.dart';",
+                project_name
+            ),
+            format!(
+                r#"  LocalDatabase get _db => LocalDatabase.instance;
+
+  Future<List<{class_name}>> getAll({{int offset = 0, int limit = 20}}) async {{
+    final query = _db.select(_db.{camel}Entries)
+      ..orderBy([(t) => OrderingTerm(expression: t.{key_name})])
+      ..limit(limit, offset: offset);
+    final rows = await query.get();
+    return rows.map((row) => _decode(row.data)).toList();
+  }}
+
+  Future<{class_name}?> getById({key_type} {key_name}) async {{
+    final query = _db.select(_db.{camel}Entries)
+      ..where((t) => t.{key_name}.equals({key_name}));
+    final row = await query.getSingleOrNull();
+    return row == null ? null : _decode(row.data);
+  }}
+
+  Future<void> putAll(List<{class_name}> items) {{
+    return _db.batch((batch) {{
+      batch.insertAllOnConflictUpdate(_db.{camel}Entries, [
+        for (final item in items)
+          {pascal}EntriesCompanion(
+            {key_name}: Value({item_key}),
+            data: Value(jsonEncode(item.toJson())),
+          ),
+      ]);
+    }});
+  }}
+
+  Future<void> delete({key_type} {key_name}) async {{
+    await (_db.delete(_db.{camel}Entries)
+          ..where((t) => t.{key_name}.equals({key_name})))
+        .go();
+  }}"#,
+                camel = camel_case(feature_name),
+            ),
+        ),
+        LocalDatabase::Isar => (
+            format!(
+                "import 'package:isar/isar.dart';\nimport 'package:{}/core/services/local_database.dart';",
+                project_name
+            ),
+            format!(
+                r#"  IsarCollection<{entry}> get _entries => LocalDatabase.instance.{entries}s;
+
+  Future<List<{class_name}>> getAll({{int offset = 0, int limit = 20}}) async {{
+    final entries = await _entries
+        .where()
+        .sortByKey()
+        .offset(offset)
+        .limit(limit)
+        .findAll();
+    return entries.map((entry) => _decode(entry.data)).toList();
+  }}
+
+  Future<{class_name}?> getById({key_type} {key_name}) async {{
+    final entry = await _entries.getByKey({key_name});
+    return entry == null ? null : _decode(entry.data);
+  }}
+
+  Future<void> putAll(List<{class_name}> items) {{
+    return LocalDatabase.instance.writeTxn(() async {{
+      await _entries.putAll([
+        for (final item in items)
+          {entry}()
+            ..key = {item_key}
+            ..data = jsonEncode(item.toJson()),
+      ]);
+    }});
+  }}
+
+  Future<void> delete({key_type} {key_name}) {{
+    return LocalDatabase.instance.writeTxn(() async {{
+      await _entries.deleteByKey({key_name});
+    }});
+  }}"#,
+                // Isar names collections after the class with an `s`
+                entries = camel_case(&entry),
+            ),
+        ),
+        LocalDatabase::Hive => (
+            "import 'package:hive_flutter/hive_flutter.dart';".to_string(),
+            format!(
+                r#"  Box<String> get _box => Hive.box<String>('{feature_name}');
+
+  Future<List<{class_name}>> getAll({{int offset = 0, int limit = 20}}) async {{
+    return _box.values.skip(offset).take(limit).map(_decode).toList();
+  }}
+
+  Future<{class_name}?> getById({key_type} {key_name}) async {{
+    final data = _box.get({arg_key});
+    return data == null ? null : _decode(data);
+  }}
+
+  Future<void> putAll(List<{class_name}> items) {{
+    return _box.putAll({{
+      for (final item in items) {item_key}: jsonEncode(item.toJson()),
+    }});
+  }}
+
+  Future<void> delete({key_type} {key_name}) => _box.delete({arg_key});"#
+            ),
+        ),
+        LocalDatabase::Sqflite => (
+            format!(
+                "import 'package:sqflite/sqflite.dart';\nimport 'package:{}/core/services/database_service.dart';",
+                project_name
+            ),
+            format!(
+                r#"  static const _table = '{feature_name}';
+
+  Future<Database> get _database => DatabaseService.instance.database;
+
+  Future<List<{class_name}>> getAll({{int offset = 0, int limit = 20}}) async {{
+    final rows = await (await _database).query(
+      _table,
+      orderBy: {order_by},
+      limit: limit,
+      offset: offset,
+    );
+    return rows.map((row) => _decode(row['data']! as String)).toList();
+  }}
+
+  Future<{class_name}?> getById({key_type} {key_name}) async {{
+    final rows = await (await _database).query(
+      _table,
+      where: {where_key},
+      whereArgs: [{arg_key}],
+      limit: 1,
+    );
+    return rows.isEmpty ? null : _decode(rows.first['data']! as String);
+  }}
+
+  Future<void> putAll(List<{class_name}> items) async {{
+    final batch = (await _database).batch();
+    for (final item in items) {{
+      batch.insert(
+        _table,
+        {{{key_column}: {item_key}, 'data': jsonEncode(item.toJson())}},
+        conflictAlgorithm: ConflictAlgorithm.replace,
+      );
+    }}
+    await batch.commit(noResult: true);
+  }}
+
+  Future<void> delete({key_type} {key_name}) async {{
+    await (await _database).delete(
+      _table,
+      where: {where_key},
+      whereArgs: [{arg_key}],
+    );
+  }}"#
+            ),
+        ),
+    };
+
+    format!(
+        r#"import 'dart:convert';
+
+{imports}
+import 'package:{project_name}/features/{feature_name}/domain/{feature_name}_model.dart';
+
+/// Copy of the `{feature_name}` rows kept on the device.
+class {pascal}LocalDataSource {{
+{body}
+
+  Future<void> put({class_name} item) => putAll([item]);
+
+  {class_name} _decode(String data) =>
+      {class_name}.fromJson(jsonDecode(data) as Map<String, dynamic>);
+}}
+"#
+    )
+}
+
+// Repository that reads through to Supabase and falls back to the local
+// copy when the remote cannot be reached
+pub fn generate_cached_repository_template(config: &ProjectConfig, model: &ModelSpec) -> String {
+    let project_name = &config.name;
+    let feature_name = &model.name;
+    let pascal = pascal_case(feature_name);
+    let class_name = model.class_name();
+    let key = model.key();
+    let key_type = key.dart_type();
+    let key_name = key.dart_name();
+
+    let fetch_by_id = if config.typed_errors {
+        format!(
+            r#"try {{
+      final item = await _remote.fetchById({key_name});
+      if (item == null) throw const NotFoundFailure();
+      await _local.put(item);
+      return item;
+    }} on NotFoundFailure {{
+      await _local.delete({key_name});
+      rethrow;
+    }} catch (_) {{
+      final cached = await _local.getById({key_name});
+      if (cached == null) rethrow;
+      return cached;
+    }}"#
+        )
+    } else {
+        format!(
+            r#"try {{
+      final item = await _remote.fetchById({key_name});
+      if (item != null) await _local.put(item);
+      return item;
+    }} catch (_) {{
+      final cached = await _local.getById({key_name});
+      if (cached == null) rethrow;
+      return cached;
+    }}"#
+        )
+    };
+    let methods = [
+        (
+            format!("List<{}>", class_name),
+            "fetchAll({int offset = 0, int limit = 20})".to_string(),
+            r#"try {
+      final items = await _remote.fetchAll(offset: offset, limit: limit);
+      await _local.putAll(items);
+      return items;
+    } catch (_) {
+      final cached = await _local.getAll(offset: offset, limit: limit);
+      if (cached.isEmpty) rethrow;
+      return cached;
+    }"#
+            .to_string(),
+        ),
+        (
+            if config.typed_errors {
+                class_name.clone()
+            } else {
+                format!("{}?", class_name)
+            },
+            format!("fetchById({} {})", key_type, key_name),
+            fetch_by_id,
+        ),
+        (
+            class_name.clone(),
+            format!("insert({} model)", class_name),
+            "final created = await _remote.insert(model);\n    await _local.put(created);\n    return created;".to_string(),
+        ),
+        (
+            class_name.clone(),
+            format!("update({} model)", class_name),
+            "final saved = await _remote.update(model);\n    await _local.put(saved);\n    return saved;".to_string(),
+        ),
+        (
+            "void".to_string(),
+            format!("delete({} {})", key_type, key_name),
+            format!(
+                "await _remote.delete({0});\n    await _local.delete({0});",
+                key_name
+            ),
+        ),
+    ];
+
+    // Typed failures wrap the same steps in `guard`
+    let methods = methods
+        .iter()
+        .map(|(returns, signature, body)| {
+            if config.typed_errors {
+                let body = body.replace("\n", "\n  ");
+                format!(
+                    "  Future<Result<{}>> {} {{\n    return guard(() async {{\n      {}\n    }});\n  }}",
+                    returns, signature, body
+                )
+            } else {
+                format!("  Future<{}> {} async {{\n    {}\n  }}", returns, signature, body)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let result_imports = if config.typed_errors {
+        format!(
+            "import 'package:{0}/core/errors/failure.dart';\nimport 'package:{0}/core/errors/result.dart';\n",
+            project_name
+        )
+    } else {
+        String::new()
+    };
+
+    format!(
+        r#"{result_imports}import 'package:{project_name}/features/{feature_name}/data/{feature_name}_local_data_source.dart';
+import 'package:{project_name}/features/{feature_name}/data/{feature_name}_remote_data_source.dart';
+import 'package:{project_name}/features/{feature_name}/domain/{feature_name}_model.dart';
+
+/// Reads through to Supabase and keeps a local copy, which is served while
+/// the device is offline.
+class {pascal}Repository {{
+  {pascal}Repository({{
+    {pascal}RemoteDataSource? remote,
+    {pascal}LocalDataSource? local,
+  }})  : _remote = remote ?? {pascal}RemoteDataSource(),
+        _local = local ?? {pascal}LocalDataSource();
+
+  final {pascal}RemoteDataSource _remote;
+  final {pascal}LocalDataSource _local;
+
+{methods}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::FieldSpec;
+
+    #[test]
+    fn sqflite_quotes_the_key_column() {
+        let model = ModelSpec {
+            name: "order".to_string(),
+            table: None,
+            fields: vec![FieldSpec {
+                column: "it's \"id\"".to_string(),
+                field_type: FieldType::Int,
+                is_list: false,
+                nullable: false,
+                primary_key: true,
+                has_default: true,
+            }],
+        };
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "name": "app",
+            "package": "com.example",
+            "use_riverpod": true,
+            "use_supabase": true,
+            "features": [{ "name": "order", "layers": [] }],
+            "models": [model],
+        }))
+        .unwrap();
+
+        assert!(sqflite_tables(&config).contains(
+            r#"'CREATE TABLE "order" ("it\'s ""id""" INTEGER PRIMARY KEY, data TEXT NOT NULL)',"#
+        ));
+        let source =
+            generate_local_data_source_template(&config, LocalDatabase::Sqflite, &config.models[0]);
+        assert!(source.contains(r#"orderBy: '"it\'s ""id"""',"#));
+        assert!(source.contains(r#"where: '"it\'s ""id""" = ?',"#));
+        assert!(source.contains(r#"{'it\'s "id"': item."#));
+    }
+}
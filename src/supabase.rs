@@ -0,0 +1,166 @@
+use crate::dart;
+use crate::model::ModelSpec;
+use crate::pascal_case;
+use crate::project::ProjectConfig;
+
+pub fn generate_repository_template(config: &ProjectConfig, model: &ModelSpec) -> String {
+    let class = format!("{}Repository", pascal_case(&model.name));
+    generate_table_template(config, model, &class, config.typed_errors)
+}
+
+// Remote half of a repository that also keeps a local copy
+pub fn generate_remote_data_source_template(config: &ProjectConfig, model: &ModelSpec) -> String {
+    let class = format!("{}RemoteDataSource", pascal_case(&model.name));
+    generate_table_template(config, model, &class, false)
+}
+
+fn generate_table_template(
+    config: &ProjectConfig,
+    model: &ModelSpec,
+    class: &str,
+    typed_errors: bool,
+) -> String {
+    let project_name = &config.name;
+    let feature_name = &model.name;
+    let class_name = model.class_name();
+    let table = dart::string_literal(model.table.as_deref().unwrap_or(feature_name));
+
+    let key = model.key();
+    let key_column = dart::string_literal(&key.column);
+    let key_type = key.dart_type();
+    let key_field = key.dart_name();
+    let key_unwrap = if key.is_optional() { "!" } else { "" };
+
+    if typed_errors {
+        return format!(
+            r#"import 'package:supabase_flutter/supabase_flutter.dart';
+import 'package:{project_name}/core/errors/failure.dart';
+import 'package:{project_name}/core/errors/result.dart';
+import 'package:{project_name}/features/{feature_name}/domain/{feature_name}_model.dart';
+
+class {class} {{
+  SupabaseQueryBuilder get _table => Supabase.instance.client.from({table});
+
+  Future<Result<List<{class_name}>>> fetchAll({{int offset = 0, int limit = 20}}) {{
+    return guard(() async {{
+      final rows = await _table
+          .select()
+          .order({key_column})
+          .range(offset, offset + limit - 1);
+      return rows.map({class_name}.fromJson).toList();
+    }});
+  }}
+
+  Future<Result<{class_name}>> fetchById({key_type} {key_field}) {{
+    return guard(() async {{
+      final row = await _table.select().eq({key_column}, {key_field}).maybeSingle();
+      if (row == null) throw const NotFoundFailure();
+      return {class_name}.fromJson(row);
+    }});
+  }}
+
+  Future<Result<{class_name}>> insert({class_name} model) {{
+    return guard(() async {{
+      final row = await _table.insert(model.toJson()).select().single();
+      return {class_name}.fromJson(row);
+    }});
+  }}
+
+  Future<Result<{class_name}>> update({class_name} model) {{
+    return guard(() async {{
+      final row = await _table
+          .update(model.toJson())
+          .eq({key_column}, model.{key_field}{key_unwrap})
+          .select()
+          .single();
+      return {class_name}.fromJson(row);
+    }});
+  }}
+
+  Future<Result<void>> delete({key_type} {key_field}) {{
+    return guard(() async {{
+      await _table.delete().eq({key_column}, {key_field});
+    }});
+  }}
+}}
+"#,
+        );
+    }
+
+    format!(
+        r#"import 'package:supabase_flutter/supabase_flutter.dart';
+import 'package:{project_name}/features/{feature_name}/domain/{feature_name}_model.dart';
+
+class {class} {{
+  SupabaseQueryBuilder get _table => Supabase.instance.client.from({table});
+
+  Future<List<{class_name}>> fetchAll({{int offset = 0, int limit = 20}}) async {{
+    final rows = await _table
+        .select()
+        .order({key_column})
+        .range(offset, offset + limit - 1);
+    return rows.map({class_name}.fromJson).toList();
+  }}
+
+  Future<{class_name}?> fetchById({key_type} {key_field}) async {{
+    final row = await _table.select().eq({key_column}, {key_field}).maybeSingle();
+    return row == null ? null : {class_name}.fromJson(row);
+  }}
+
+  Future<{class_name}> insert({class_name} model) async {{
+    final row = await _table.insert(model.toJson()).select().single();
+    return {class_name}.fromJson(row);
+  }}
+
+  Future<{class_name}> update({class_name} model) async {{
+    final row = await _table
+        .update(model.toJson())
+        .eq({key_column}, model.{key_field}{key_unwrap})
+        .select()
+        .single();
+    return {class_name}.fromJson(row);
+  }}
+
+  Future<void> delete({key_type} {key_field}) async {{
+    await _table.delete().eq({key_column}, {key_field});
+  }}
+}}
+"#,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{FieldSpec, FieldType};
+
+    #[test]
+    fn table_and_column_names_are_escaped() {
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "name": "app",
+            "package": "com.example",
+            "use_riverpod": true,
+            "use_supabase": true,
+            "features": [{ "name": "todo", "layers": [] }],
+        }))
+        .unwrap();
+        let model = ModelSpec {
+            name: "todo".to_string(),
+            table: Some("todo's".to_string()),
+            fields: vec![FieldSpec {
+                column: "$id".to_string(),
+                field_type: FieldType::Int,
+                is_list: false,
+                nullable: false,
+                primary_key: true,
+                has_default: true,
+            }],
+        };
+        for typed_errors in [false, true] {
+            let template = generate_table_template(&config, &model, "TodoRepository", typed_errors);
+            assert!(template.contains(r"Supabase.instance.client.from('todo\'s')"));
+            assert!(template.contains(r".order('\$id')"));
+            assert!(template.contains(r".eq('\$id', id)"));
+        }
+    }
+}
@@ -0,0 +1,848 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::camel_case;
+use crate::dart;
+use crate::project::ProjectConfig;
+use crate::tokens;
+use crate::ui_kit::UiKit;
+
+pub const DEFAULT_SEED: &str = "#6750A4";
+
+// Roles of a Material 3 `ColorScheme`, tokens named after one override it
+const COLOR_ROLES: &[&str] = &[
+    "primary",
+    "onPrimary",
+    "primaryContainer",
+    "onPrimaryContainer",
+    "secondary",
+    "onSecondary",
+    "secondaryContainer",
+    "onSecondaryContainer",
+    "tertiary",
+    "onTertiary",
+    "tertiaryContainer",
+    "onTertiaryContainer",
+    "error",
+    "onError",
+    "errorContainer",
+    "onErrorContainer",
+    "surface",
+    "onSurface",
+    "surfaceContainerHighest",
+    "onSurfaceVariant",
+    "outline",
+    "outlineVariant",
+    "shadow",
+    "scrim",
+    "inverseSurface",
+    "onInverseSurface",
+    "inversePrimary",
+    "surfaceTint",
+];
+
+// Styles of a Material 3 `TextTheme`
+const TEXT_ROLES: &[&str] = &[
+    "displayLarge",
+    "displayMedium",
+    "displaySmall",
+    "headlineLarge",
+    "headlineMedium",
+    "headlineSmall",
+    "titleLarge",
+    "titleMedium",
+    "titleSmall",
+    "bodyLarge",
+    "bodyMedium",
+    "bodySmall",
+    "labelLarge",
+    "labelMedium",
+    "labelSmall",
+];
+
+/// Color of a token, either shared by both brightnesses or one per brightness
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ColorToken {
+    Single(String),
+    Modes { light: String, dark: String },
+}
+
+impl ColorToken {
+    fn light(&self) -> &str {
+        match self {
+            ColorToken::Single(hex) => hex,
+            ColorToken::Modes { light, .. } => light,
+        }
+    }
+
+    fn dark(&self) -> Option<&str> {
+        match self {
+            ColorToken::Single(_) => None,
+            ColorToken::Modes { dark, .. } => Some(dark),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextStyleToken {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_weight: Option<u16>,
+    /// Multiple of the font size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_height: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub letter_spacing: Option<f64>,
+}
+
+/// What the generated theme is built from, a seed color and design tokens
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DesignTokens {
+    /// Color the Material 3 color schemes are generated from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub colors: BTreeMap<String, ColorToken>,
+    /// Font family of every text style without its own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub typography: BTreeMap<String, TextStyleToken>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub spacing: BTreeMap<String, f64>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub radii: BTreeMap<String, f64>,
+}
+
+impl DesignTokens {
+    /// Tokens of a theme generated from `seed` alone, with a default scale
+    pub fn from_seed(seed: &str) -> Result<Self> {
+        let tokens = DesignTokens {
+            seed: Some(seed.to_string()),
+            spacing: [
+                ("xs", 4.0),
+                ("sm", 8.0),
+                ("md", 16.0),
+                ("lg", 24.0),
+                ("xl", 32.0),
+            ]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+            radii: [("sm", 4.0), ("md", 8.0), ("lg", 16.0)]
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+            ..Default::default()
+        };
+        tokens.validate()?;
+        Ok(tokens)
+    }
+
+    /// Tokens of a W3C or Style Dictionary file, or of the format they are
+    /// saved in, with the tokens the theme has no use for
+    pub fn read(path: &Path) -> Result<(Self, Vec<String>)> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse design tokens in {}", path.display()))?;
+        let (tokens, skipped) = if tokens::is_token_tree(&value) {
+            tokens::from_token_tree(&value)?
+        } else {
+            let tokens = serde_json::from_value(value)
+                .with_context(|| format!("Failed to parse design tokens in {}", path.display()))?;
+            (tokens, Vec::new())
+        };
+        tokens.validate()?;
+        Ok((tokens, skipped))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == DesignTokens::default()
+    }
+
+    fn validate(&self) -> Result<()> {
+        let seed = self.seed.as_deref().map(|seed| ("seed", seed));
+        let colors = self.colors.iter().flat_map(|(name, color)| {
+            std::iter::once((name.as_str(), color.light()))
+                .chain(color.dark().map(|dark| (name.as_str(), dark)))
+        });
+        for (name, hex) in seed.into_iter().chain(colors) {
+            if argb(hex).is_none() {
+                bail!(
+                    "Color `{}` of `{}` is not a hex color like #6750A4",
+                    hex,
+                    name
+                );
+            }
+        }
+
+        // Tokens whose names meet once converted to Dart would declare the
+        // same member twice
+        let mut colors = Members::new("AppColors", &["seed"]);
+        for (name, color) in &self.colors {
+            let id = identifier(name, "color");
+            if color.dark().is_some() {
+                colors.add(format!("{}Dark", id), name)?;
+            }
+            colors.add(id, name)?;
+        }
+        // Custom colors are also fields of the `AppTokens` theme extension
+        let mut extension = Members::new(
+            "AppTokens",
+            &[
+                "light",
+                "dark",
+                "copyWith",
+                "lerp",
+                "type",
+                "hashCode",
+                "runtimeType",
+                "toString",
+                "noSuchMethod",
+            ],
+        );
+        for name in self.colors.keys() {
+            let id = identifier(name, "color");
+            if !COLOR_ROLES.contains(&id.as_str()) {
+                extension.add(id, name)?;
+            }
+        }
+        let family: &[&str] = match self.font_family {
+            Some(_) => &["fontFamily"],
+            None => &[],
+        };
+        let mut typography = Members::new("AppTypography", family);
+        for name in self.typography.keys() {
+            typography.add(identifier(name, "text"), name)?;
+        }
+        let mut spacing = Members::new("AppSpacing", &[]);
+        for name in self.spacing.keys() {
+            spacing.add(identifier(name, "space"), name)?;
+        }
+        let mut radii = Members::new("AppRadii", &[]);
+        for name in self.radii.keys() {
+            radii.add(identifier(name, "radius"), name)?;
+        }
+        Ok(())
+    }
+
+    fn seed(&self) -> &str {
+        self.seed
+            .as_deref()
+            .or_else(|| self.colors.get("primary").map(ColorToken::light))
+            .unwrap_or(DEFAULT_SEED)
+    }
+
+    // Colors overriding a role of the color scheme, the rest go to `AppTokens`
+    fn role_colors(&self) -> impl Iterator<Item = (String, &ColorToken)> {
+        self.colors
+            .iter()
+            .map(|(name, color)| (identifier(name, "color"), color))
+            .filter(|(name, _)| COLOR_ROLES.contains(&name.as_str()))
+    }
+
+    fn custom_colors(&self) -> impl Iterator<Item = (String, &ColorToken)> {
+        self.colors
+            .iter()
+            .map(|(name, color)| (identifier(name, "color"), color))
+            .filter(|(name, _)| !COLOR_ROLES.contains(&name.as_str()))
+    }
+
+    fn has_custom_colors(&self) -> bool {
+        self.custom_colors().next().is_some()
+    }
+
+    // Radius of the shadcn components, `md` when the tokens have it
+    fn default_radius(&self) -> Option<String> {
+        self.radii
+            .keys()
+            .find(|name| *name == "md")
+            .or_else(|| self.radii.keys().next())
+            .map(|name| identifier(name, "radius"))
+    }
+}
+
+// Static members of a generated class and the tokens they come from
+struct Members<'a> {
+    class: &'static str,
+    names: BTreeMap<String, &'a str>,
+}
+
+impl<'a> Members<'a> {
+    fn new(class: &'static str, generated: &[&'a str]) -> Self {
+        Members {
+            class,
+            names: generated
+                .iter()
+                .map(|name| (name.to_string(), *name))
+                .collect(),
+        }
+    }
+
+    fn add(&mut self, member: String, token: &'a str) -> Result<()> {
+        if let Some(other) = self.names.get(&member) {
+            bail!(
+                "Tokens `{}` and `{}` would both be {}.{}, rename one of them",
+                other,
+                token,
+                self.class,
+                member
+            );
+        }
+        self.names.insert(member, token);
+        Ok(())
+    }
+}
+
+/// `#RRGGBB` or CSS's `#RRGGBBAA` as the ARGB value of a Dart `Color`
+pub fn argb(hex: &str) -> Option<u32> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    let value = u32::from_str_radix(digits, 16).ok()?;
+    match digits.len() {
+        6 => Some(0xFF00_0000 | value),
+        8 => Some(value.rotate_right(8)),
+        _ => None,
+    }
+}
+
+fn color(hex: &str) -> String {
+    format!("Color(0x{:08X})", argb(hex).unwrap_or(0xFF67_50A4))
+}
+
+fn number(value: f64) -> String {
+    value.to_string()
+}
+
+// Dart name of a token, e.g. `primary-container` to `primaryContainer` and
+// `2xl` to `space2xl` with the `space` prefix
+fn identifier(name: &str, prefix: &str) -> String {
+    let words: Vec<&str> = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let name = camel_case(&words.join("_"));
+    if name.is_empty()
+        || name.starts_with(|c: char| c.is_ascii_digit())
+        || dart::KEYWORDS.contains(&name.as_str())
+    {
+        camel_case(&format!("{}_{}", prefix, name))
+    } else {
+        name
+    }
+}
+
+fn generate_colors_template(tokens: &DesignTokens) -> String {
+    let mut colors = vec![format!("  static const seed = {};", color(tokens.seed()))];
+    for (name, token) in &tokens.colors {
+        let name = identifier(name, "color");
+        colors.push(format!(
+            "  static const {} = {};",
+            name,
+            color(token.light())
+        ));
+        if let Some(dark) = token.dark() {
+            colors.push(format!("  static const {}Dark = {};", name, color(dark)));
+        }
+    }
+
+    format!(
+        r#"import 'package:flutter/material.dart';
+
+/// Colors of the design tokens, [seed] generates the Material 3 color schemes.
+abstract final class AppColors {{
+{}
+}}
+"#,
+        colors.join("\n")
+    )
+}
+
+fn generate_typography_template(tokens: &DesignTokens) -> String {
+    let mut members = Vec::new();
+    if let Some(family) = &tokens.font_family {
+        members.push(format!(
+            "  static const fontFamily = {};",
+            dart::string_literal(family)
+        ));
+    }
+    for (name, style) in &tokens.typography {
+        let mut properties = Vec::new();
+        if let Some(family) = &style.font_family {
+            properties.push(format!("fontFamily: {}", dart::string_literal(family)));
+        }
+        if let Some(size) = style.font_size {
+            properties.push(format!("fontSize: {}", number(size)));
+        }
+        if let Some(weight) = style.font_weight {
+            properties.push(format!(
+                "fontWeight: FontWeight.w{}",
+                weight.clamp(100, 900) / 100 * 100
+            ));
+        }
+        if let Some(height) = style.line_height {
+            properties.push(format!("height: {}", number(height)));
+        }
+        if let Some(spacing) = style.letter_spacing {
+            properties.push(format!("letterSpacing: {}", number(spacing)));
+        }
+        let style = if properties.is_empty() {
+            "TextStyle()".to_string()
+        } else {
+            format!("TextStyle(\n    {},\n  )", properties.join(",\n    "))
+        };
+        members.push(format!(
+            "  static const {} = {};",
+            identifier(name, "text"),
+            style
+        ));
+    }
+    if members.is_empty() {
+        members.push(
+            "  // No text styles in the design tokens, Material 3 defaults apply".to_string(),
+        );
+    }
+    // Only a `TextStyle` needs Flutter
+    let import = if tokens.typography.is_empty() {
+        ""
+    } else {
+        "import 'package:flutter/material.dart';\n\n"
+    };
+
+    format!(
+        r#"{}/// Font and text styles of the design tokens.
+abstract final class AppTypography {{
+{}
+}}
+"#,
+        import,
+        members.join("\n\n")
+    )
+}
+
+fn generate_scale_template(
+    class: &str,
+    doc: &str,
+    prefix: &str,
+    scale: &BTreeMap<String, f64>,
+) -> String {
+    let mut values: Vec<(&String, &f64)> = scale.iter().collect();
+    values.sort_by(|a, b| a.1.total_cmp(b.1));
+    let members: Vec<String> = values
+        .into_iter()
+        .map(|(name, value)| {
+            format!(
+                "  static const double {} = {};",
+                identifier(name, prefix),
+                number(*value)
+            )
+        })
+        .collect();
+    let members = if members.is_empty() {
+        vec!["  // None in the design tokens".to_string()]
+    } else {
+        members
+    };
+
+    format!(
+        "/// {}\nabstract final class {} {{\n{}\n}}\n",
+        doc,
+        class,
+        members.join("\n")
+    )
+}
+
+// Custom colors as a `ThemeExtension`, read with `context.tokens`
+fn generate_tokens_template(config: &ProjectConfig) -> String {
+    let tokens = &config.theme;
+    let colors: Vec<(String, &ColorToken)> = tokens.custom_colors().collect();
+    let fields: Vec<String> = colors
+        .iter()
+        .map(|(name, _)| format!("  final Color {};", name))
+        .collect();
+    let instance = |dark: bool| {
+        colors
+            .iter()
+            .map(|(name, token)| {
+                let suffix = if dark && token.dark().is_some() {
+                    "Dark"
+                } else {
+                    ""
+                };
+                format!("    {0}: AppColors.{0}{1},", name, suffix)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    format!(
+        r#"import 'package:flutter/material.dart';
+import 'package:{project_name}/core/constants/app_colors.dart';
+
+/// Colors of the design tokens without a Material 3 role.
+class AppTokens extends ThemeExtension<AppTokens> {{
+  const AppTokens({{
+{required}
+  }});
+
+  static const light = AppTokens(
+{light}
+  );
+
+  static const dark = AppTokens(
+{dark}
+  );
+
+{fields}
+
+  @override
+  AppTokens copyWith({{
+{optional}
+  }}) {{
+    return AppTokens(
+{copied}
+    );
+  }}
+
+  @override
+  AppTokens lerp(AppTokens? other, double t) {{
+    if (other == null) return this;
+    return AppTokens(
+{lerped}
+    );
+  }}
+}}
+
+extension AppTokensContext on BuildContext {{
+  AppTokens get tokens => Theme.of(this).extension<AppTokens>()!;
+}}
+"#,
+        project_name = config.name,
+        required = colors
+            .iter()
+            .map(|(name, _)| format!("    required this.{},", name))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        light = instance(false),
+        dark = instance(true),
+        fields = fields.join("\n"),
+        optional = colors
+            .iter()
+            .map(|(name, _)| format!("    Color? {},", name))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        copied = colors
+            .iter()
+            .map(|(name, _)| format!("      {0}: {0} ?? this.{0},", name))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        lerped = colors
+            .iter()
+            .map(|(name, _)| format!("      {0}: Color.lerp({0}, other.{0}, t)!,", name))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+fn generate_theme_template(config: &ProjectConfig) -> String {
+    let project_name = &config.name;
+    let tokens = &config.theme;
+
+    let kit = config.ui_kit;
+
+    let mut imports = vec!["import 'package:flutter/material.dart';".to_string()];
+    match kit {
+        UiKit::Shadcn => imports.push("import 'package:shadcn_ui/shadcn_ui.dart';".to_string()),
+        UiKit::Material => {}
+        UiKit::Cupertino => imports.insert(
+            0,
+            "import 'package:flutter/cupertino.dart'\n    show CupertinoDynamicColor, CupertinoThemeData;"
+                .to_string(),
+        ),
+        UiKit::Fluent => imports.insert(
+            0,
+            "import 'package:fluent_ui/fluent_ui.dart' show AccentColor, FluentThemeData;"
+                .to_string(),
+        ),
+    }
+    imports.push(format!(
+        "import 'package:{project_name}/core/constants/app_colors.dart';"
+    ));
+
+    // Token colors named after a role replace the generated one
+    let overrides: Vec<String> = tokens
+        .role_colors()
+        .map(|(name, token)| match token.dark() {
+            Some(_) => format!("      {0}: dark ? AppColors.{0}Dark : AppColors.{0},", name),
+            None => format!("      {0}: AppColors.{0},", name),
+        })
+        .collect();
+    let uses_dark = overrides.iter().any(|line| line.contains("dark ?"));
+    let color_scheme = if overrides.is_empty() {
+        "    return ColorScheme.fromSeed(\n      seedColor: AppColors.seed,\n      brightness: brightness,\n    );".to_string()
+    } else {
+        format!(
+            "{}    return ColorScheme.fromSeed(\n      seedColor: AppColors.seed,\n      brightness: brightness,\n    ).copyWith(\n{}\n    );",
+            if uses_dark {
+                "    final dark = brightness == Brightness.dark;\n"
+            } else {
+                ""
+            },
+            overrides.join("\n")
+        )
+    };
+
+    let mut material = vec![
+        "      useMaterial3: true,".to_string(),
+        "      colorScheme: colorScheme,".to_string(),
+    ];
+    let mut shad = vec![
+        "      brightness: theme.brightness,".to_string(),
+        "      colorScheme: colorScheme.copyWith(\n        primary: scheme.primary,\n        primaryForeground: scheme.onPrimary,\n        secondary: scheme.secondaryContainer,\n        secondaryForeground: scheme.onSecondaryContainer,\n        destructive: scheme.error,\n        destructiveForeground: scheme.onError,\n        ring: scheme.primary,\n      ),".to_string(),
+    ];
+
+    let text_styles: Vec<String> = tokens
+        .typography
+        .keys()
+        .map(|name| identifier(name, "text"))
+        .filter(|name| TEXT_ROLES.contains(&name.as_str()))
+        .map(|name| format!("        {0}: AppTypography.{0},", name))
+        .collect();
+    if tokens.font_family.is_some() || !text_styles.is_empty() {
+        imports.push(format!(
+            "import 'package:{project_name}/core/constants/app_typography.dart';"
+        ));
+    }
+    let mut fluent = vec![
+        "      brightness: theme.brightness,".to_string(),
+        "      accentColor: AccentColor.swatch({'normal': theme.colorScheme.primary}),".to_string(),
+    ];
+    if tokens.font_family.is_some() {
+        material.push("      fontFamily: AppTypography.fontFamily,".to_string());
+        shad.push("      textTheme: ShadTextTheme(family: AppTypography.fontFamily),".to_string());
+        fluent.push("      fontFamily: AppTypography.fontFamily,".to_string());
+    }
+    if !text_styles.is_empty() {
+        material.push(format!(
+            "      textTheme: const TextTheme(\n{}\n      ),",
+            text_styles.join("\n")
+        ));
+    }
+    let radius = tokens.default_radius().filter(|_| kit == UiKit::Shadcn);
+    if let Some(radius) = radius {
+        imports.push(format!(
+            "import 'package:{project_name}/core/constants/app_radii.dart';"
+        ));
+        shad.push(format!(
+            "      radius: BorderRadius.circular(AppRadii.{}),",
+            radius
+        ));
+    }
+    if tokens.has_custom_colors() {
+        imports.push(format!(
+            "import 'package:{project_name}/theme/app_tokens.dart';"
+        ));
+        material.push(
+            "      extensions: [\n        if (brightness == Brightness.dark) AppTokens.dark else AppTokens.light,\n      ],"
+                .to_string(),
+        );
+    }
+
+    // Themes of the UI kit, derived from the Material ones
+    let (kit_fields, kit_builder) = match kit {
+        UiKit::Shadcn => (
+            "\n  static final shadLight = _shad(light);\n  static final shadDark = _shad(dark);\n"
+                .to_string(),
+            format!(
+                r#"
+
+  static ShadThemeData _shad(ThemeData theme) {{
+    final scheme = theme.colorScheme;
+    final ShadColorScheme colorScheme = switch (theme.brightness) {{
+      Brightness.light => const ShadSlateColorScheme.light(),
+      Brightness.dark => const ShadSlateColorScheme.dark(),
+    }};
+    return ShadThemeData(
+{}
+    );
+  }}"#,
+                shad.join("\n")
+            ),
+        ),
+        UiKit::Material => (String::new(), String::new()),
+        UiKit::Cupertino => (
+            String::new(),
+            r#"
+
+  static CupertinoThemeData cupertino(ThemeMode mode) {
+    return CupertinoThemeData(
+      brightness: switch (mode) {
+        ThemeMode.light => Brightness.light,
+        ThemeMode.dark => Brightness.dark,
+        ThemeMode.system => null,
+      },
+      primaryColor: CupertinoDynamicColor.withBrightness(
+        color: light.colorScheme.primary,
+        darkColor: dark.colorScheme.primary,
+      ),
+    );
+  }"#
+            .to_string(),
+        ),
+        UiKit::Fluent => (
+            "\n  static final fluentLight = _fluent(light);\n  static final fluentDark = _fluent(dark);\n"
+                .to_string(),
+            format!(
+                r#"
+
+  static FluentThemeData _fluent(ThemeData theme) {{
+    return FluentThemeData(
+{}
+    );
+  }}"#,
+                fluent.join("\n")
+            ),
+        ),
+    };
+    let audience = match kit {
+        UiKit::Material => "for Material widgets".to_string(),
+        _ => format!("for Material widgets and for {}", kit),
+    };
+
+    format!(
+        r#"{imports}
+
+/// Light and dark themes built from [AppColors.seed] and the design tokens,
+/// {audience}.
+abstract final class AppTheme {{
+  static final light = _material(Brightness.light);
+  static final dark = _material(Brightness.dark);
+{kit_fields}
+  static ColorScheme _colorScheme(Brightness brightness) {{
+{color_scheme}
+  }}
+
+  static ThemeData _material(Brightness brightness) {{
+    final colorScheme = _colorScheme(brightness);
+    return ThemeData(
+{material}
+    );
+  }}{kit_builder}
+}}
+"#,
+        imports = imports.join("\n"),
+        material = material.join("\n"),
+    )
+}
+
+// Files below `lib/core/constants`, generated from the design tokens
+pub fn constants_files(config: &ProjectConfig) -> Vec<(&'static str, String)> {
+    let tokens = &config.theme;
+    vec![
+        (
+            "constants/app_colors.dart",
+            generate_colors_template(tokens),
+        ),
+        (
+            "constants/app_typography.dart",
+            generate_typography_template(tokens),
+        ),
+        (
+            "constants/app_spacing.dart",
+            generate_scale_template(
+                "AppSpacing",
+                "Spacing of the design tokens, in logical pixels.",
+                "space",
+                &tokens.spacing,
+            ),
+        ),
+        (
+            "constants/app_radii.dart",
+            generate_scale_template(
+                "AppRadii",
+                "Corner radii of the design tokens, in logical pixels.",
+                "radius",
+                &tokens.radii,
+            ),
+        ),
+    ]
+}
+
+// Files below `lib/theme`
+pub fn theme_files(config: &ProjectConfig) -> Vec<(&'static str, String)> {
+    let mut files = vec![("app_theme.dart", generate_theme_template(config))];
+    if config.theme.has_custom_colors() {
+        files.push(("app_tokens.dart", generate_tokens_template(config)));
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn colors(names: &[&str]) -> DesignTokens {
+        DesignTokens {
+            colors: names
+                .iter()
+                .map(|name| (name.to_string(), ColorToken::Single("#6750A4".to_string())))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn converts_token_names_to_dart() {
+        assert_eq!(identifier("primary-container", "color"), "primaryContainer");
+        assert_eq!(identifier("2xl", "space"), "space2xl");
+        assert_eq!(identifier("new", "color"), "colorNew");
+        assert_eq!(argb("#6750A4"), Some(0xFF67_50A4));
+        assert_eq!(argb("#6750A480"), Some(0x8067_50A4));
+        assert_eq!(argb("#675"), None);
+    }
+
+    #[test]
+    fn rejects_tokens_with_the_same_dart_name() {
+        assert!(colors(&["primary", "brand"]).validate().is_ok());
+        assert!(colors(&["primary-container", "primaryContainer"])
+            .validate()
+            .is_err());
+        assert!(colors(&["seed-"]).validate().is_err());
+        // A custom color named after a member of `AppTokens`
+        assert!(colors(&["lerp"]).validate().is_err());
+
+        let mut tokens = colors(&["brand-dark"]);
+        tokens.colors.insert(
+            "brand".to_string(),
+            ColorToken::Modes {
+                light: "#FFFFFF".to_string(),
+                dark: "#000000".to_string(),
+            },
+        );
+        assert!(tokens.validate().is_err());
+
+        let mut tokens = DesignTokens::default();
+        tokens.spacing.insert("2xl".to_string(), 48.0);
+        tokens.spacing.insert("space-2xl".to_string(), 48.0);
+        assert!(tokens.validate().is_err());
+    }
+
+    #[test]
+    fn escapes_font_families() {
+        let tokens = DesignTokens {
+            font_family: Some("O'Reilly $ans".to_string()),
+            ..Default::default()
+        };
+        assert!(generate_typography_template(&tokens)
+            .contains(r"static const fontFamily = 'O\'Reilly \$ans';"));
+    }
+}
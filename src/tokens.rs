@@ -0,0 +1,537 @@
+use anyhow::{bail, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::project::ProjectConfig;
+use crate::theme::{self, ColorToken, DesignTokens, TextStyleToken};
+
+// References such as `{color.base.blue}` are followed this deep
+const MAX_ALIAS_DEPTH: usize = 10;
+
+// Root font size `rem` and `em` dimensions are relative to
+const ROOT_FONT_SIZE: f64 = 16.0;
+
+// Groups the tokens of a category are usually kept in
+const COLOR_GROUPS: &[&str] = &["color", "colors", "colour", "colours"];
+const TEXT_GROUPS: &[&str] = &[
+    "typography",
+    "textstyles",
+    "textstyle",
+    "text",
+    "font",
+    "fonts",
+];
+const FONT_FAMILY_GROUPS: &[&str] = &["fontfamily", "fontfamilies"];
+const RADIUS_GROUPS: &[&str] = &[
+    "radius",
+    "radii",
+    "borderradius",
+    "rounded",
+    "corner",
+    "corners",
+];
+const SPACING_GROUPS: &[&str] = &["spacing", "space", "spacer", "spacers", "gap"];
+
+/// Token of a W3C or Style Dictionary file, with the path of its groups
+struct Token {
+    path: Vec<String>,
+    kind: Option<String>,
+    value: Value,
+}
+
+enum Mode {
+    Light,
+    Dark,
+}
+
+/// Whether `value` is a W3C or Style Dictionary token tree rather than the
+/// flat format of `flutter_gen.json`
+pub fn is_token_tree(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => {
+            object.contains_key("$value")
+                || object
+                    .get("value")
+                    .is_some_and(|value| !is_token_tree(value))
+                || object.values().any(is_token_tree)
+        }
+        _ => false,
+    }
+}
+
+/// Design tokens of a W3C (`$value`, `$type`) or Style Dictionary (`value`,
+/// `type`) file. Tokens the theme has no use for are returned by path.
+pub fn from_token_tree(tree: &Value) -> Result<(DesignTokens, Vec<String>)> {
+    let mut tokens = Vec::new();
+    collect(tree, &mut Vec::new(), None, &mut tokens);
+    let by_path: BTreeMap<String, &Value> = tokens
+        .iter()
+        .map(|token| (token.path.join("."), &token.value))
+        .collect();
+
+    let mut design = DesignTokens::default();
+    let mut colors: BTreeMap<String, [Option<String>; 3]> = BTreeMap::new();
+    let mut skipped = Vec::new();
+
+    for token in &tokens {
+        let value = resolve(&token.value, &by_path, 0)?;
+        let (path, mode) = without_mode(&token.path);
+        let category = path.first().map(|segment| normalize(segment));
+        let kind = token.kind.as_deref().map(normalize);
+        let in_group = |groups: &[&str]| {
+            category
+                .as_deref()
+                .is_some_and(|category| groups.contains(&category))
+        };
+        let is = |kinds: &[&str], groups: &[&str]| {
+            kind.as_deref().is_some_and(|kind| kinds.contains(&kind)) || in_group(groups)
+        };
+        // Name of the token below its category group, e.g. `color.brand.primary`
+        // is `brand-primary`
+        let name = |groups: &[&str]| match path.as_slice() {
+            [_, rest @ ..] if !rest.is_empty() && in_group(groups) => rest.join("-"),
+            _ => path.join("-"),
+        };
+
+        if is(&["color"], COLOR_GROUPS) {
+            let name = name(COLOR_GROUPS);
+            let Some(hex) = color(&value) else {
+                skipped.push(token.path.join("."));
+                continue;
+            };
+            if name == "seed" {
+                design.seed = Some(hex);
+                continue;
+            }
+            let slot = match mode {
+                Some(Mode::Light) => 0,
+                Some(Mode::Dark) => 1,
+                None => 2,
+            };
+            colors.entry(name).or_default()[slot] = Some(hex);
+        } else if is(&["typography"], TEXT_GROUPS) && value.is_object() {
+            design
+                .typography
+                .insert(name(TEXT_GROUPS), text_style(&value));
+        } else if is(&["fontfamily", "fontfamilies"], FONT_FAMILY_GROUPS)
+            || path.iter().any(|segment| normalize(segment) == "family")
+        {
+            if design.font_family.is_none() {
+                design.font_family = font_family(&value);
+            }
+        } else if is(&["borderradius", "radius", "radii"], RADIUS_GROUPS) {
+            match dimension(&value) {
+                Some(radius) => {
+                    design.radii.insert(name(RADIUS_GROUPS), radius);
+                }
+                None => skipped.push(token.path.join(".")),
+            }
+        } else if is(&["spacing"], SPACING_GROUPS) {
+            match dimension(&value) {
+                Some(spacing) => {
+                    design.spacing.insert(name(SPACING_GROUPS), spacing);
+                }
+                None => skipped.push(token.path.join(".")),
+            }
+        } else {
+            skipped.push(token.path.join("."));
+        }
+    }
+
+    // A mode group only sets its own brightness, tokens outside of one both
+    for (name, [light, dark, both]) in colors {
+        let token = match (light.or(both.clone()), dark) {
+            (Some(light), Some(dark)) => ColorToken::Modes { light, dark },
+            (Some(color), None) | (None, Some(color)) => ColorToken::Single(color),
+            (None, None) => continue,
+        };
+        design.colors.insert(name, token);
+    }
+
+    Ok((design, skipped))
+}
+
+fn collect(value: &Value, path: &mut Vec<String>, kind: Option<&str>, tokens: &mut Vec<Token>) {
+    let Value::Object(object) = value else {
+        return;
+    };
+    let (value_key, type_key) = if object.contains_key("$value") {
+        ("$value", "$type")
+    } else {
+        ("value", "type")
+    };
+    // Groups pass their type on to the tokens below them
+    let kind = object
+        .get(type_key)
+        .or_else(|| object.get("$type"))
+        .and_then(Value::as_str)
+        .or(kind);
+
+    if let Some(token) = object.get(value_key) {
+        // A Style Dictionary `value` may be a composite such as a text style
+        if value_key == "$value" || !is_token_tree(token) {
+            tokens.push(Token {
+                path: path.clone(),
+                kind: kind.map(str::to_string),
+                value: token.clone(),
+            });
+            return;
+        }
+    }
+    for (key, child) in object {
+        if key.starts_with('$') {
+            continue;
+        }
+        path.push(key.clone());
+        collect(child, path, kind, tokens);
+        path.pop();
+    }
+}
+
+// Follows `{group.token}` references to the value they point at
+fn resolve(value: &Value, tokens: &BTreeMap<String, &Value>, depth: usize) -> Result<Value> {
+    match value {
+        Value::String(text) if text.starts_with('{') && text.ends_with('}') => {
+            // Style Dictionary references may end in the `value` key
+            let reference = &text[1..text.len() - 1];
+            let reference = reference.strip_suffix(".value").unwrap_or(reference);
+            let Some(target) = tokens.get(reference) else {
+                bail!("Token reference {} points at no token", text);
+            };
+            if depth >= MAX_ALIAS_DEPTH {
+                bail!("Token reference {} is circular", text);
+            }
+            resolve(target, tokens, depth + 1)
+        }
+        Value::Object(object) => {
+            let mut resolved = Map::new();
+            for (key, value) in object {
+                resolved.insert(key.clone(), resolve(value, tokens, depth)?);
+            }
+            Ok(Value::Object(resolved))
+        }
+        _ => Ok(value.clone()),
+    }
+}
+
+// Path without a `light` or `dark` group, and the mode that group sets
+fn without_mode(path: &[String]) -> (Vec<String>, Option<Mode>) {
+    let mut mode = None;
+    let path = path
+        .iter()
+        .filter(|segment| match normalize(segment).as_str() {
+            "light" => {
+                mode = Some(Mode::Light);
+                false
+            }
+            "dark" => {
+                mode = Some(Mode::Dark);
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect();
+    (path, mode)
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Color as `#RRGGBB` or `#RRGGBBAA`
+fn color(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => {
+            let text = text.trim();
+            if let Some(digits) = text.strip_prefix('#') {
+                // `#RGB` and `#RGBA` repeat each digit
+                let digits: String = if digits.len() == 3 || digits.len() == 4 {
+                    digits.chars().flat_map(|c| [c, c]).collect()
+                } else {
+                    digits.to_string()
+                };
+                let hex = format!("#{}", digits.to_ascii_uppercase());
+                return theme::argb(&hex).map(|_| hex);
+            }
+            let arguments = text
+                .strip_prefix("rgba(")
+                .or_else(|| text.strip_prefix("rgb("))?
+                .strip_suffix(')')?;
+            let parts: Vec<&str> = arguments
+                .split([',', ' ', '/'])
+                .filter(|part| !part.is_empty())
+                .collect();
+            let channel = |part: &str| {
+                part.parse::<f64>()
+                    .ok()
+                    .map(|c| c.round().clamp(0.0, 255.0) as u8)
+            };
+            let (r, g, b) = (
+                channel(parts.first()?)?,
+                channel(parts.get(1)?)?,
+                channel(parts.get(2)?)?,
+            );
+            match parts.get(3) {
+                Some(alpha) => {
+                    let alpha = match alpha.strip_suffix('%') {
+                        Some(percent) => percent.parse::<f64>().ok()? / 100.0,
+                        None => alpha.parse::<f64>().ok()?,
+                    };
+                    let alpha = (alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+                    Some(format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, alpha))
+                }
+                None => Some(format!("#{:02X}{:02X}{:02X}", r, g, b)),
+            }
+        }
+        // Colors of the 2025 W3C format carry a `hex` fallback
+        Value::Object(object) => {
+            let hex = color(object.get("hex")?)?;
+            match object.get("alpha").and_then(Value::as_f64) {
+                Some(alpha) if alpha < 1.0 && hex.len() == 7 => Some(format!(
+                    "{}{:02X}",
+                    hex,
+                    (alpha.clamp(0.0, 1.0) * 255.0).round() as u8
+                )),
+                _ => Some(hex),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Dimension in logical pixels, `rem` and `em` are relative to 16px
+fn dimension(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => {
+            let text = text.trim();
+            if let Some(rem) = text.strip_suffix("rem").or_else(|| text.strip_suffix("em")) {
+                return rem
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .map(|rem| rem * ROOT_FONT_SIZE);
+            }
+            text.strip_suffix("px").unwrap_or(text).trim().parse().ok()
+        }
+        // `{"value": 16, "unit": "px"}` of the 2025 W3C format
+        Value::Object(object) => {
+            let size = object.get("value")?.as_f64()?;
+            match object.get("unit").and_then(Value::as_str) {
+                Some("rem") | Some("em") => Some(size * ROOT_FONT_SIZE),
+                _ => Some(size),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn font_family(value: &Value) -> Option<String> {
+    match value {
+        Value::String(family) => family.split(',').next().map(|family| {
+            family
+                .trim()
+                .trim_matches(|c| c == '"' || c == '\'')
+                .to_string()
+        }),
+        Value::Array(families) => families.first().and_then(font_family),
+        _ => None,
+    }
+}
+
+fn font_weight(value: &Value) -> Option<u16> {
+    if let Some(weight) = value.as_f64() {
+        return Some(weight as u16);
+    }
+    let name = normalize(value.as_str()?);
+    if let Ok(weight) = name.parse() {
+        return Some(weight);
+    }
+    let weights = [
+        ("extralight", 200),
+        ("ultralight", 200),
+        ("semibold", 600),
+        ("demibold", 600),
+        ("extrabold", 800),
+        ("ultrabold", 800),
+        ("thin", 100),
+        ("light", 300),
+        ("regular", 400),
+        ("normal", 400),
+        ("book", 400),
+        ("medium", 500),
+        ("bold", 700),
+        ("black", 900),
+        ("heavy", 900),
+    ];
+    weights
+        .iter()
+        .find(|(weight, _)| name.contains(weight))
+        .map(|(_, weight)| *weight)
+}
+
+fn text_style(value: &Value) -> TextStyleToken {
+    let field = |key: &str| value.get(key);
+    let font_size = field("fontSize").and_then(dimension);
+
+    // Line heights come as a multiple, a percentage or pixels
+    let line_height = field("lineHeight").and_then(|height| match height {
+        Value::String(text) if text.trim().ends_with('%') => text
+            .trim()
+            .trim_end_matches('%')
+            .parse::<f64>()
+            .ok()
+            .map(|percent| percent / 100.0),
+        Value::String(text) if text.trim().ends_with("px") => Some(dimension(height)? / font_size?),
+        _ => {
+            let height = dimension(height)?;
+            match font_size {
+                Some(size) if height > 4.0 => Some(height / size),
+                _ => Some(height),
+            }
+        }
+    });
+
+    // Letter spacing relative to the font size is converted to pixels
+    let letter_spacing = field("letterSpacing").and_then(|spacing| match spacing {
+        Value::String(text) if text.trim().ends_with('%') => {
+            let percent = text.trim().trim_end_matches('%').parse::<f64>().ok()?;
+            Some(percent / 100.0 * font_size?)
+        }
+        Value::String(text) if text.trim().ends_with("em") && !text.trim().ends_with("rem") => {
+            let em = text.trim().trim_end_matches("em").parse::<f64>().ok()?;
+            Some(em * font_size?)
+        }
+        _ => dimension(spacing),
+    });
+
+    TextStyleToken {
+        font_family: field("fontFamily").and_then(font_family),
+        font_size,
+        font_weight: field("fontWeight").and_then(font_weight),
+        line_height: line_height.map(|height| (height * 1000.0).round() / 1000.0),
+        letter_spacing: letter_spacing.map(|spacing| (spacing * 1000.0).round() / 1000.0),
+    }
+}
+
+/// Replaces the design tokens of the project with those of `file` and
+/// regenerates the constants and theme files, nothing else is touched
+pub fn import_tokens(project_dir: &Path, file: &Path) -> Result<Vec<String>> {
+    let mut config = ProjectConfig::load(project_dir)?;
+    let (mut tokens, skipped) = DesignTokens::read(file)?;
+    // Without a seed or primary color of their own the tokens keep the old seed
+    if tokens.seed.is_none() && !tokens.colors.contains_key("primary") {
+        tokens.seed = config.theme.seed.clone();
+    }
+    config.theme = tokens;
+
+    let lib_path = project_dir.join("lib");
+    for (path, content) in theme::constants_files(&config) {
+        fs::write(lib_path.join("core").join(path), content)?;
+    }
+    let theme_path = lib_path.join("theme");
+    fs::create_dir_all(&theme_path)?;
+    let files = theme::theme_files(&config);
+    for (path, content) in &files {
+        fs::write(theme_path.join(path), content)?;
+    }
+    // The extension is only generated for colors without a Material role
+    let extension = theme_path.join("app_tokens.dart");
+    if extension.exists() && !files.iter().any(|(path, _)| *path == "app_tokens.dart") {
+        fs::remove_file(extension)?;
+    }
+
+    config.save(project_dir)?;
+    Ok(skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn recognizes_token_trees() {
+        assert!(is_token_tree(
+            &json!({"color": {"primary": {"$value": "#fff"}}})
+        ));
+        assert!(is_token_tree(
+            &json!({"color": {"primary": {"value": "#fff"}}})
+        ));
+        assert!(!is_token_tree(&json!({"seed": "#6750A4", "colors": {}})));
+    }
+
+    #[test]
+    fn imports_w3c_tokens() {
+        let tree = json!({
+            "color": {
+                "$type": "color",
+                "base": { "blue": { "$value": "#36c" } },
+                "primary": { "$value": "{color.base.blue}" },
+                "light": { "surface": { "$value": "rgb(255, 255, 255)" } },
+                "dark": { "surface": { "$value": "rgba(0, 0, 0, 50%)" } }
+            },
+            "spacing": { "md": { "$value": "1rem" } },
+            "radius": { "sm": { "$value": "4px" } },
+            "font": {
+                "family": { "$value": "'Inter', sans-serif" },
+                "body": {
+                    "$type": "typography",
+                    "$value": { "fontSize": "16px", "fontWeight": "semibold", "lineHeight": "150%" }
+                }
+            },
+            "shadow": { "card": { "$value": "0 1px 2px #000" } }
+        });
+        let (tokens, skipped) = from_token_tree(&tree).unwrap();
+
+        assert_eq!(
+            tokens.colors["primary"],
+            ColorToken::Single("#3366CC".to_string())
+        );
+        assert_eq!(
+            tokens.colors["surface"],
+            ColorToken::Modes {
+                light: "#FFFFFF".to_string(),
+                dark: "#00000080".to_string(),
+            }
+        );
+        assert_eq!(tokens.spacing["md"], 16.0);
+        assert_eq!(tokens.radii["sm"], 4.0);
+        assert_eq!(tokens.font_family.as_deref(), Some("Inter"));
+        let body = &tokens.typography["body"];
+        assert_eq!(body.font_size, Some(16.0));
+        assert_eq!(body.font_weight, Some(600));
+        assert_eq!(body.line_height, Some(1.5));
+        assert_eq!(skipped, ["shadow.card"]);
+    }
+
+    #[test]
+    fn imports_style_dictionary_tokens() {
+        let tree = json!({
+            "color": { "brand": { "value": "#FF0000", "type": "color" } },
+            "size": { "spacing": { "lg": { "value": 24 } } }
+        });
+        let (tokens, _) = from_token_tree(&tree).unwrap();
+        assert_eq!(
+            tokens.colors["brand"],
+            ColorToken::Single("#FF0000".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_broken_references() {
+        let missing = json!({ "color": { "a": { "$value": "{color.b}" } } });
+        assert!(from_token_tree(&missing).is_err());
+        let circular = json!({ "color": {
+            "a": { "$value": "{color.b}" },
+            "b": { "$value": "{color.a}" }
+        } });
+        assert!(from_token_tree(&circular).is_err());
+    }
+}
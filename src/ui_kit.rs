@@ -0,0 +1,459 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Design system the generated app and its screens are built with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UiKit {
+    /// shadcn_ui components in a `ShadApp.router`
+    #[default]
+    Shadcn,
+    /// Material 3 widgets in a `MaterialApp.router`
+    Material,
+    /// iOS-style widgets in a `CupertinoApp.router`
+    Cupertino,
+    /// Windows-style widgets of fluent_ui in a `FluentApp.router`
+    Fluent,
+}
+
+impl fmt::Display for UiKit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            UiKit::Shadcn => "shadcn_ui",
+            UiKit::Material => "Material 3",
+            UiKit::Cupertino => "Cupertino",
+            UiKit::Fluent => "Fluent UI",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Shifts every line after the first, for widgets nested one level deeper
+pub fn indent(code: &str, spaces: usize) -> String {
+    code.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 || line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", " ".repeat(spaces), line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Icon of a page action, drawn from the kit's icon set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionIcon {
+    Add,
+    Edit,
+    Delete,
+    Refresh,
+}
+
+/// Button of a page's title bar. A `floating` action floats above the
+/// content in the kits with a floating action button.
+pub struct PageAction<'a> {
+    pub icon: ActionIcon,
+    /// English tooltip or label
+    pub label: &'a str,
+    /// Callback run on press, each line after the first is shifted into place
+    pub on_pressed: &'a str,
+    pub floating: bool,
+}
+
+// Shifts every line, for code placed at the start of a line
+fn indent_lines(code: &str, spaces: usize) -> String {
+    code.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", " ".repeat(spaces), line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl UiKit {
+    pub const ALL: [UiKit; 4] = [
+        UiKit::Shadcn,
+        UiKit::Material,
+        UiKit::Cupertino,
+        UiKit::Fluent,
+    ];
+
+    pub fn dependency(self) -> Option<&'static str> {
+        match self {
+            UiKit::Shadcn => Some("shadcn_ui"),
+            UiKit::Fluent => Some("fluent_ui"),
+            UiKit::Material | UiKit::Cupertino => None,
+        }
+    }
+
+    /// Import of the widget library the kit builds on
+    pub fn widgets_import(self) -> &'static str {
+        match self {
+            UiKit::Shadcn | UiKit::Material => "import 'package:flutter/material.dart';",
+            UiKit::Cupertino => "import 'package:flutter/cupertino.dart';",
+            UiKit::Fluent => "import 'package:fluent_ui/fluent_ui.dart';",
+        }
+    }
+
+    /// Line importing the kit's own components, if they are a separate package
+    pub fn components_import(self) -> &'static str {
+        match self {
+            UiKit::Shadcn => "\nimport 'package:shadcn_ui/shadcn_ui.dart';",
+            _ => "",
+        }
+    }
+
+    /// Page with a title bar, `body` is indented for the `Scaffold` layout
+    pub fn scaffold(self, title: &str, body: &str) -> String {
+        if matches!(self, UiKit::Shadcn | UiKit::Material) {
+            return format!(
+                "Scaffold(\n      appBar: AppBar(\n        title: {},\n      ),\n      body: {},\n    )",
+                title, body
+            );
+        }
+
+        // Unlike `AppBar`, these pages and bars have const constructors, so
+        // `const` moves to the outermost widget it applies to
+        let constant = |code: &str| code.strip_prefix("const ").map(str::to_string);
+        let (page, title, bar, body, wrapper) = match (constant(title), constant(body)) {
+            (Some(title), Some(body)) => ("const ", title, "", body, ""),
+            (Some(title), None) => ("", title, "const ", body.to_string(), ""),
+            (None, Some(body)) => ("", title.to_string(), "", body, "const "),
+            (None, None) => ("", title.to_string(), "", body.to_string(), ""),
+        };
+        match self {
+            UiKit::Cupertino => format!(
+                "{page}CupertinoPageScaffold(\n      navigationBar: {bar}CupertinoNavigationBar(\n        middle: {title},\n      ),\n      child: {wrapper}SafeArea(\n        child: {},\n      ),\n    )",
+                indent(&body, 2)
+            ),
+            _ => format!(
+                "{page}ScaffoldPage(\n      header: {bar}PageHeader(\n        title: {title},\n      ),\n      content: {wrapper}{body},\n    )"
+            ),
+        }
+    }
+
+    /// Page with a title bar holding `actions`, laid out like `scaffold`
+    pub fn page(self, title: &str, actions: &[PageAction], body: &str) -> String {
+        if actions.is_empty() {
+            return self.scaffold(title, body);
+        }
+
+        let floating = match self {
+            UiKit::Shadcn | UiKit::Material => actions.iter().position(|a| a.floating),
+            UiKit::Cupertino | UiKit::Fluent => None,
+        };
+        let bar_actions = actions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != floating)
+            .map(|(_, action)| action)
+            .collect::<Vec<_>>();
+        let icon = |action: &PageAction| self.icon(action.icon);
+
+        match self {
+            UiKit::Shadcn | UiKit::Material => {
+                let buttons = bar_actions
+                    .iter()
+                    .map(|action| match self {
+                        UiKit::Shadcn => format!(
+                            "          ShadIconButton.ghost(\n            icon: const Icon({}),\n            onPressed: {},\n          ),",
+                            icon(action),
+                            indent(action.on_pressed, 12)
+                        ),
+                        _ => format!(
+                            "          IconButton(\n            icon: const Icon({}),\n            tooltip: '{}',\n            onPressed: {},\n          ),",
+                            icon(action),
+                            action.label,
+                            indent(action.on_pressed, 12)
+                        ),
+                    })
+                    .collect::<Vec<_>>();
+                let bar = if buttons.is_empty() {
+                    String::new()
+                } else {
+                    format!("\n        actions: [\n{}\n        ],", buttons.join("\n"))
+                };
+                let fab = match floating.map(|i| &actions[i]) {
+                    Some(action) if self == UiKit::Shadcn => format!(
+                        "\n      floatingActionButton: ShadIconButton(\n        icon: const Icon({}),\n        onPressed: {},\n      ),",
+                        icon(action),
+                        indent(action.on_pressed, 8)
+                    ),
+                    Some(action) => format!(
+                        "\n      floatingActionButton: FloatingActionButton(\n        tooltip: '{}',\n        onPressed: {},\n        child: const Icon({}),\n      ),",
+                        action.label,
+                        indent(action.on_pressed, 8),
+                        icon(action)
+                    ),
+                    None => String::new(),
+                };
+                format!(
+                    "Scaffold(\n      appBar: AppBar(\n        title: {},{}\n      ),{}\n      body: {},\n    )",
+                    title, bar, fab, body
+                )
+            }
+            UiKit::Cupertino => {
+                let buttons = bar_actions
+                    .iter()
+                    .map(|action| {
+                        format!(
+                            "            CupertinoButton(\n              padding: EdgeInsets.zero,\n              onPressed: {},\n              child: const Icon({}),\n            ),",
+                            indent(action.on_pressed, 14),
+                            icon(action)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    "CupertinoPageScaffold(\n      navigationBar: CupertinoNavigationBar(\n        middle: {},\n        trailing: Row(\n          mainAxisSize: MainAxisSize.min,\n          children: [\n{}\n          ],\n        ),\n      ),\n      child: SafeArea(\n        child: {},\n      ),\n    )",
+                    title,
+                    buttons.join("\n"),
+                    indent(body, 2)
+                )
+            }
+            UiKit::Fluent => {
+                let buttons = bar_actions
+                    .iter()
+                    .map(|action| {
+                        format!(
+                            "            CommandBarButton(\n              icon: const Icon({}),\n              label: const Text('{}'),\n              onPressed: {},\n            ),",
+                            icon(action),
+                            action.label,
+                            indent(action.on_pressed, 14)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    "ScaffoldPage(\n      header: PageHeader(\n        title: {},\n        commandBar: CommandBar(\n          mainAxisAlignment: MainAxisAlignment.end,\n          primaryItems: [\n{}\n          ],\n        ),\n      ),\n      content: {},\n    )",
+                    title,
+                    buttons.join("\n"),
+                    body
+                )
+            }
+        }
+    }
+
+    fn icon(self, icon: ActionIcon) -> &'static str {
+        match (self, icon) {
+            (UiKit::Shadcn, ActionIcon::Add) => "LucideIcons.plus",
+            (UiKit::Shadcn, ActionIcon::Edit) => "LucideIcons.pencil",
+            (UiKit::Shadcn, ActionIcon::Delete) => "LucideIcons.trash",
+            (UiKit::Shadcn, ActionIcon::Refresh) => "LucideIcons.refreshCw",
+            (UiKit::Material, ActionIcon::Add) => "Icons.add",
+            (UiKit::Material, ActionIcon::Edit) => "Icons.edit",
+            (UiKit::Material, ActionIcon::Delete) => "Icons.delete",
+            (UiKit::Material, ActionIcon::Refresh) => "Icons.refresh",
+            (UiKit::Cupertino, ActionIcon::Add) => "CupertinoIcons.add",
+            (UiKit::Cupertino, ActionIcon::Edit) => "CupertinoIcons.pencil",
+            (UiKit::Cupertino, ActionIcon::Delete) => "CupertinoIcons.delete",
+            (UiKit::Cupertino, ActionIcon::Refresh) => "CupertinoIcons.refresh",
+            (UiKit::Fluent, ActionIcon::Add) => "FluentIcons.add",
+            (UiKit::Fluent, ActionIcon::Edit) => "FluentIcons.edit",
+            (UiKit::Fluent, ActionIcon::Delete) => "FluentIcons.delete",
+            (UiKit::Fluent, ActionIcon::Refresh) => "FluentIcons.refresh",
+        }
+    }
+
+    /// Row of a list, `title` and `subtitle` are widgets
+    pub fn list_tile(self, title: &str, subtitle: Option<&str>, on_tap: Option<&str>) -> String {
+        let (widget, tap) = match self {
+            UiKit::Shadcn | UiKit::Material => ("ListTile", "onTap"),
+            UiKit::Cupertino => ("CupertinoListTile", "onTap"),
+            UiKit::Fluent => ("ListTile", "onPressed"),
+        };
+        let mut lines = vec![format!("{}(", widget), format!("  title: {},", title)];
+        if let Some(subtitle) = subtitle {
+            lines.push(format!("  subtitle: {},", subtitle));
+        }
+        if let Some(on_tap) = on_tap {
+            lines.push(format!("  {}: {},", tap, indent(on_tap, 2)));
+        }
+        lines.push(")".to_string());
+        lines.join("\n")
+    }
+
+    /// Whether lists refresh with a pull gesture rather than a page action
+    pub fn pulls_to_refresh(self) -> bool {
+        self != UiKit::Fluent
+    }
+
+    /// Scroll view of `slivers` that `on_refresh` reloads when pulled down,
+    /// in the kits with the gesture. `slivers` are given without indentation.
+    pub fn refreshable_list(self, on_refresh: &str, controller: &str, slivers: &str) -> String {
+        match self {
+            UiKit::Shadcn | UiKit::Material => format!(
+                "RefreshIndicator(\n  onRefresh: {},\n  child: CustomScrollView(\n    controller: {},\n    physics: const AlwaysScrollableScrollPhysics(),\n    slivers: [\n{}\n    ],\n  ),\n)",
+                on_refresh,
+                controller,
+                indent_lines(slivers, 6)
+            ),
+            UiKit::Cupertino => format!(
+                "CustomScrollView(\n  controller: {},\n  physics: const AlwaysScrollableScrollPhysics(\n    parent: BouncingScrollPhysics(),\n  ),\n  slivers: [\n    CupertinoSliverRefreshControl(\n      onRefresh: {},\n    ),\n{}\n  ],\n)",
+                controller,
+                on_refresh,
+                indent_lines(slivers, 4)
+            ),
+            UiKit::Fluent => format!(
+                "CustomScrollView(\n  controller: {},\n  slivers: [\n{}\n  ],\n)",
+                controller,
+                indent_lines(slivers, 4)
+            ),
+        }
+    }
+
+    /// Dialog asking to confirm a destructive action, completing with `true`
+    /// once confirmed
+    pub fn confirm(self, question: &str, action: &str) -> String {
+        let (show, dialog, cancel, confirm) = match self {
+            UiKit::Shadcn => (
+                "showShadDialog<bool>",
+                "ShadDialog.alert",
+                "ShadButton.outline",
+                "ShadButton.destructive",
+            ),
+            UiKit::Material => (
+                "showDialog<bool>",
+                "AlertDialog",
+                "TextButton",
+                "TextButton",
+            ),
+            UiKit::Cupertino => (
+                "showCupertinoDialog<bool>",
+                "CupertinoAlertDialog",
+                "CupertinoDialogAction",
+                "CupertinoDialogAction",
+            ),
+            UiKit::Fluent => (
+                "showDialog<bool>",
+                "ContentDialog",
+                "Button",
+                "FilledButton",
+            ),
+        };
+        let destructive = if self == UiKit::Cupertino {
+            "\n        isDestructiveAction: true,"
+        } else {
+            ""
+        };
+        format!(
+            r#"{show}(
+  context: context,
+  builder: (context) => {dialog}(
+    title: const Text('{question}'),
+    actions: [
+      {cancel}(
+        onPressed: () => Navigator.of(context).pop(false),
+        child: const Text('Cancel'),
+      ),
+      {confirm}({destructive}
+        onPressed: () => Navigator.of(context).pop(true),
+        child: const Text('{action}'),
+      ),
+    ],
+  ),
+)"#
+        )
+    }
+
+    pub fn progress_indicator(self) -> &'static str {
+        match self {
+            UiKit::Shadcn | UiKit::Material => "CircularProgressIndicator()",
+            UiKit::Cupertino => "CupertinoActivityIndicator()",
+            UiKit::Fluent => "ProgressRing()",
+        }
+    }
+
+    /// Page shown while a screen waits for its data
+    pub fn loading_page(self) -> String {
+        let indicator = self.progress_indicator();
+        match self {
+            UiKit::Shadcn | UiKit::Material => {
+                format!("const Scaffold(body: Center(child: {}))", indicator)
+            }
+            UiKit::Cupertino => format!(
+                "const CupertinoPageScaffold(\n        child: Center(child: {}),\n      )",
+                indicator
+            ),
+            UiKit::Fluent => format!("const ScaffoldPage(content: Center(child: {}))", indicator),
+        }
+    }
+
+    pub fn button(self) -> &'static str {
+        match self {
+            UiKit::Shadcn => "ShadButton",
+            UiKit::Material | UiKit::Fluent => "FilledButton",
+            UiKit::Cupertino => "CupertinoButton.filled",
+        }
+    }
+
+    pub fn form(self) -> &'static str {
+        match self {
+            UiKit::Shadcn => "ShadForm",
+            _ => "Form",
+        }
+    }
+
+    pub fn form_state(self) -> &'static str {
+        match self {
+            UiKit::Shadcn => "ShadFormState",
+            _ => "FormState",
+        }
+    }
+
+    /// Call on the form state that validates the fields
+    pub fn validate(self) -> &'static str {
+        match self {
+            UiKit::Shadcn => "saveAndValidate()",
+            _ => "validate()",
+        }
+    }
+
+    /// Whether field validators receive a nullable value
+    pub fn nullable_validators(self) -> bool {
+        self != UiKit::Shadcn
+    }
+
+    /// Statement telling the user about an error, indented by `spaces`
+    pub fn show_error(self, message: &str, spaces: usize) -> String {
+        let code = match self {
+            UiKit::Shadcn => format!(
+                "ShadToaster.of(context).show(\n  ShadToast.destructive(description: Text({})),\n);",
+                message
+            ),
+            UiKit::Material => format!(
+                "ScaffoldMessenger.of(context).showSnackBar(\n  SnackBar(content: Text({})),\n);",
+                message
+            ),
+            UiKit::Cupertino => format!(
+                r#"showCupertinoDialog<void>(
+  context: context,
+  builder: (context) => CupertinoAlertDialog(
+    content: Text({}),
+    actions: [
+      CupertinoDialogAction(
+        onPressed: () => Navigator.pop(context),
+        child: const Text('OK'),
+      ),
+    ],
+  ),
+);"#,
+                message
+            ),
+            UiKit::Fluent => format!(
+                "displayInfoBar(\n  context,\n  builder: (context, close) => InfoBar(\n    title: Text({}),\n    severity: InfoBarSeverity.error,\n  ),\n);",
+                message
+            ),
+        };
+        code.lines()
+            .map(|line| format!("{}{}", " ".repeat(spaces), line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
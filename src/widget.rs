@@ -0,0 +1,416 @@
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use std::fs;
+use std::path::Path;
+
+use crate::dart;
+use crate::l10n;
+use crate::project::{ProjectConfig, RouteParam, Screen, ScreenKind};
+use crate::router::write_router;
+use crate::{camel_case, pascal_case};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WidgetKind {
+    Stateless,
+    Stateful,
+    Consumer,
+    HookConsumer,
+}
+
+impl From<WidgetKind> for ScreenKind {
+    fn from(kind: WidgetKind) -> Self {
+        match kind {
+            WidgetKind::Stateless => ScreenKind::Stateless,
+            WidgetKind::Stateful => ScreenKind::Stateful,
+            WidgetKind::Consumer => ScreenKind::Consumer,
+            WidgetKind::HookConsumer => ScreenKind::HookConsumer,
+        }
+    }
+}
+
+impl WidgetKind {
+    fn needs_riverpod(self) -> bool {
+        matches!(self, WidgetKind::Consumer | WidgetKind::HookConsumer)
+    }
+}
+
+pub fn generate_widget_template(
+    config: &ProjectConfig,
+    class_name: &str,
+    kind: WidgetKind,
+    params: &[RouteParam],
+    body: &str,
+) -> String {
+    let import = match kind {
+        WidgetKind::Stateless | WidgetKind::Stateful => "",
+        WidgetKind::Consumer => "\nimport 'package:flutter_riverpod/flutter_riverpod.dart';",
+        WidgetKind::HookConsumer => "\nimport 'package:hooks_riverpod/hooks_riverpod.dart';",
+    };
+
+    let constructor = if params.is_empty() {
+        format!("  const {}({{super.key}});", class_name)
+    } else {
+        let required = params
+            .iter()
+            .map(|p| {
+                if p.query {
+                    format!("this.{}", camel_case(&p.name))
+                } else {
+                    format!("required this.{}", camel_case(&p.name))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let fields = params
+            .iter()
+            .map(|p| {
+                format!(
+                    "  final {}{} {};",
+                    p.dart_type,
+                    if p.query { "?" } else { "" },
+                    camel_case(&p.name)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            "  const {}({{super.key, {}}});\n\n{}",
+            class_name, required, fields
+        )
+    };
+
+    let widgets = config.ui_kit.widgets_import();
+    // Bodies showing messages look them up from `AppLocalizations`
+    let (l10n_import, lookup) = if body.contains("l10n.") {
+        (l10n::import(config), l10n::lookup(config))
+    } else {
+        (String::new(), "")
+    };
+    let build = format!(
+        "  @override\n  Widget build(BuildContext context{}) {{{}\n    return {};\n  }}",
+        if kind.needs_riverpod() {
+            ", WidgetRef ref"
+        } else {
+            ""
+        },
+        lookup,
+        body
+    );
+
+    match kind {
+        WidgetKind::Stateful => format!(
+            r#"{widgets}{l10n_import}
+
+class {class_name} extends StatefulWidget {{
+{constructor}
+
+  @override
+  State<{class_name}> createState() => _{class_name}State();
+}}
+
+class _{class_name}State extends State<{class_name}> {{
+{build}
+}}
+"#
+        ),
+        _ => {
+            let base = match kind {
+                WidgetKind::Consumer => "ConsumerWidget",
+                WidgetKind::HookConsumer => "HookConsumerWidget",
+                _ => "StatelessWidget",
+            };
+            format!(
+                r#"{widgets}{import}{l10n_import}
+
+class {class_name} extends {base} {{
+{constructor}
+
+{build}
+}}
+"#
+            )
+        }
+    }
+}
+
+pub fn generate_screen_template(
+    config: &ProjectConfig,
+    feature_name: &str,
+    screen_name: &str,
+    kind: WidgetKind,
+    params: &[RouteParam],
+) -> String {
+    let pascal = pascal_case(screen_name);
+    let title = l10n::text(
+        config,
+        &l10n::key(feature_name, screen_name, "title"),
+        &pascal,
+    );
+    let message = l10n::text(
+        config,
+        &l10n::key(feature_name, screen_name, "body"),
+        &format!("{}Screen", pascal),
+    );
+    // A literal `Text` makes the whole `Center` const
+    let body = match message.strip_prefix("const ") {
+        Some(message) => format!("const Center(\n        child: {},\n      )", message),
+        None => format!("Center(\n        child: {},\n      )", message),
+    };
+    let body = config.ui_kit.scaffold(&title, &body);
+
+    generate_widget_template(config, &format!("{}Screen", pascal), kind, params, &body)
+}
+
+// Names become file names and, in PascalCase, Dart class names
+pub fn validate_name(what: &str, name: &str) -> Result<()> {
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        bail!(
+            "Invalid {} name `{}`, use lowercase letters, digits and underscores",
+            what,
+            name
+        );
+    }
+    Ok(())
+}
+
+pub fn add_screen(
+    project_dir: &Path,
+    feature_name: &str,
+    screen_name: &str,
+    kind: WidgetKind,
+    path: Option<&str>,
+    query: &[String],
+) -> Result<()> {
+    validate_name("screen", screen_name)?;
+    let mut config = ProjectConfig::load(project_dir)?;
+    check_target(&config, feature_name, kind)?;
+    if config.has_route(screen_name) {
+        bail!("A route named `{}` already exists", screen_name);
+    }
+
+    // Path parameters such as `:id` are passed to the screen as strings
+    let path = path
+        .map(str::to_string)
+        .unwrap_or_else(|| screen_name.replace('_', "-"));
+    validate_path(&path)?;
+    let mut params = path
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix(':'))
+        .map(|name| RouteParam {
+            name: name.to_string(),
+            dart_type: "String".to_string(),
+            query: false,
+        })
+        .collect::<Vec<_>>();
+    for spec in query {
+        params.push(parse_query_param(spec)?);
+    }
+    for (i, param) in params.iter().enumerate() {
+        validate_param(&param.name)?;
+        if params[..i].iter().any(|p| p.name == param.name) {
+            bail!("Duplicate route parameter `{}`", param.name);
+        }
+    }
+
+    // Screens of other commands, such as the CRUD list and form, are not
+    // routes of the same name
+    let presentation = project_dir
+        .join("lib")
+        .join("features")
+        .join(feature_name)
+        .join("presentation");
+    let file = presentation.join(format!("{}_screen.dart", screen_name));
+    if file.exists() {
+        bail!("A screen named `{}` already exists", screen_name);
+    }
+    fs::create_dir_all(&presentation)?;
+    fs::write(
+        file,
+        generate_screen_template(&config, feature_name, screen_name, kind, &params),
+    )?;
+
+    config
+        .feature_mut(feature_name)
+        .expect("feature checked above")
+        .screens
+        .push(Screen {
+            name: screen_name.to_string(),
+            widget: screen_name.to_string(),
+            path,
+            params,
+            children: Vec::new(),
+            kind: kind.into(),
+            fields: Vec::new(),
+            guards: Vec::new(),
+        });
+
+    write_router(project_dir, &config)?;
+    l10n::write_arb_files(project_dir, &config)?;
+    config.save(project_dir)?;
+
+    Ok(())
+}
+
+pub fn add_widget(
+    project_dir: &Path,
+    feature_name: &str,
+    widget_name: &str,
+    kind: WidgetKind,
+) -> Result<()> {
+    validate_name("widget", widget_name)?;
+    let config = ProjectConfig::load(project_dir)?;
+    check_target(&config, feature_name, kind)?;
+
+    let widgets = project_dir
+        .join("lib")
+        .join("features")
+        .join(feature_name)
+        .join("presentation")
+        .join("widgets");
+    let file = widgets.join(format!("{}.dart", widget_name));
+    if file.exists() {
+        bail!("A widget named `{}` already exists", widget_name);
+    }
+    fs::create_dir_all(&widgets)?;
+
+    let body = format!(
+        "const Placeholder(\n      // TODO: Build {}\n    )",
+        pascal_case(widget_name)
+    );
+    fs::write(
+        file,
+        generate_widget_template(&config, &pascal_case(widget_name), kind, &[], &body),
+    )?;
+
+    Ok(())
+}
+
+// `name[:type]`, where type is one of string, int, double or bool
+fn parse_query_param(spec: &str) -> Result<RouteParam> {
+    let (name, dart_type) = spec.split_once(':').unwrap_or((spec, "string"));
+    let dart_type = match dart_type {
+        "string" => "String",
+        "int" => "int",
+        "double" => "double",
+        "bool" => "bool",
+        other => bail!("Unknown query parameter type `{}`", other),
+    };
+    if name.is_empty() {
+        bail!("Missing query parameter name");
+    }
+    Ok(RouteParam {
+        name: name.to_string(),
+        dart_type: dart_type.to_string(),
+        query: true,
+    })
+}
+
+// Screens are child routes, so their paths are relative to the feature's
+fn validate_path(path: &str) -> Result<()> {
+    let valid = path.split('/').all(|segment| {
+        let name = segment.strip_prefix(':').unwrap_or(segment);
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    });
+    if !valid {
+        bail!(
+            "Invalid route path `{}`, use segments of letters, digits, `-` and `_` or `:param`, without a leading or trailing `/`",
+            path
+        );
+    }
+    Ok(())
+}
+
+// Parameters are passed to the screen's constructor in camelCase, next to
+// the widget's `key`
+fn validate_param(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && dart::is_identifier(&camel_case(name))
+        && camel_case(name) != "key";
+    if !valid {
+        bail!(
+            "Invalid route parameter `{}`, use letters, digits and underscores, not starting with a digit or naming a Dart reserved word",
+            name
+        );
+    }
+    Ok(())
+}
+
+fn check_target(config: &ProjectConfig, feature_name: &str, kind: WidgetKind) -> Result<()> {
+    if !config.features.iter().any(|f| f.name == feature_name) {
+        bail!("Unknown feature `{}`", feature_name);
+    }
+    if kind.needs_riverpod() && !config.use_riverpod {
+        bail!("Consumer widgets need Riverpod, but this project does not use Riverpod");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_names() {
+        assert!(validate_name("screen", "todo_list2").is_ok());
+        for name in ["", "Todo", "2nd", "todo-list", "_todo"] {
+            assert!(
+                validate_name("screen", name).is_err(),
+                "accepted `{}`",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn parses_query_params() {
+        let param = parse_query_param("page:int").unwrap();
+        assert_eq!(param.name, "page");
+        assert_eq!(param.dart_type, "int");
+        assert!(param.query);
+        assert_eq!(parse_query_param("q").unwrap().dart_type, "String");
+        assert!(parse_query_param("page:float").is_err());
+        assert!(parse_query_param(":int").is_err());
+    }
+
+    #[test]
+    fn validates_route_params() {
+        for name in ["id", "user_id", "userId"] {
+            assert!(validate_param(name).is_ok(), "rejected `{}`", name);
+        }
+        for name in ["", "1id", "class", "user-id", "key", "a.b"] {
+            assert!(validate_param(name).is_err(), "accepted `{}`", name);
+        }
+    }
+
+    #[test]
+    fn validates_route_paths() {
+        for path in [
+            "details",
+            "todo-details/:id",
+            ":id/edit",
+            "users/:user_id/posts_2",
+        ] {
+            assert!(validate_path(path).is_ok(), "rejected `{}`", path);
+        }
+        for path in [
+            "",
+            "/details",
+            "details/",
+            "todos//:id",
+            "details?tab=1",
+            "my details",
+            "details#top",
+            ":",
+        ] {
+            assert!(validate_path(path).is_err(), "accepted `{}`", path);
+        }
+    }
+}